@@ -0,0 +1,61 @@
+//! `cargo bench --features bench` times drawing, saving and searching the
+//! data base `forget gen` makes, with criterion's statistics to compare runs
+//! against. `forget bench` is the quick check of the same work against budgets.
+
+use std::fs;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use tui::backend::TestBackend;
+use tui::Terminal;
+
+use forget::app::{App, ListState, Remind};
+use forget::bench::{self, SEARCH};
+use forget::config::{self, CFG};
+use forget::query::Query;
+use forget::ux;
+
+/// The size `forget gen` makes by default.
+fn notes() -> ListState<Remind> {
+    bench::generate(100, 10_000)
+}
+
+fn render(c: &mut Criterion) {
+    // the default config so timings don't depend on whose machine it is
+    let config = CFG.with(|cfg| cfg.clone());
+    let mut app = App::with_notes(notes(), config).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(200, 60)).unwrap();
+    c.bench_function("render", |b| {
+        b.iter(|| {
+            ux::draw(&mut terminal, &mut app).unwrap();
+            app.on_right();
+        })
+    });
+}
+
+fn save(c: &mut Criterion) {
+    let notes = notes();
+    let path = std::env::temp_dir().join(format!("forget_bench_{}.json", std::process::id()));
+    c.bench_function("save", |b| {
+        b.iter(|| {
+            let json = serde_json::to_string(&config::sealed(&notes).unwrap()).unwrap();
+            fs::write(&path, json).unwrap();
+        })
+    });
+    let _ = fs::remove_file(&path);
+}
+
+fn search(c: &mut Criterion) {
+    let notes = notes();
+    let query = &Query::parse(SEARCH);
+    c.bench_function("search", |b| {
+        b.iter(|| {
+            notes
+                .iter()
+                .flat_map(|n| n.list.iter().filter(move |t| query.matches(n, t)))
+                .count()
+        })
+    });
+}
+
+criterion_group!(benches, render, save, search);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::Status;
+use crate::config::AppKey;
+
+/// Something the app can do, macros are a list of these.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum Action {
+    /// Select the todo above.
+    Up,
+    /// Select the todo below.
+    Down,
+    /// Go to the previous sticky note.
+    PreviousNote,
+    /// Go to the next sticky note.
+    NextNote,
+    /// Check off the selected todo, or uncheck it if it was done.
+    MarkDone,
+    /// Delete the selected todo.
+    RemoveTodo,
+    /// Stop every command and quit.
+    Quit,
+    /// Move the selected todo on to its next status.
+    NextStatus,
+    /// Give the selected todo a status.
+    SetStatus(Status),
+    /// Save the sticky notes.
+    Save,
+    /// Run the selected todo's command, what enter does in the list.
+    RunSelected,
+    /// Run the commands of every unfinished todo.
+    RunAll,
+    /// Bring the unfinished todos of the note made before from the same
+    /// recurring template over to the current one.
+    CarryOver,
+    /// Upload the current sticky note to `publish` and copy the link.
+    Publish,
+    /// Pick the symbol in front of the selected todo from a list.
+    PickHighlight,
+    /// Pick an icon shown in front of the current sticky note's title.
+    PickIcon,
+    /// Change the colors with a picker that shows them right away.
+    PickColors,
+    /// Switch to one of the themes in `~/.forget/themes`.
+    PickTheme,
+    /// Turn `dry_run` on or off until `forget` quits.
+    ToggleDryRun,
+    /// Make the current sticky note a goal or a normal note again.
+    ToggleGoal,
+    /// Make the selected todo count for more toward the goal.
+    WeightUp,
+    /// Make the selected todo count for less toward the goal.
+    WeightDown,
+    /// Make the current sticky note a habit tracker or a normal note again.
+    ToggleHabit,
+    /// Make the selected todo count down the days to its due time, or a todo again.
+    ToggleCountdown,
+    /// Show the todos due this week grouped by day.
+    Agenda,
+    /// Show the current sticky note full screen to share in a meeting.
+    Present,
+    /// Go back to the sticky note viewed before the current one.
+    LastTab,
+    /// Put back the last todo or sticky note deleted this session.
+    Undelete,
+    /// Leave done and cancelled todos out of the current sticky note's list.
+    ToggleHideDone,
+    /// Start every addition to the current sticky note's notes with the time.
+    ToggleJournal,
+    /// Attach a file to the current sticky note by its path.
+    Attach,
+    /// Open one of the current sticky note's attachments with `attachment_opener`.
+    OpenAttachment,
+    /// Take an attachment off the current sticky note, the file stays.
+    RemoveAttachment,
+    /// Show every key and what it does until the next key.
+    Help,
+    /// Press a key, this is what the recorder saves.
+    Key(AppKey),
+}
+
+/// A named list of actions run one after the other when `key` is pressed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Macro {
+    pub name: String,
+    /// Only works while no form is open, a recorded macro has none.
+    #[serde(default)]
+    pub key: Option<AppKey>,
+    pub actions: Vec<Action>,
+}
+
+/// The name the recorder saves its macro under.
+pub const RECORDED: &str = "recorded";
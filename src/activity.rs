@@ -0,0 +1,123 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use chrono::{offset::TimeZone, DateTime, Local};
+
+use crate::config;
+
+const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// Only the most recent entries are kept in memory, the file keeps everything.
+const MAX_ENTRIES: usize = 1000;
+
+/// A single state changing action, one line of `activity.log`.
+#[derive(Clone, Debug)]
+pub struct Activity {
+    pub date: DateTime<Local>,
+    pub action: String,
+    pub detail: String,
+}
+
+impl Activity {
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}",
+            self.date.format(FORMAT),
+            self.action,
+            self.detail.replace(['\t', '\n'], " ")
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Activity> {
+        let mut parts = line.splitn(3, '\t');
+        let date = Local.datetime_from_str(parts.next()?, FORMAT).ok()?;
+        Some(Activity {
+            date,
+            action: parts.next()?.to_string(),
+            detail: parts.next().unwrap_or_default().to_string(),
+        })
+    }
+
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        self.to_line().to_lowercase().contains(&filter)
+    }
+}
+
+/// Every state changing action is appended to `activity.log` next to the data base.
+#[derive(Debug, Default)]
+pub struct ActivityLog {
+    pub entries: Vec<Activity>,
+    /// Only entries containing this are shown in the viewer.
+    pub filter: String,
+    pub selected: usize,
+    path: Option<PathBuf>,
+}
+
+impl ActivityLog {
+    pub fn open() -> io::Result<Self> {
+        let path = config::db_path().with_file_name("activity.log");
+
+        let entries = match fs::read_to_string(&path) {
+            Ok(text) => {
+                let mut entries = text
+                    .lines()
+                    .filter_map(Activity::from_line)
+                    .collect::<Vec<_>>();
+                let skip = entries.len().saturating_sub(MAX_ENTRIES);
+                entries.drain(..skip);
+                entries
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            entries,
+            filter: String::new(),
+            selected: 0,
+            path: Some(path),
+        })
+    }
+
+    /// Records `action`, failing to write the log never stops the action itself.
+    pub fn record<S: Into<String>>(&mut self, action: &str, detail: S) {
+        let activity = Activity {
+            date: Local::now(),
+            action: action.to_string(),
+            detail: detail.into(),
+        };
+
+        if let Some(path) = &self.path {
+            let _ = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut fd| writeln!(fd, "{}", activity.to_line()));
+        }
+
+        self.entries.push(activity);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// The entries matching `filter`, newest first.
+    pub fn filtered(&self) -> Vec<&Activity> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|a| a.matches(&self.filter))
+            .collect()
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.filtered().len() {
+            self.selected += 1;
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! The agenda, a week of todos grouped by the day they are due. Weeks start
+//! on `week_start` and days are named with `day_names` and `month_names`,
+//! countdowns ending within `countdown_days` come first.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone};
+use termion::event::Key;
+
+use crate::app::{ListState, Remind};
+use crate::dates::Dates;
+use crate::due;
+
+/// A todo due in the week shown.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub due: i64,
+    pub tab: usize,
+    pub idx: usize,
+    pub task: String,
+    pub note: String,
+    pub done: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgendaResult {
+    /// Go to the todo in the sticky note at `tab`.
+    Goto(usize, usize),
+    Closed,
+}
+
+#[derive(Clone, Debug)]
+pub struct Agenda {
+    /// Any day in the week shown.
+    pub day: NaiveDate,
+    /// The selected entry counting through the whole week.
+    pub selected: usize,
+}
+
+/// The week of today.
+impl Default for Agenda {
+    fn default() -> Self {
+        Agenda {
+            day: Local::now().naive_local().date(),
+            selected: 0,
+        }
+    }
+}
+
+impl Agenda {
+    /// Unfinished countdown todos ending within `days` from today, soonest first.
+    pub fn countdowns(&self, notes: &ListState<Remind>, days: i64) -> Vec<Entry> {
+        let now = Local::now();
+        let mut entries = notes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !n.is_smart())
+            .flat_map(|(tab, note)| {
+                note.list.iter().enumerate().filter_map(move |(idx, todo)| {
+                    let due = todo
+                        .due
+                        .filter(|_| todo.countdown && !todo.status.is_closed())?;
+                    Some(Entry {
+                        due,
+                        tab,
+                        idx,
+                        task: todo.as_str().to_string(),
+                        note: note.title.clone(),
+                        done: false,
+                    })
+                })
+            })
+            .filter(|e| (0..=days).contains(&due::days_until(e.due, now)))
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|e| e.due);
+        entries
+    }
+
+    /// Every entry that can be selected, the countdowns then the week.
+    pub fn entries(&self, notes: &ListState<Remind>, dates: &Dates, days: i64) -> Vec<Entry> {
+        let mut entries = self.countdowns(notes, days);
+        entries.extend(self.week(notes, dates).into_iter().flat_map(|(_, e)| e));
+        entries
+    }
+
+    /// The days of the week shown with the todos due on each.
+    pub fn week(&self, notes: &ListState<Remind>, dates: &Dates) -> Vec<(NaiveDate, Vec<Entry>)> {
+        let start = dates.start_of_week(self.day);
+        let mut days = (0..7)
+            .map(|d| (start + Duration::days(d), Vec::new()))
+            .collect::<Vec<_>>();
+        // smart sticky notes only hold copies of todos that are listed anyway
+        for (tab, note) in notes.iter().enumerate().filter(|(_, n)| !n.is_smart()) {
+            for (idx, todo) in note.list.iter().enumerate() {
+                let due = match todo.due {
+                    Some(due) => due,
+                    None => continue,
+                };
+                let day = Local.timestamp(due, 0).naive_local().date();
+                let offset = (day - start).num_days();
+                if (0..7).contains(&offset) {
+                    days[offset as usize].1.push(Entry {
+                        due,
+                        tab,
+                        idx,
+                        task: todo.as_str().to_string(),
+                        note: note.title.clone(),
+                        done: todo.status.is_closed(),
+                    });
+                }
+            }
+        }
+        for (_, entries) in days.iter_mut() {
+            entries.sort_by_key(|e| e.due);
+        }
+        days
+    }
+
+    /// Left and right go a week back or on, page up and page down a month,
+    /// home back to this week and enter goes to the selected todo.
+    pub fn on_key(&mut self, key: Key, entries: &[Entry]) -> Option<AgendaResult> {
+        match key {
+            Key::Esc | Key::Ctrl('q') => return Some(AgendaResult::Closed),
+            Key::Char('\n') => {
+                return entries
+                    .get(self.selected)
+                    .map(|e| AgendaResult::Goto(e.tab, e.idx))
+            }
+            Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::Down if self.selected + 1 < entries.len() => self.selected += 1,
+            Key::Left => self.jump(self.day - Duration::days(7)),
+            Key::Right => self.jump(self.day + Duration::days(7)),
+            Key::PageUp => self.jump(add_months(self.day, -1)),
+            Key::PageDown => self.jump(add_months(self.day, 1)),
+            Key::Home => self.jump(Local::now().naive_local().date()),
+            _ => {}
+        }
+        None
+    }
+
+    fn jump(&mut self, day: NaiveDate) {
+        self.day = day;
+        self.selected = 0;
+    }
+}
+
+/// The same day `months` later, or the last day of a shorter month.
+fn add_months(day: NaiveDate, months: i32) -> NaiveDate {
+    let month0 = day.year() * 12 + day.month0() as i32 + months;
+    let (year, month) = (month0.div_euclid(12), month0.rem_euclid(12) as u32 + 1);
+    (0..4)
+        .find_map(|back| NaiveDate::from_ymd_opt(year, month, day.day() - back))
+        .unwrap_or(day)
+}
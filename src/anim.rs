@@ -0,0 +1,105 @@
+//! What moves between ticks. The tick updates the model, animations are only
+//! looked up while drawing, so the frames in between repaint without running
+//! any of `App::on_tick`. The main loop asks for frames only while one runs.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// The things that animate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Anim {
+    /// The background task spinner in the tab bar.
+    Spinner,
+    /// Expired toasts folding away.
+    ToastsLeaving,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Kind {
+    /// Runs until stopped, a frame every `Duration`.
+    Loop(Duration),
+    /// Done after `Duration`.
+    Once(Duration),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Animation {
+    started: Instant,
+    kind: Kind,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Animations {
+    running: BTreeMap<Anim, Animation>,
+}
+
+impl Animations {
+    /// Starts `anim` looping with a frame every `frame`, one already looping
+    /// keeps its place.
+    pub fn start_loop(&mut self, anim: Anim, frame: Duration) {
+        self.running.entry(anim).or_insert(Animation {
+            started: Instant::now(),
+            kind: Kind::Loop(frame),
+        });
+    }
+
+    /// Starts `anim` over, it runs for `lasts`.
+    pub fn start_once(&mut self, anim: Anim, lasts: Duration) {
+        self.running.insert(
+            anim,
+            Animation {
+                started: Instant::now(),
+                kind: Kind::Once(lasts),
+            },
+        );
+    }
+
+    pub fn stop(&mut self, anim: Anim) {
+        self.running.remove(&anim);
+    }
+
+    /// How many frames a looping `anim` is in, `None` when it isn't running.
+    pub fn frame(&self, anim: Anim) -> Option<usize> {
+        let animation = self.running.get(&anim)?;
+        match animation.kind {
+            Kind::Loop(frame) => {
+                Some((animation.started.elapsed().as_millis() / frame.as_millis().max(1)) as usize)
+            }
+            Kind::Once(_) => None,
+        }
+    }
+
+    /// How far along `anim` is from 0 to 1, `None` once it's done.
+    pub fn progress(&self, anim: Anim) -> Option<f64> {
+        let animation = self.running.get(&anim)?;
+        match animation.kind {
+            Kind::Once(lasts) => {
+                let done = animation.started.elapsed().as_secs_f64() / lasts.as_secs_f64();
+                if done < 1.0 {
+                    Some(done)
+                } else {
+                    None
+                }
+            }
+            Kind::Loop(_) => None,
+        }
+    }
+
+    /// Drops the animations that are done, called from `App::on_tick`.
+    pub fn tick(&mut self) {
+        self.running.retain(|_, animation| match animation.kind {
+            Kind::Once(lasts) => animation.started.elapsed() < lasts,
+            Kind::Loop(_) => true,
+        });
+    }
+
+    /// True while anything needs frames drawn.
+    pub fn is_running(&self) -> bool {
+        self.running
+            .iter()
+            .any(|(anim, animation)| match animation.kind {
+                Kind::Once(_) => self.progress(*anim).is_some(),
+                Kind::Loop(_) => true,
+            })
+    }
+}
@@ -0,0 +1,181 @@
+//! `forget snapshot` draws one sticky note the way the app shows it and
+//! writes the screen as text with ANSI colors, for pasting into a terminal
+//! recording or turning into an image with something like `ansi2html`.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use tui::backend::TestBackend;
+use tui::buffer::Buffer;
+use tui::style::{Color, Modifier, Style};
+use tui::Terminal;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{App, ListState};
+use crate::config;
+use crate::ux;
+
+const DEFAULT_WIDTH: u16 = 80;
+/// Rows around the todos, the tab bar and the list's borders.
+const CHROME: u16 = 6;
+const MAX_HEIGHT: u16 = 200;
+
+/// The SGR parameter of `color`, `base` is 30 for the foreground and 40 for
+/// the background.
+fn color(color: Color, base: u8) -> Option<String> {
+    let bright = base + 60;
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => bright,
+        Color::LightRed => bright + 1,
+        Color::LightGreen => bright + 2,
+        Color::LightYellow => bright + 3,
+        Color::LightBlue => bright + 4,
+        Color::LightMagenta => bright + 5,
+        Color::LightCyan => bright + 6,
+        Color::White => bright + 7,
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+        Color::Indexed(i) => return Some(format!("{};5;{}", base + 8, i)),
+    };
+    Some(code.to_string())
+}
+
+/// The escape sequence that switches to `style` from no style at all.
+fn sgr(style: Style) -> String {
+    let mut codes = vec!["0".to_string()];
+    let modifiers = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, code) in modifiers.iter() {
+        if style.modifier.contains(*modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(color(style.fg, 30));
+    codes.extend(color(style.bg, 40));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// Every row of `buffer` as a line, styles only change where the cells do
+/// and each line ends reset so it can be pasted anywhere.
+pub fn render(buffer: &Buffer) -> String {
+    let width = buffer.area().width as usize;
+    let mut out = String::new();
+    for row in buffer.content().chunks(width) {
+        let mut style = None;
+        let mut skip = 0;
+        for cell in row {
+            // the cells a wide symbol covers are drawn by it
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            if style != Some(cell.style) {
+                out.push_str(&sgr(cell.style));
+                style = Some(cell.style);
+            }
+            out.push_str(&cell.symbol);
+            skip = cell.symbol.width().saturating_sub(1);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// The value after `--name` in `args`.
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn size(args: &[String], name: &str) -> io::Result<Option<u16>> {
+    flag(args, name)
+        .map(|n| {
+            n.parse().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} takes a number, not {:?}", name, n),
+                )
+            })
+        })
+        .transpose()
+}
+
+/// `forget snapshot <title> [--width 80] [--height rows] [--out file]`.
+pub fn run(args: &[String]) -> io::Result<()> {
+    let usage = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "usage: forget snapshot <title> [--width 80] [--height rows] [--out file]",
+        )
+    };
+    // the title is the word that isn't a flag or a flag's value
+    let title = args
+        .iter()
+        .enumerate()
+        .find(|(i, arg)| !arg.starts_with("--") && (*i == 0 || !args[i - 1].starts_with("--")))
+        .map(|(_, arg)| arg)
+        .ok_or_else(usage)?;
+    let remind = config::open_db()?
+        .items
+        .into_iter()
+        .find(|n| n.title == *title)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("there is no sticky note called {:?}", title),
+            )
+        })?;
+    if remind.is_locked() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{:?} is secret", title),
+        ));
+    }
+
+    let rows = remind.list.len().max(remind.note.lines().count()) as u16;
+    let width = size(args, "--width")?
+        .unwrap_or(DEFAULT_WIDTH)
+        .max(ux::MIN_WIDTH);
+    let height = size(args, "--height")?
+        .unwrap_or(rows + CHROME)
+        .clamp(ux::MIN_HEIGHT, MAX_HEIGHT);
+
+    config::save_cfg_file()?;
+    let mut config = config::open_cfg_file()?;
+    config.key_hints = false;
+    // only this sticky note, the other tabs aren't anyone else's business
+    let mut app = App::with_notes(ListState::new(vec![remind]), config)?;
+    app.toasts.list.clear();
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    ux::draw(&mut terminal, &mut app)?;
+    let text = render(terminal.backend().buffer());
+
+    match flag(args, "--out") {
+        Some(path) if Path::new(path).exists() => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} is already there", path),
+        )),
+        Some(path) => fs::write(path, text),
+        None => io::stdout().write_all(text.as_bytes()),
+    }
+}
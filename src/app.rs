@@ -1,26 +1,157 @@
 use std::cell::RefCell;
-use std::io;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write as _};
 use std::ops::{Index, IndexMut};
-use std::process::{Child, Command, Stdio};
-use std::thread;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use chrono::{offset::TimeZone, DateTime, Local};
+use chrono::{offset::TimeZone, DateTime, Local, NaiveDate};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use termion::event::{Key, MouseButton, MouseEvent};
+use tui::layout::Rect;
+use unicode_width::UnicodeWidthStr;
 
+use crate::action::{self, Action, Macro};
+use crate::activity::ActivityLog;
+use crate::agenda::{Agenda, AgendaResult};
+use crate::anim::{Anim, Animations};
+use crate::attach;
+use crate::calc;
+#[cfg(feature = "chat")]
+use crate::chat;
+use crate::checklist;
+use crate::clipboard;
+use crate::colors::{ColorPicker, PickerResult};
 use crate::config::{self, AppConfig};
+use crate::dates::Dates;
+use crate::debug::DebugStats;
+use crate::deleted::{Deleted, RecentlyDeleted};
+use crate::draft::{self, Draft};
+use crate::due;
+use crate::effort::{Estimate, Remaining};
+use crate::feed::{self, FeedFetched};
+use crate::find::Find;
+#[cfg(feature = "github")]
+use crate::github;
+use crate::habit;
+use crate::hints;
+use crate::holidays::Holidays;
+use crate::jobs::{self, Finished, JobStatus, Jobs, Report};
+use crate::kiosk::Kiosk;
+#[cfg(feature = "links")]
+use crate::links::{self, Titled};
+use crate::modal::{Modal, ModalAction, ModalResult};
+use crate::notify;
+use crate::paths;
+use crate::pty::Pty;
+use crate::publish;
+use crate::query::Query;
+use crate::quiet::Quiet;
+use crate::recurring::{CarryOver, Instance};
+use crate::remote::{self, Fetched, Fetcher};
+use crate::rules::ColorRules;
+use crate::secret;
+use crate::session::Session;
+use crate::spell::Dictionary;
+use crate::symbols;
+#[cfg(feature = "sync")]
+use crate::sync::{self, SyncState, Synced};
+use crate::theme;
+use crate::title;
+use crate::toast::{Level, Toasts};
+use crate::worker::{self, Output, TaskEvent, Workers};
+
+/// How long an expired toast takes to fold away.
+const TOAST_LEAVE: Duration = Duration::from_millis(200);
+/// How much `<` and `>` move the divider between the todos and the notes.
+const RESIZE_STEP: i16 = 5;
+/// Neither the todos nor the notes get less than this percent of the width.
+const MIN_SPLIT: i16 = 20;
+/// Activity that doesn't change the current sticky note, everything else logged does.
+const UNCHANGED: &[&str] = &[
+    "edit in editor failed",
+    "save config failed",
+    "lock",
+    "publish sticky note",
+    "record macro",
+    "make recurring note",
+    "subscribe to sticky note",
+    "subscribe to feed",
+    "feed entries",
+    "carry over",
+    "dry run",
+    "refuse command",
+];
 
 #[derive(Clone, Debug)]
 pub struct TabsState {
     pub titles: Vec<String>,
     pub index: usize,
+    /// The tab viewed before the current one.
+    previous: Option<usize>,
+    /// The tab `remember` last saw.
+    shown: usize,
 }
 
 impl TabsState {
     pub fn new(titles: Vec<String>) -> TabsState {
-        TabsState { titles, index: 0 }
+        TabsState {
+            titles,
+            index: 0,
+            previous: None,
+            shown: 0,
+        }
+    }
+
+    /// Notes a change of tab since the last call, so `flip` can go back.
+    pub fn remember(&mut self) {
+        if self.index != self.shown {
+            self.previous = Some(self.shown);
+            self.shown = self.index;
+        }
+    }
+
+    /// Goes back to the previously viewed tab, again to return.
+    pub fn flip(&mut self) -> bool {
+        self.remember();
+        match self.previous.filter(|p| *p < self.titles.len()) {
+            Some(previous) => {
+                self.previous = Some(self.index);
+                self.index = previous;
+                self.shown = previous;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Keeps the remembered tabs pointing at the same sticky notes after the
+    /// one at `idx` is removed.
+    pub fn removed(&mut self, idx: usize) {
+        self.previous = match self.previous {
+            Some(p) if p == idx => None,
+            Some(p) if p > idx => Some(p - 1),
+            p => p,
+        };
+        if self.shown > idx {
+            self.shown -= 1;
+        }
     }
+
+    /// Like `removed` for a sticky note put back at `idx`.
+    pub fn inserted(&mut self, idx: usize) {
+        self.previous = self.previous.map(|p| if p >= idx { p + 1 } else { p });
+        if self.shown >= idx {
+            self.shown += 1;
+        }
+    }
+
     pub fn next(&mut self) {
-        self.index = (self.index + 1) % self.titles.len();
+        if !self.titles.is_empty() {
+            self.index = (self.index + 1) % self.titles.len();
+        }
     }
 
     pub fn previous(&mut self) {
@@ -46,6 +177,14 @@ impl<I> Default for ListState<I> {
     }
 }
 
+impl ListState<Remind> {
+    /// The id for a new todo, every todo gets its id from here so none is
+    /// handed out twice, not even one sealed in a locked secret note.
+    pub fn next_todo_id(&self) -> u64 {
+        self.iter().map(Remind::max_id).max().unwrap_or(0) + 1
+    }
+}
+
 impl<I> ListState<I> {
     pub fn new(items: Vec<I>) -> ListState<I> {
         ListState { items, selected: 0 }
@@ -59,20 +198,18 @@ impl<I> ListState<I> {
         self.items.is_empty()
     }
 
-    pub fn select_previous(&mut self) {
-        if self.selected != 0 {
-            self.selected -= 1;
-        }
+    /// Removes the selected item, the one after it is selected next or the
+    /// new last one when the last was removed.
+    pub fn remove_selected(&mut self) -> Option<I> {
+        let item = if self.selected < self.items.len() {
+            Some(self.items.remove(self.selected))
+        } else {
+            None
+        };
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+        item
     }
 
-    pub fn select_next(&mut self) {
-        if self.is_empty() {
-            return;
-        }
-        if self.selected < self.len() - 1 {
-            self.selected += 1
-        }
-    }
     pub fn get_selected(&self) -> Option<&I> {
         self.items.get(self.selected)
     }
@@ -85,6 +222,24 @@ impl<I> ListState<I> {
     }
 }
 
+impl ListState<Todo> {
+    /// Edits the todo with `id` where it is and selects it, `None` if it's gone.
+    pub fn edit(
+        &mut self,
+        id: u64,
+        form: &AddTodo,
+        now: DateTime<Local>,
+        dates: &Dates,
+        device: &str,
+    ) -> Option<&Todo> {
+        let idx = self.items.iter().position(|t| t.id == id)?;
+        self.selected = idx;
+        let todo = &mut self.items[idx];
+        todo.apply_edit(form, now, dates, device);
+        Some(todo)
+    }
+}
+
 impl<I> Index<usize> for ListState<I> {
     type Output = I;
     fn index(&self, idx: usize) -> &Self::Output {
@@ -96,28 +251,77 @@ impl<I> IndexMut<usize> for ListState<I> {
         &mut self.items[idx]
     }
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct AddTodo {
-    pub date: DateTime<Local>,
     pub question_index: usize,
     pub task: String,
     pub cmd: String,
+    /// The `#id` of the todo this one waits on.
+    pub blocked_by: String,
+    /// Minutes, `2h` or story points like `3p`.
+    pub estimate: String,
+    /// `14:30`, `tomorrow 9:00` or `2020-03-01 14:30`.
+    pub due: String,
+    /// `toast`, `silent`, `failures`, `notify` or `note`, see `Report`.
+    #[serde(default)]
+    pub report: String,
+    /// The id of the todo being edited, so the edit lands on it even if the
+    /// list changed while the form was open.
+    #[serde(default)]
+    pub editing: Option<u64>,
 }
 
-impl Default for AddTodo {
-    fn default() -> Self {
-        Self {
-            date: chrono::Local::now(),
+impl AddTodo {
+    /// The form for editing `todo`.
+    pub fn from_todo(todo: &Todo, dates: &Dates) -> AddTodo {
+        AddTodo {
             question_index: 0,
-            task: String::default(),
-            cmd: String::default(),
+            task: todo.task.clone(),
+            cmd: todo.cmd.clone(),
+            blocked_by: todo.blocked_by.map(|id| id.to_string()).unwrap_or_default(),
+            estimate: todo.estimate.map(|e| e.to_string()).unwrap_or_default(),
+            due: todo
+                .due
+                .map(|due| due::input(due, dates))
+                .unwrap_or_default(),
+            report: match todo.report {
+                Report::Toast => String::new(),
+                report => report.name().to_string(),
+            },
+            editing: Some(todo.id),
         }
     }
-}
 
-impl AddTodo {
+    /// The text of the question being answered.
+    pub fn focused_mut(&mut self) -> &mut String {
+        match self.question_index {
+            0 => &mut self.task,
+            1 => &mut self.cmd,
+            2 => &mut self.blocked_by,
+            3 => &mut self.estimate,
+            4 => &mut self.due,
+            _ => &mut self.report,
+        }
+    }
+
+    /// The id typed into the blocked by question, `#` is optional.
+    pub fn blocker(&self) -> Option<u64> {
+        self.blocked_by.trim().trim_start_matches('#').parse().ok()
+    }
+
+    pub fn clear(&mut self) {
+        self.task.clear();
+        self.cmd.clear();
+        self.blocked_by.clear();
+        self.estimate.clear();
+        self.due.clear();
+        self.report.clear();
+        self.editing = None;
+        self.question_index = 0;
+    }
+
     pub fn next(&mut self) {
-        if self.question_index != 1 {
+        if self.question_index != 5 {
             self.question_index += 1
         }
     }
@@ -128,47 +332,691 @@ impl AddTodo {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct AddRemind {
+    pub question_index: usize,
     pub title: String,
+    pub cmd: String,
+    /// Smart sticky notes ask for a query instead of a default command.
+    pub smart: bool,
+    pub query: String,
+    /// Most todos allowed in progress at once, empty for no limit.
+    pub wip_limit: String,
 }
 
-impl Default for AddRemind {
-    fn default() -> Self {
-        Self {
-            title: String::default(),
+impl AddRemind {
+    /// The text of the question being answered.
+    pub fn focused_mut(&mut self) -> &mut String {
+        match (self.question_index, self.smart) {
+            (0, _) => &mut self.title,
+            (1, true) => &mut self.query,
+            (1, false) => &mut self.cmd,
+            _ => &mut self.wip_limit,
+        }
+    }
+
+    pub fn wip_limit(&self) -> Option<usize> {
+        self.wip_limit.trim().parse().ok().filter(|n| *n > 0)
+    }
+
+    pub fn next(&mut self) {
+        if self.question_index != 2 {
+            self.question_index += 1
+        }
+    }
+    pub fn previous(&mut self) {
+        if self.question_index != 0 {
+            self.question_index -= 1
+        }
+    }
+}
+
+/// Where things were last drawn, for finding what the mouse is over.
+#[derive(Clone, Debug, Default)]
+pub struct Areas {
+    /// The todos and the notes next to them.
+    pub main: Rect,
+    /// The todo list including its borders, empty in the board view.
+    pub list: Rect,
+    /// The first column and width of each sticky note's tab title.
+    pub tabs: Vec<(u16, u16)>,
+    /// The notes next to the todos including their borders.
+    pub notes: Rect,
+    /// The row of the tab titles.
+    pub tabs_row: u16,
+    /// The first column and width of the next due todo next to the tabs.
+    pub due: (u16, u16),
+    /// The terminal pane including its borders.
+    pub pty: Rect,
+}
+
+/// Where a dragged todo would land.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropTarget {
+    /// Where the todo ends up in the list.
+    Row(usize),
+    /// On another sticky note's tab.
+    Tab(usize),
+}
+
+/// A todo being dragged with the mouse.
+#[derive(Clone, Copy, Debug)]
+pub struct Drag {
+    pub from: usize,
+    pub over: Option<DropTarget>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AddPassphrase {
+    pub pass: String,
+    /// The passphrase typed again, a new secret note needs both to match.
+    pub confirm: String,
+    pub confirming: bool,
+    pub error: Option<String>,
+}
+
+impl AddPassphrase {
+    /// The text of the question being answered.
+    pub fn focused_mut(&mut self) -> &mut String {
+        if self.confirming {
+            &mut self.confirm
+        } else {
+            &mut self.pass
+        }
+    }
+}
+
+/// Where a todo is at, also the column it's in on the board view.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Status {
+    #[default]
+    #[serde(alias = "Todo")]
+    Open,
+    #[serde(alias = "Doing")]
+    InProgress,
+    Waiting,
+    Done,
+    Cancelled,
+}
+
+impl Status {
+    pub const ALL: [Status; 5] = [
+        Status::Open,
+        Status::InProgress,
+        Status::Waiting,
+        Status::Done,
+        Status::Cancelled,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Status::Open => "Open",
+            Status::InProgress => "In Progress",
+            Status::Waiting => "Waiting",
+            Status::Done => "Done",
+            Status::Cancelled => "Cancelled",
         }
     }
+
+    /// Done and cancelled todos need no more work.
+    pub fn is_closed(self) -> bool {
+        self == Status::Done || self == Status::Cancelled
+    }
+
+    fn index(self) -> usize {
+        Status::ALL.iter().position(|s| *s == self).unwrap_or(0)
+    }
+
+    /// The status after this one, wrapping around to `Open`.
+    pub fn next(self) -> Status {
+        Status::ALL[(self.index() + 1) % Status::ALL.len()]
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(from = "TodoRepr")]
 pub struct Todo {
+    /// Unique across every sticky note, 0 until one is assigned when the
+    /// data base is opened.
+    pub id: u64,
     #[serde(with = "date_fmt")]
     pub date: DateTime<Local>,
     pub task: String,
     pub cmd: String,
-    pub completed: bool,
+    /// The id of a todo that has to be closed before this one can be done.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked_by: Option<u64>,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<Estimate>,
+    /// Seconds spent in progress, not counting the current stretch.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub tracked: u64,
+    /// Unix time the todo was last put in progress, while it still is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started: Option<i64>,
+    /// How many times the command has been run.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub runs: u64,
+    /// Unix time the command was last run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<i64>,
+    /// How many times it was carried over to a new day's note unfinished.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub carried: u64,
+    /// Unix time it should be done by.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<i64>,
+    /// The `device_name` of the machine it was added on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_on: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edited: Option<Edited>,
+    /// Shows the days left until `due` instead of being a task to do.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub countdown: bool,
+    /// The `2020-03-01` days a habit was done, oldest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub habit_days: Vec<String>,
+    /// How much it counts toward a goal sticky note's progress, 0 counts as 1.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub weight: u32,
+    /// How its command tells how it went.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub report: Report,
+    /// The issue it was imported from, like `owner/repo#12`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+}
+
+/// Where and when a todo was last changed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Edited {
+    pub device: String,
+    /// Unix time.
+    pub at: i64,
+}
+
+fn is_zero<N: Default + PartialEq>(n: &N) -> bool {
+    *n == N::default()
+}
+
+/// What goes before a new entry added to the end of `note`, an empty line
+/// between it and the last one.
+fn entry_sep(note: &str) -> &'static str {
+    match note.chars().last() {
+        None => "",
+        Some('\n') => "\n",
+        Some(_) => "\n\n",
+    }
+}
+
+/// A todo as it's stored, older data bases only have `completed`.
+#[derive(Deserialize)]
+struct TodoRepr {
+    #[serde(default)]
+    id: u64,
+    #[serde(with = "date_fmt")]
+    date: DateTime<Local>,
+    task: String,
+    cmd: String,
+    #[serde(default)]
+    blocked_by: Option<u64>,
+    #[serde(default)]
+    status: Option<Status>,
+    #[serde(default)]
+    completed: bool,
+    #[serde(default)]
+    estimate: Option<Estimate>,
+    #[serde(default)]
+    tracked: u64,
+    #[serde(default)]
+    started: Option<i64>,
+    #[serde(default)]
+    runs: u64,
+    #[serde(default)]
+    last_run: Option<i64>,
+    #[serde(default)]
+    carried: u64,
+    #[serde(default)]
+    due: Option<i64>,
+    #[serde(default)]
+    created_on: Option<String>,
+    #[serde(default)]
+    edited: Option<Edited>,
+    #[serde(default)]
+    countdown: bool,
+    #[serde(default)]
+    habit_days: Vec<String>,
+    #[serde(default)]
+    weight: u32,
+    #[serde(default)]
+    report: Report,
+    #[serde(default)]
+    issue: Option<String>,
+}
+
+impl From<TodoRepr> for Todo {
+    fn from(repr: TodoRepr) -> Todo {
+        let status = match (repr.status, repr.completed) {
+            (Some(status), _) => status,
+            (None, true) => Status::Done,
+            (None, false) => Status::Open,
+        };
+        Todo {
+            id: repr.id,
+            date: repr.date,
+            task: repr.task,
+            cmd: repr.cmd,
+            blocked_by: repr.blocked_by,
+            status,
+            estimate: repr.estimate,
+            tracked: repr.tracked,
+            started: repr.started,
+            runs: repr.runs,
+            last_run: repr.last_run,
+            carried: repr.carried,
+            due: repr.due,
+            created_on: repr.created_on,
+            edited: repr.edited,
+            countdown: repr.countdown,
+            habit_days: repr.habit_days,
+            weight: repr.weight,
+            report: repr.report,
+            issue: repr.issue,
+        }
+    }
 }
 
 impl Todo {
+    /// An open todo added now, with no command and nothing else set yet.
+    pub fn new(id: u64, task: String) -> Todo {
+        Todo {
+            id,
+            date: Local::now(),
+            task,
+            cmd: String::new(),
+            blocked_by: None,
+            status: Status::Open,
+            estimate: None,
+            tracked: 0,
+            started: None,
+            runs: 0,
+            last_run: None,
+            carried: 0,
+            due: None,
+            created_on: None,
+            edited: None,
+            countdown: false,
+            habit_days: Vec::new(),
+            weight: 0,
+            report: Report::Toast,
+            issue: None,
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         &self.task
     }
+
+    /// Changes the status, time in progress is added to `tracked`.
+    pub fn set_status(&mut self, status: Status) {
+        let now = Local::now().timestamp();
+        if status == Status::InProgress && self.started.is_none() {
+            self.started = Some(now);
+        } else if status != Status::InProgress {
+            if let Some(started) = self.started.take() {
+                self.tracked += (now - started).max(0) as u64;
+            }
+        }
+        self.status = status;
+    }
+
+    /// Takes what the edit form holds, the id, creation date, status, run
+    /// history and time tracked stay as they were.
+    pub fn apply_edit(
+        &mut self,
+        form: &AddTodo,
+        now: DateTime<Local>,
+        dates: &Dates,
+        device: &str,
+    ) {
+        self.task = form.task.clone();
+        self.cmd = form.cmd.clone();
+        self.blocked_by = form.blocker().filter(|b| *b != self.id);
+        self.estimate = Estimate::parse(&form.estimate);
+        self.due = due::parse(&form.due, now, dates).map(|d| d.timestamp());
+        self.report = Report::parse(&form.report).unwrap_or_default();
+        self.edited = Some(Edited {
+            device: device.to_string(),
+            at: now.timestamp(),
+        });
+    }
+
+    /// Marks it as changed just now on `device`.
+    pub fn touch(&mut self, device: &str) {
+        self.edited = Some(Edited {
+            device: device.to_string(),
+            at: Local::now().timestamp(),
+        });
+    }
+
+    /// Seconds spent in progress so far.
+    pub fn tracked_secs(&self) -> u64 {
+        let current = self
+            .started
+            .map(|started| (Local::now().timestamp() - started).max(0) as u64)
+            .unwrap_or(0);
+        self.tracked + current
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Remind {
     pub title: String,
     pub note: String,
+    /// Command template used by todos that don't have their own command,
+    /// `{task}` is replaced with the todo's task.
+    #[serde(default)]
+    pub default_cmd: String,
     pub list: ListState<Todo>,
+    /// The encrypted `note` and `list` of a secret sticky note, both are empty
+    /// while the note is locked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Set once a secret note has been unlocked this session.
+    #[serde(skip)]
+    pub passphrase: Option<String>,
+    /// The body last encrypted and its cipher text, so saving an unchanged
+    /// secret note doesn't run `openssl` again.
+    #[serde(skip)]
+    pub seal: RefCell<Option<Seal>>,
+    /// The highest todo id of a secret sticky note when it was sealed, so
+    /// its ids aren't handed out again while it's locked.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub sealed_max_id: u64,
+    /// Smart sticky notes show the todos of every other sticky note matching
+    /// this query, their list is never saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Leave done and cancelled todos out of the list, the selected one stays.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub hide_done: bool,
+    /// Show the todos as a board with a column for each status instead of a list.
+    #[serde(default)]
+    pub board: bool,
+    /// Most todos that should be in progress at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wip_limit: Option<usize>,
+    /// Set on sticky notes made from a `recurring` template.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<Instance>,
+    /// Remote sticky notes are fetched from this URL, their list is never saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The URL of the feed whose new entries are added as todos.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feed: Option<String>,
+    /// The first line of the note in view.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub note_scroll: usize,
+    /// Keep the end of the note in view as it grows instead.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub follow_tail: bool,
+    /// Unix time the note text was last changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note_edited: Option<i64>,
+    /// Unix time the sticky note was made.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    /// Unix time anything in the sticky note, its todos included, last changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    /// Shown in front of the title in the tabs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Every addition to the note starts with the time, see `journal_format`.
+    #[serde(default)]
+    pub journal: bool,
+    /// Every todo is a habit done once a day, they open again each morning.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub habit: bool,
+    /// The todos are steps toward a goal, the header shows their weighted progress.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub goal: bool,
+    /// Paths of files that belong with the sticky note, the files aren't copied.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<String>,
+    /// Set on sticky notes added by `forget share import`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared: Option<Shared>,
 }
 
-impl Default for Remind {
-    fn default() -> Self {
-        Self {
-            title: String::default(),
-            note: String::default(),
-            list: ListState::default(),
+/// Who shared a sticky note and when.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Shared {
+    pub from: String,
+    /// Unix time it was exported.
+    pub at: i64,
+}
+
+/// What is encrypted for a secret sticky note.
+#[derive(Deserialize, Serialize)]
+struct SecretBody {
+    note: String,
+    list: ListState<Todo>,
+}
+
+/// The JSON of a `SecretBody` and what it was encrypted to.
+#[derive(Clone, Debug)]
+pub struct Seal {
+    body: String,
+    cipher: String,
+}
+
+impl Remind {
+    pub fn is_locked(&self) -> bool {
+        self.secret.is_some() && self.passphrase.is_none()
+    }
+
+    /// The highest id of its todos, the ones sealed while locked included.
+    pub fn max_id(&self) -> u64 {
+        self.list
+            .iter()
+            .map(|t| t.id)
+            .max()
+            .unwrap_or(0)
+            .max(self.sealed_max_id)
+    }
+
+    /// Makes this a secret sticky note, encrypted with `pass` from now on.
+    pub fn make_secret(&mut self, pass: &str) -> io::Result<()> {
+        self.seal.take();
+        self.passphrase = Some(pass.to_string());
+        self.secret = Some(self.encrypt_body(pass)?);
+        Ok(())
+    }
+
+    pub fn unlock(&mut self, pass: &str) -> io::Result<()> {
+        if let Some(cipher) = &self.secret {
+            let json = secret::decrypt(cipher, pass)?;
+            let body = serde_json::from_str::<SecretBody>(&json)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.note = body.note;
+            self.list = body.list;
+            self.passphrase = Some(pass.to_string());
+            // saving it unchanged keeps the cipher text it was read from
+            *self.seal.borrow_mut() = Some(Seal {
+                body: self.body_json()?,
+                cipher: cipher.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn lock(&mut self) -> io::Result<()> {
+        if let Some(pass) = &self.passphrase {
+            // a failed encryption leaves the note unlocked, not half locked
+            let cipher = self.encrypt_body(pass)?;
+            self.sealed_max_id = self.max_id();
+            self.secret = Some(cipher);
+            self.passphrase = None;
+            self.note.clear();
+            self.list = ListState::default();
+            self.seal.take();
+        }
+        Ok(())
+    }
+
+    /// A copy that is safe to write to disk, unlocked secret notes are encrypted again.
+    pub fn sealed(&self) -> io::Result<Remind> {
+        let mut remind = self.clone();
+        // encrypted through `self` so the next save can reuse it
+        if let Some(pass) = remind.passphrase.take() {
+            remind.sealed_max_id = self.max_id();
+            remind.secret = Some(self.encrypt_body(&pass)?);
+            remind.note.clear();
+            remind.list = ListState::default();
+        }
+        if remind.is_view() {
+            remind.list = ListState::default();
+        }
+        Ok(remind)
+    }
+
+    /// The number of todos in progress and the limit when there are more than allowed.
+    pub fn wip_exceeded(&self) -> Option<(usize, usize)> {
+        let limit = self.wip_limit?;
+        let doing = self
+            .list
+            .iter()
+            .filter(|t| t.status == Status::InProgress)
+            .count();
+        if doing > limit {
+            Some((doing, limit))
+        } else {
+            None
+        }
+    }
+
+    /// The estimates of the todos that aren't done or cancelled added up.
+    pub fn remaining(&self) -> Remaining {
+        let mut remaining = Remaining::default();
+        self.list
+            .iter()
+            .filter(|t| !t.status.is_closed())
+            .filter_map(|t| t.estimate)
+            .for_each(|e| remaining.add(e));
+        remaining
+    }
+
+    pub fn is_smart(&self) -> bool {
+        self.query.is_some()
+    }
+
+    /// The first line of the note that keeps its end in view in a `width` by
+    /// `height` area, lines longer than `width` wrap onto more rows.
+    pub fn note_tail(&self, width: u16, height: u16) -> usize {
+        let width = width.max(1) as usize;
+        let lines = self.note.split('\n').collect::<Vec<_>>();
+        let mut rows = 0;
+        for (i, line) in lines.iter().enumerate().rev() {
+            rows += line.width().div_ceil(width).max(1);
+            if rows > height as usize {
+                return i + 1;
+            }
+        }
+        0
+    }
+
+    pub fn note_changed(&mut self) {
+        self.note_edited = Some(Local::now().timestamp());
+    }
+
+    /// A new empty sticky note made just now.
+    pub fn new(title: String) -> Remind {
+        let now = Local::now().timestamp();
+        Remind {
+            title,
+            created_at: Some(now),
+            updated_at: Some(now),
+            ..Remind::default()
+        }
+    }
+
+    pub fn touch(&mut self) {
+        self.updated_at = Some(Local::now().timestamp());
+    }
+
+    /// The weight of the done todos and of all of them, cancelled ones don't count.
+    pub fn goal_progress(&self) -> (u32, u32) {
+        self.list
+            .iter()
+            .filter(|t| t.status != Status::Cancelled)
+            .fold((0, 0), |(done, total), t| {
+                let weight = t.weight.max(1);
+                let done = if t.status == Status::Done {
+                    done + weight
+                } else {
+                    done
+                };
+                (done, total + weight)
+            })
+    }
+
+    /// The indexes of the todos the list shows.
+    pub fn visible_rows(&self) -> Vec<usize> {
+        self.list
+            .iter()
+            .enumerate()
+            .filter(|(i, t)| !self.hide_done || !t.status.is_closed() || *i == self.list.selected)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.url.is_some()
+    }
+
+    /// Smart and remote sticky notes only show todos kept somewhere else.
+    pub fn is_view(&self) -> bool {
+        self.is_smart() || self.is_remote()
+    }
+
+    fn body_json(&self) -> io::Result<String> {
+        Ok(serde_json::to_string(&SecretBody {
+            note: self.note.clone(),
+            list: self.list.clone(),
+        })?)
+    }
+
+    /// The cipher text of the note and todos, only encrypted again when
+    /// they changed since the last time.
+    fn encrypt_body(&self, pass: &str) -> io::Result<String> {
+        let body = self.body_json()?;
+        if let Some(seal) = self.seal.borrow().as_ref().filter(|s| s.body == body) {
+            return Ok(seal.cipher.clone());
+        }
+        let cipher = secret::encrypt(&body, pass)?;
+        *self.seal.borrow_mut() = Some(Seal {
+            body,
+            cipher: cipher.clone(),
+        });
+        Ok(cipher)
+    }
+
+    /// The command that runs for `todo`, its own command wins over
+    /// the sticky note's default command.
+    pub fn command_for(&self, todo: &Todo) -> Option<String> {
+        // never run what someone else published
+        if self.is_remote() {
+            None
+        } else if !todo.cmd.trim().is_empty() {
+            Some(todo.cmd.clone())
+        } else if !self.default_cmd.trim().is_empty() {
+            Some(self.default_cmd.replace("{task}", &todo.task))
+        } else {
+            None
         }
     }
 }
@@ -176,17 +1024,122 @@ impl Default for Remind {
 #[derive(Debug)]
 pub struct App {
     pub title: String,
+    /// Which data base this is when it isn't the `db_path` one, shown next
+    /// to the title.
+    pub db_label: Option<String>,
     pub tabs: TabsState,
     pub add_todo: AddTodo,
     pub add_remind: AddRemind,
+    pub add_passphrase: AddPassphrase,
     pub should_quit: bool,
     pub new_reminder: bool,
+    pub edit_reminder: bool,
     pub new_todo: bool,
     pub edit_todo: bool,
     pub new_note: bool,
+    pub runbook: bool,
+    pub passphrase: bool,
+    /// The whole app is locked after `idle_lock_minutes` without input.
+    pub locked: bool,
+    pub unlock: AddPassphrase,
+    pub last_input: Instant,
     pub sticky_note: ListState<Remind>,
-    pub cmd_handle: RefCell<Vec<thread::JoinHandle<Result<Child, io::Error>>>>,
-    pub cmd_err: String,
+    pub jobs: Jobs,
+    pub activity: ActivityLog,
+    /// How far each sticky note has been seen, for the unread marks on the tabs.
+    pub session: Session,
+    pub activity_view: bool,
+    pub rules: ColorRules,
+    pub toasts: Toasts,
+    /// Finished commands don't pop up a toast during quiet hours.
+    pub quiet: Quiet,
+    /// How dates and times are shown and typed.
+    pub dates: Dates,
+    /// Days recurring notes can skip.
+    pub holidays: Holidays,
+    /// The day habit sticky notes were last opened for.
+    habit_day: NaiveDate,
+    /// While open every key goes to the modal.
+    pub modal: Option<Modal>,
+    /// Commands not in `cmd_allowlist` waiting to be confirmed, with their todo and label.
+    pending_cmds: Vec<(u64, String, String)>,
+    pub debug_overlay: bool,
+    /// The keys and what they do over the screen, any key closes it.
+    pub help: bool,
+    /// Something smart sticky notes could show changed since they were
+    /// last filled, the main loop fills them again before drawing.
+    pub smart_stale: bool,
+    /// `low_bandwidth` or `--low-bandwidth`, out of `config` so saving the
+    /// config doesn't keep the flag.
+    pub low_bandwidth: bool,
+    /// `dry_run`, toggled until `forget` quits without changing the config.
+    pub dry_run: bool,
+    /// Loaded when `spellcheck` is on.
+    pub dictionary: Option<Dictionary>,
+    pub debug: DebugStats,
+    /// What moves between ticks, drawn on the frames `animating` asks for.
+    pub animations: Animations,
+    /// The keys pressed since the recorder was started.
+    pub recording: Option<Vec<Action>>,
+    pub areas: Areas,
+    pub drag: Option<Drag>,
+    /// The divider between the todos and the notes is being dragged.
+    pub resizing: bool,
+    /// Up and down scroll the notes instead of moving between todos.
+    pub notes_focus: bool,
+    /// The find prompt under the notes, it gets every key while open.
+    pub find: Option<Find>,
+    /// The color picker, it gets every key while open.
+    pub colors: Option<ColorPicker>,
+    /// The week of due todos, it gets every key while open.
+    pub agenda: Option<Agenda>,
+    /// The highlighted todo while the current sticky note is presented, it
+    /// gets every key then.
+    pub present: Option<usize>,
+    /// Presenting one sticky note for good, read again from the data base
+    /// every so often.
+    pub kiosk: Option<Kiosk>,
+    /// An interactive command running in the terminal pane.
+    pub pty: Option<Pty>,
+    /// The terminal pane is shown and gets every key but the exit key.
+    pub pty_focus: bool,
+    /// The tab and note length before and after the journal time was added,
+    /// so it can be taken out again when nothing was written after it.
+    journal_stamp: Option<(usize, usize, usize)>,
+    /// The checklist file waiting to be opened in `$EDITOR`, see `main`.
+    pub external_edit: Option<PathBuf>,
+    /// Deletions that can still be taken back.
+    pub deleted: RecentlyDeleted,
+    /// A draft left over from last time, until it's picked up or thrown away.
+    restore: Option<Draft>,
+    /// What was last written to `draft.json` and when.
+    last_draft: Option<Draft>,
+    draft_saved: Instant,
+    /// When Enter last saved a form or ran a command, for `enter_debounce_ms`.
+    last_enter: Option<Instant>,
+    /// Fetches the remote sticky notes.
+    pub remote: Fetcher,
+    /// Fetching, publishing and posting done in the background.
+    pub workers: Workers,
+    /// The task uploading the sticky note being published.
+    pub publishing: Option<u64>,
+    /// The task writing the sticky notes to disk.
+    #[cfg(feature = "tokio")]
+    saving: Option<u64>,
+    /// Saved again while writing, so written once more when it's done.
+    #[cfg(feature = "tokio")]
+    save_again: bool,
+    /// How each shared sticky note was last synced.
+    #[cfg(feature = "sync")]
+    sync_state: SyncState,
+    /// The task syncing and when the last one started.
+    #[cfg(feature = "sync")]
+    syncing: Option<u64>,
+    #[cfg(feature = "sync")]
+    synced_at: Option<Instant>,
+    /// The last sync failed with this, it's only shown once.
+    #[cfg(feature = "sync")]
+    sync_error: Option<String>,
     pub config: AppConfig,
 }
 
@@ -202,276 +1155,3456 @@ impl App {
         // also checks if the directory is needed
         let sticky_note = config::open_db()?;
         let config = config::open_cfg_file()?;
+        let mut app = App::with_notes(sticky_note, config)?;
+        app.session = Session::load();
+        app.db_label = config::db_label();
+        #[cfg(feature = "sync")]
+        {
+            app.sync_state = SyncState::load();
+        }
+        if let Some(draft) = draft::load() {
+            app.modal = Some(Modal::confirm(
+                "Restore draft".to_string(),
+                format!("Pick up {} where you left off?", draft.describe()),
+                ModalAction::RestoreDraft,
+            ));
+            app.restore = Some(draft);
+        }
+        Ok(app)
+    }
 
-        Ok(App {
+    /// An app showing `sticky_note` without opening the data base, which
+    /// `forget bench` uses too.
+    pub fn with_notes(mut sticky_note: ListState<Remind>, config: AppConfig) -> io::Result<Self> {
+        if config.sort_tabs_by_activity {
+            sticky_note
+                .items
+                .sort_by_key(|n| std::cmp::Reverse(n.updated_at));
+            sticky_note.selected = 0;
+        }
+        let tab = sticky_note
+            .selected
+            .min(sticky_note.len().saturating_sub(1));
+        let (rules, bad_rules) = ColorRules::new(&config.color_rules);
+
+        let mut app = App {
             title: config.title.clone(),
+            db_label: None,
             add_todo: AddTodo::default(),
             add_remind: AddRemind::default(),
+            add_passphrase: AddPassphrase::default(),
             should_quit: false,
             new_reminder: false,
+            edit_reminder: false,
             new_note: false,
+            runbook: false,
+            passphrase: false,
+            locked: false,
+            unlock: AddPassphrase::default(),
+            last_input: Instant::now(),
             new_todo: false,
             edit_todo: false,
             tabs: TabsState::new(sticky_note.items.iter().map(|n| n.title.clone()).collect()),
             sticky_note,
-            cmd_handle: RefCell::new(Vec::default()),
-            cmd_err: String::default(),
+            jobs: Jobs::new(
+                config.max_running_cmds,
+                config.cmd_timeout_secs,
+                config.note_output_lines,
+            ),
+            activity: ActivityLog::open()?,
+            session: Session::new(),
+            activity_view: false,
+            rules,
+            toasts: Toasts::new(config.toast_ticks),
+            quiet: Quiet::new(config.quiet_hours.as_ref())?,
+            dates: Dates::new(&config)?,
+            holidays: Holidays::load(config.holidays_file.as_deref())?,
+            habit_day: Local::now().naive_local().date(),
+            modal: None,
+            pending_cmds: Vec::new(),
+            debug_overlay: false,
+            help: false,
+            smart_stale: true,
+            low_bandwidth: config.low_bandwidth,
+            dry_run: config.dry_run,
+            dictionary: None,
+            debug: DebugStats::default(),
+            animations: Animations::default(),
+            recording: None,
+            areas: Areas::default(),
+            drag: None,
+            resizing: false,
+            notes_focus: false,
+            find: None,
+            colors: None,
+            agenda: None,
+            present: None,
+            kiosk: None,
+            pty: None,
+            pty_focus: false,
+            journal_stamp: None,
+            external_edit: None,
+            deleted: RecentlyDeleted::default(),
+            restore: None,
+            last_draft: None,
+            draft_saved: Instant::now(),
+            last_enter: None,
+            remote: Fetcher::default(),
+            workers: Workers::default(),
+            publishing: None,
+            #[cfg(feature = "tokio")]
+            saving: None,
+            #[cfg(feature = "tokio")]
+            save_again: false,
+            #[cfg(feature = "sync")]
+            sync_state: SyncState::default(),
+            #[cfg(feature = "sync")]
+            syncing: None,
+            #[cfg(feature = "sync")]
+            synced_at: None,
+            #[cfg(feature = "sync")]
+            sync_error: None,
             config,
-        })
+        };
+        app.tabs.index = tab;
+        app.tabs.shown = tab;
+        for recurring in app.config.recurring.iter() {
+            recurring.validate()?;
+        }
+        app.make_recurring();
+        let today = app.habit_day;
+        for remind in app.sticky_note.items.iter_mut().filter(|n| n.habit) {
+            habit::reset(remind, today);
+        }
+        app.make_remote();
+        app.make_feeds();
+        app.assign_todo_ids();
+        app.refresh_smart_notes();
+        for bad in bad_rules {
+            app.toasts.error(bad);
+        }
+        if app.config.spellcheck {
+            app.dictionary = Dictionary::load(&app.config.spell_language);
+            if app.dictionary.is_none() {
+                app.toasts.error(format!(
+                    "No {} dictionary found, spell checking is off",
+                    app.config.spell_language
+                ));
+            }
+        }
+        Ok(app)
     }
 
     pub fn on_up(&mut self) {
-        if self.new_todo || self.edit_todo {
+        if self.activity_view {
+            self.activity.select_previous()
+        } else if self.new_todo || self.edit_todo {
             self.add_todo.previous()
-        } else if self.new_reminder || self.new_note {
+        } else if self.new_reminder || self.edit_reminder {
+            self.add_remind.previous()
+        } else if self.new_note {
             // do nothing TODO how to do this idomaticaly
+        } else if self.notes_focus {
+            self.scroll_notes(-1)
+        } else if self.current_board() {
+            self.board_select(false)
         } else if !self.sticky_note.is_empty() {
-            self.sticky_note[self.tabs.index].list.select_previous()
+            self.step_todo(false)
         }
     }
 
     pub fn on_down(&mut self) {
-        if self.new_todo || self.edit_todo {
+        if self.activity_view {
+            self.activity.select_next()
+        } else if self.new_todo || self.edit_todo {
             self.add_todo.next()
-        } else if self.new_reminder || self.new_note {
+        } else if self.new_reminder || self.edit_reminder {
+            self.add_remind.next()
+        } else if self.new_note {
             // do nothing TODO how to do this idomaticaly
+        } else if self.notes_focus {
+            self.scroll_notes(1)
+        } else if self.current_board() {
+            self.board_select(true)
         } else if !self.sticky_note.is_empty() {
-            self.sticky_note[self.tabs.index].list.select_next();
+            self.step_todo(true);
+        }
+    }
+
+    /// Selects the todo above or below, passing over hidden done todos.
+    fn step_todo(&mut self, forward: bool) {
+        let remind = &mut self.sticky_note[self.tabs.index];
+        let rows = remind.visible_rows();
+        let pos = rows.iter().position(|i| *i == remind.list.selected);
+        let next = match (pos, forward) {
+            (Some(pos), true) => rows.get(pos + 1),
+            (Some(pos), false) => pos.checked_sub(1).and_then(|pos| rows.get(pos)),
+            (None, _) => rows.first(),
+        };
+        if let Some(next) = next {
+            remind.list.selected = *next;
         }
     }
     /// TODO should any addition be reset here?
     pub fn on_right(&mut self) {
+        if self.current_board() && !self.editing() {
+            self.move_card(true);
+            return;
+        }
         self.reset_addition();
+        self.runbook = false;
         self.tabs.next();
     }
 
     /// TODO should any addition be reset here?
     pub fn on_left(&mut self) {
+        if self.current_board() && !self.editing() {
+            self.move_card(false);
+            return;
+        }
         self.reset_addition();
+        self.runbook = false;
         self.tabs.previous();
     }
 
-    fn reset_addition(&mut self) {
-        self.add_remind.title.clear();
+    /// True when the current sticky note is shown as a board.
+    pub fn current_board(&self) -> bool {
+        self.sticky_note
+            .items
+            .get(self.tabs.index)
+            .map(|n| n.board)
+            .unwrap_or(false)
+    }
 
-        self.add_todo.cmd.clear();
-        self.add_todo.task.clear();
-        self.add_todo.question_index = 0;
+    /// True while any form, the runbook or the activity log has the keyboard.
+    pub fn editing(&self) -> bool {
+        self.new_reminder
+            || self.edit_reminder
+            || self.new_todo
+            || self.edit_todo
+            || self.new_note
+            || self.runbook
+            || self.passphrase
+            || self.activity_view
     }
 
-    fn run_cmd(&self, cmd: String) {
-        self.cmd_handle.borrow_mut().push(thread::spawn(move || {
-            let cmd_args = &cmd.split_whitespace().collect::<Vec<_>>();
-            let mut cmd = Command::new(&cmd_args[0]);
-            let cmd = cmd
-                .args(&cmd_args[1..])
-                .stdout(Stdio::null())
-                .stderr(Stdio::null());
-            cmd.spawn()
-        }));
+    /// Selects the previous or next todo in the selected todo's board column.
+    fn board_select(&mut self, forward: bool) {
+        let list = &mut self.sticky_note[self.tabs.index].list;
+        let column = match list.get_selected() {
+            Some(todo) => todo.status,
+            None => return,
+        };
+        let selected = list.selected;
+        let next = if forward {
+            list.iter()
+                .enumerate()
+                .skip(selected + 1)
+                .find(|(_, t)| t.status == column)
+                .map(|(i, _)| i)
+        } else {
+            list.iter()
+                .enumerate()
+                .take(selected)
+                .filter(|(_, t)| t.status == column)
+                .last()
+                .map(|(i, _)| i)
+        };
+        if let Some(idx) = next {
+            list.selected = idx;
+        }
     }
 
-    fn add_char(&mut self, c: char) {
-        if self.new_reminder {
-            if c == '\n' {
-                self.sticky_note.items.push(Remind {
-                    title: self.add_remind.title.clone(),
-                    note: String::default(),
-                    list: ListState::default(),
-                });
-                self.tabs.titles.push(self.add_remind.title.clone());
-                self.add_remind.title.clear();
-                self.new_reminder = false;
-                return;
-            }
-            self.add_remind.title.push(c);
+    /// Moves the selected card one column to the left or right.
+    fn move_card(&mut self, right: bool) {
+        if self.current_read_only() {
             return;
-        } else if self.new_todo && !self.sticky_note.is_empty() {
-            if c == '\n' {
-                self.sticky_note[self.tabs.index].list.items.push(Todo {
-                    date: chrono::Local::now(),
-                    task: self.add_todo.task.clone(),
-                    cmd: self.add_todo.cmd.clone(),
-                    completed: false,
-                });
-                self.add_todo.task.clear();
-                self.add_todo.cmd.clear();
-                self.add_todo.question_index = 0;
-                self.new_todo = false;
-                return;
-            }
+        }
+        let column = match self.sticky_note[self.tabs.index].list.get_selected() {
+            Some(todo) => todo.status,
+            None => return,
+        };
+        let idx = column.index();
+        let target = if right {
+            Status::ALL.get(idx + 1)
+        } else {
+            idx.checked_sub(1).and_then(|i| Status::ALL.get(i))
+        };
+        let target = match target {
+            Some(target) => *target,
+            None => return,
+        };
+        self.set_status(target);
+    }
 
-            if self.add_todo.question_index == 0 {
-                self.add_todo.task.push(c)
-            } else {
-                self.add_todo.cmd.push(c)
-            }
+    /// Sets the status of the selected todo, a blocked todo can't be done.
+    fn set_status(&mut self, status: Status) {
+        if status == Status::Done && self.refuse_blocked() {
             return;
-        } else if self.edit_todo && !self.sticky_note.is_empty() {
-            if c == '\n' {
-                let idx = self.sticky_note[self.tabs.index].list.selected;
-                let todo_len = self.sticky_note[self.tabs.index].list.items.len();
-                let todo_items = &mut self.sticky_note[self.tabs.index].list.items;
-
-                todo_items.push(Todo {
-                    date: chrono::Local::now(),
-                    task: self.add_todo.task.clone(),
-                    cmd: self.add_todo.cmd.clone(),
-                    completed: false,
-                });
-                todo_items.swap(idx, todo_len);
-                todo_items.pop();
+        }
+        let mut done = false;
+        if let Some(todo) = self.sticky_note[self.tabs.index].list.get_selected_mut() {
+            done = status == Status::Done && todo.status != Status::Done;
+            todo.set_status(status);
+            todo.touch(&self.config.device_name);
+        }
+        let task = self.selected_task();
+        self.log(&format!("set {}", status.as_str().to_lowercase()), &task);
+        if done {
+            self.announce_done();
+        }
+    }
 
-                self.add_todo.task.clear();
-                self.add_todo.cmd.clear();
-                self.add_todo.question_index = 0;
-                self.new_todo = false;
-                return;
-            }
+    /// Tells the `chat_hooks` and GitHub the selected todo is done.
+    fn announce_done(&mut self) {
+        self.post_done();
+        self.close_issue();
+    }
 
-            if self.add_todo.question_index == 0 {
-                self.add_todo.task.push(c)
-            } else {
-                self.add_todo.cmd.push(c)
-            }
-            return;
-        } else if self.new_note && !self.sticky_note.is_empty() {
-            self.sticky_note[self.tabs.index].note.push(c);
-            return;
+    #[cfg(feature = "chat")]
+    fn post_done(&mut self) {
+        let remind = match self.sticky_note.items.get(self.tabs.index) {
+            Some(remind) if remind.secret.is_none() => remind,
+            _ => return,
+        };
+        let messages = match remind.list.get_selected() {
+            Some(todo) => chat::messages(&self.config.chat_hooks, &remind.title, &todo.task),
+            None => return,
+        };
+        if !messages.is_empty() {
+            self.workers.spawn("Posting to chat", move |progress| {
+                Output::Posted(chat::post_all(&messages, progress))
+            });
         }
-        if c == '\n' && !self.sticky_note.is_empty() {
-            if let Some(todo) = self.sticky_note[self.tabs.index].list.get_selected() {
-                if !todo.cmd.trim().is_empty() {
-                    self.run_cmd(todo.cmd.clone());
-                }
-            }
+    }
+
+    #[cfg(not(feature = "chat"))]
+    fn post_done(&mut self) {}
+
+    /// Closes the GitHub issue of the selected todo when `close_issues` is set.
+    #[cfg(feature = "github")]
+    fn close_issue(&mut self) {
+        let token_env = match &self.config.github {
+            Some(github) if github.close_issues => github.token_env.clone(),
+            _ => return,
+        };
+        let issue = match self.sticky_note.items.get(self.tabs.index) {
+            Some(remind) if remind.secret.is_none() => remind
+                .list
+                .get_selected()
+                .and_then(|todo| todo.issue.clone()),
+            _ => return,
+        };
+        if let Some(issue) = issue {
+            self.workers.spawn(format!("Closing {}", issue), move |_| {
+                Output::IssueClosed(github::close(&issue, &token_env).map(|_| issue))
+            });
         }
     }
 
-    pub fn on_key(&mut self, c: char) {
-        self.add_char(c)
+    #[cfg(not(feature = "github"))]
+    fn close_issue(&mut self) {}
+
+    #[cfg(feature = "links")]
+    fn fetch_link_title(&mut self, id: u64, task: &str) {
+        let url = match links::link(task) {
+            Some(url) if self.config.link_titles => url.to_string(),
+            _ => return,
+        };
+        self.workers.spawn(format!("Fetching {}", url), move |_| {
+            let title = links::get_title(&url);
+            Output::Titled(Titled { id, url, title })
+        });
     }
 
-    pub fn on_backspace(&mut self) {
-        if self.new_reminder {
-            self.add_remind.title.pop();
-        } else if self.new_todo || self.edit_todo {
-            if self.add_todo.question_index == 0 {
-                self.add_todo.task.pop();
-            } else {
-                self.add_todo.cmd.pop();
-            }
-        } else if self.new_note && !self.sticky_note.is_empty() {
-            self.sticky_note[self.tabs.index].note.pop();
-        } else if !self.sticky_note.is_empty() {
-            if let Some(todo) = self.sticky_note[self.tabs.index].list.get_selected() {
-                let flag = todo.completed;
+    #[cfg(not(feature = "links"))]
+    fn fetch_link_title(&mut self, _id: u64, _task: &str) {}
 
-                self.sticky_note[self.tabs.index]
-                    .list
-                    .get_selected_mut()
-                    .unwrap()
-                    .completed = !flag;
+    /// Titles the link todo whose page title arrived, unless it was changed meanwhile.
+    #[cfg(feature = "links")]
+    fn on_link_title(&mut self, titled: Titled) {
+        let title = match titled.title {
+            Ok(title) => title,
+            Err(e) => {
+                self.toasts
+                    .error(format!("No title for {}: {}", titled.url, e));
+                return;
             }
+        };
+        let (tab, idx) = match self.find_todo(titled.id) {
+            Some(found) => found,
+            None => return,
+        };
+        let remind = &mut self.sticky_note[tab];
+        if remind.list[idx].task.trim() != titled.url {
+            return;
         }
+        remind.list[idx].task = title.clone();
+        if !remind.attachments.contains(&titled.url) {
+            remind.attachments.push(titled.url.clone());
+        }
+        remind.touch();
+        self.log("link title", &title);
     }
 
-    pub fn on_delete(&mut self) {
-        if self.new_reminder || self.new_todo {
-            self.reset_addition();
-        } else if self.new_note && !self.sticky_note.is_empty() {
-            self.sticky_note[self.tabs.index].note.pop();
-        } else if !self.sticky_note.is_empty() {
-            let idx = self.sticky_note[self.tabs.index].list.selected;
-            if idx > 0 {
-                self.sticky_note[self.tabs.index].list.selected -= 1;
+    /// Hands what a background task made to the feature that started it.
+    pub fn on_task(&mut self, event: TaskEvent) {
+        let output = match self.workers.on_event(event) {
+            Some(output) => output,
+            None => return,
+        };
+        self.smart_stale = true;
+        match output {
+            Output::Fetched(fetched) => self.on_fetched(fetched),
+            Output::FeedFetched(fetched) => self.on_feed_fetched(fetched),
+            Output::Published(link) => self.on_published(link),
+            #[cfg(feature = "links")]
+            Output::Titled(titled) => self.on_link_title(titled),
+            #[cfg(feature = "chat")]
+            Output::Posted(failed) => {
+                for e in failed {
+                    self.toasts.error(format!("Posting to chat failed: {}", e));
+                }
             }
-            if self.sticky_note[self.tabs.index].list.is_empty() {
-                return;
+            #[cfg(feature = "github")]
+            Output::IssueClosed(closed) => match closed {
+                Ok(issue) => self.toasts.info(format!("Closed {}", issue)),
+                Err(e) => self
+                    .toasts
+                    .error(format!("Closing the issue failed: {}", e)),
+            },
+            #[cfg(feature = "tokio")]
+            Output::Saved(saved) => {
+                self.saving = None;
+                self.on_saved(saved);
+                if std::mem::take(&mut self.save_again) {
+                    self.write_db();
+                }
             }
-            self.sticky_note[self.tabs.index].list.items.remove(idx);
+            #[cfg(feature = "sync")]
+            Output::Synced(synced) => self.on_synced(synced),
         }
     }
 
-    pub fn reset_new_flag(&mut self) {
-        self.new_note = false;
-        self.new_reminder = false;
-        self.new_todo = false;
-        self.edit_todo = false;
-    }
-
-    pub fn on_ctrl_key(&mut self, c: char) {
-        match c {
-            'q' => {
-                self.should_quit = true;
-                for hndl in self.cmd_handle.get_mut().drain(..) {
-                    if let Ok(Ok(mut thread)) = hndl.join() {
-                        let _ = thread.kill();
+    /// Pushes the changes to the shared sticky notes and pulls everyone
+    /// else's, every `every_secs`.
+    #[cfg(feature = "sync")]
+    fn sync_notes(&mut self) {
+        let server = match &self.config.sync {
+            Some(server) => server.clone(),
+            None => return,
+        };
+        let every = Duration::from_secs(server.every_secs);
+        if self.syncing.is_some() || self.synced_at.is_some_and(|at| at.elapsed() < every) {
+            return;
+        }
+        let device = &self.config.device_name;
+        let mut notes = Vec::new();
+        for title in server.notes.iter() {
+            // secret notes never leave the machine unencrypted
+            let remind = self
+                .sticky_note
+                .iter()
+                .find(|n| n.title == *title && n.secret.is_none() && !n.is_view());
+            if let Some(remind) = remind {
+                let channel = self.sync_state.channels.entry(title.clone()).or_default();
+                notes.push((title.clone(), channel.diff(remind, device), channel.since()));
+            }
+        }
+        if notes.is_empty() {
+            return;
+        }
+        self.synced_at = Some(Instant::now());
+        let id = self.workers.spawn("Syncing", move |_| {
+            Output::Synced(
+                notes
+                    .into_iter()
+                    .map(|(title, ops, since)| sync::sync(&server, title, ops, since))
+                    .collect(),
+            )
+        });
+        self.syncing = Some(id);
+    }
+
+    #[cfg(not(feature = "sync"))]
+    fn sync_notes(&mut self) {}
+
+    /// Merges what the other devices changed, their sticky notes show as unread.
+    #[cfg(feature = "sync")]
+    fn on_synced(&mut self, synced: Vec<Synced>) {
+        self.syncing = None;
+        let device = self.config.device_name.clone();
+        let mut error = None;
+        for Synced {
+            title,
+            pushed,
+            pulled,
+        } in synced
+        {
+            let next_id = self.sticky_note.next_todo_id();
+            let channel = match self.sync_state.channels.get_mut(&title) {
+                Some(channel) => channel,
+                None => continue,
+            };
+            channel.pushed(&pushed);
+            let ops = match pulled {
+                Ok(ops) => ops,
+                Err(e) => {
+                    error = Some(format!("Syncing {} failed: {}", title, e));
+                    continue;
+                }
+            };
+            let remind = match self.sticky_note.items.iter_mut().find(|n| n.title == title) {
+                Some(remind) => remind,
+                None => continue,
+            };
+            let from = channel.merge(remind, ops, &device, next_id);
+            if !from.is_empty() {
+                remind.touch();
+                let changed = format!("{} by {}", title, from.join(", "));
+                self.toasts.info(format!("Changed {}", changed));
+                self.log("sync", &changed);
+            }
+        }
+        if let Err(e) = self.sync_state.save() {
+            tracing::warn!("saving the sync state failed: {}", e);
+        }
+        match error {
+            Some(e) if self.sync_error.as_ref() != Some(&e) => {
+                self.toasts.error(e.clone());
+                self.sync_error = Some(e);
+            }
+            Some(e) => tracing::warn!("{}", e),
+            None => self.sync_error = None,
+        }
+    }
+
+    /// Moves the selected todo on to the next status, skipping done while it is blocked.
+    fn cycle_status(&mut self) {
+        let status = match self.sticky_note[self.tabs.index].list.get_selected() {
+            Some(todo) => todo.status,
+            None => return,
+        };
+        let mut next = status.next();
+        if next == Status::Done && self.selected_blocked() {
+            next = next.next();
+        }
+        self.set_status(next);
+    }
+
+    /// True when the current sticky note is a secret that hasn't been unlocked.
+    pub fn current_locked(&self) -> bool {
+        self.sticky_note
+            .items
+            .get(self.tabs.index)
+            .map(Remind::is_locked)
+            .unwrap_or(false)
+    }
+
+    /// True when the current sticky note can't be changed, it is either a
+    /// locked secret, a smart or a remote sticky note.
+    pub fn current_read_only(&self) -> bool {
+        self.sticky_note
+            .items
+            .get(self.tabs.index)
+            .map(|n| n.is_locked() || n.is_view())
+            .unwrap_or(false)
+    }
+
+    /// Fills every smart sticky note with the todos matching its query.
+    pub fn refresh_smart_notes(&mut self) {
+        self.smart_stale = false;
+        let smart = self
+            .sticky_note
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| n.query.as_ref().map(|q| (i, Query::parse(q))))
+            .collect::<Vec<_>>();
+
+        for (idx, query) in smart {
+            let items = self
+                .sticky_note
+                .iter()
+                .filter(|n| !n.is_view() && n.secret.is_none())
+                .flat_map(|n| {
+                    let query = &query;
+                    n.list
+                        .iter()
+                        .filter(move |t| query.matches(n, t))
+                        .map(move |t| Todo {
+                            task: format!("{} ({})", t.task, n.title),
+                            cmd: n.command_for(t).unwrap_or_default(),
+                            ..t.clone()
+                        })
+                })
+                .collect::<Vec<_>>();
+
+            let list = &mut self.sticky_note[idx].list;
+            list.selected = list.selected.min(items.len().saturating_sub(1));
+            list.items = items;
+        }
+    }
+
+    /// Gives every todo saved before todos had ids one.
+    fn assign_todo_ids(&mut self) {
+        let mut next = self.sticky_note.next_todo_id();
+        for remind in self.sticky_note.items.iter_mut() {
+            for todo in remind.list.items.iter_mut().filter(|t| t.id == 0) {
+                todo.id = next;
+                next += 1;
+            }
+        }
+    }
+
+    /// The sticky note and position of the todo with `id`, smart and remote
+    /// sticky notes only hold copies so they are skipped.
+    fn find_todo(&self, id: u64) -> Option<(usize, usize)> {
+        self.sticky_note
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !n.is_view())
+            .find_map(|(i, n)| n.list.iter().position(|t| t.id == id).map(|j| (i, j)))
+    }
+
+    /// The ids of every todo that isn't closed, a todo blocked by one of them can't be done.
+    pub fn open_ids(&self) -> HashSet<u64> {
+        self.sticky_note
+            .iter()
+            .filter(|n| !n.is_view())
+            .flat_map(|n| {
+                n.list
+                    .iter()
+                    .filter(|t| !t.status.is_closed())
+                    .map(|t| t.id)
+            })
+            .collect()
+    }
+
+    /// True when the selected todo waits on one that isn't closed.
+    fn selected_blocked(&self) -> bool {
+        let blocker = self
+            .sticky_note
+            .items
+            .get(self.tabs.index)
+            .and_then(|n| n.list.get_selected())
+            .and_then(|t| t.blocked_by);
+        match blocker {
+            Some(id) => self.open_ids().contains(&id),
+            None => false,
+        }
+    }
+
+    /// True, with a toast saying why, when the selected todo can't be done yet.
+    fn refuse_blocked(&mut self) -> bool {
+        let blocked = self.selected_blocked();
+        if blocked {
+            let blocker = self
+                .sticky_note
+                .items
+                .get(self.tabs.index)
+                .and_then(|n| n.list.get_selected())
+                .and_then(|t| t.blocked_by)
+                .unwrap_or_default();
+            self.toasts.error(format!("Blocked by #{}", blocker));
+        }
+        blocked
+    }
+
+    /// Selects the todo blocking the selected one, switching sticky notes if needed.
+    fn goto_blocker(&mut self) {
+        let blocker = self
+            .sticky_note
+            .items
+            .get(self.tabs.index)
+            .and_then(|n| n.list.get_selected())
+            .and_then(|t| t.blocked_by)
+            .and_then(|id| self.find_todo(id));
+        if let Some((tab, idx)) = blocker {
+            self.reset_new_flag();
+            self.reset_addition();
+            self.tabs.index = tab;
+            self.sticky_note[tab].list.selected = idx;
+        }
+    }
+
+    /// The sticky note and index of the unfinished todo due soonest from now on.
+    pub fn next_due(&self) -> Option<(usize, usize)> {
+        let now = Local::now().timestamp();
+        self.sticky_note
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, note)| !note.is_smart())
+            .flat_map(|(tab, note)| {
+                note.list
+                    .items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| !t.status.is_closed())
+                    .filter_map(move |(idx, t)| t.due.filter(|d| *d >= now).map(|d| (d, tab, idx)))
+            })
+            .min()
+            .map(|(_, tab, idx)| (tab, idx))
+    }
+
+    fn goto_due(&mut self) {
+        if let Some((tab, idx)) = self.next_due() {
+            self.reset_new_flag();
+            self.reset_addition();
+            self.tabs.index = tab;
+            self.sticky_note[tab].list.selected = idx;
+        }
+    }
+
+    /// Writes the current sticky note out as a checklist for `main` to open in `$EDITOR`.
+    fn start_external_edit(&mut self) {
+        let remind = match self.sticky_note.items.get(self.tabs.index) {
+            Some(remind) if remind.secret.is_some() => {
+                // the editor would get the todos as plain text
+                self.toasts
+                    .error("Secret sticky notes can't be edited in an editor");
+                return;
+            }
+            Some(remind) => remind,
+            None => return,
+        };
+        let written = paths::private_dir("edit").and_then(|dir| {
+            let path = dir.join(format!("{}.md", std::process::id()));
+            // left over from a `forget` that died with the same pid
+            let _ = fs::remove_file(&path);
+            paths::create_private(&path)?.write_all(checklist::dump(remind).as_bytes())?;
+            Ok(path)
+        });
+        match written {
+            Ok(path) => self.external_edit = Some(path),
+            Err(e) => {
+                self.toasts.error(format!("Editing failed: {}", e));
+                self.log("edit in editor failed", &e.to_string());
+            }
+        }
+    }
+
+    /// Reads the checklist back after the editor exits, `edited` is how that went.
+    pub fn finish_external_edit(&mut self, edited: io::Result<()>) {
+        let path = match self.external_edit.take() {
+            Some(path) => path,
+            None => return,
+        };
+        self.smart_stale = true;
+        let text = edited.and_then(|()| fs::read_to_string(&path));
+        let _ = fs::remove_file(&path);
+        match text {
+            Ok(text) => self.apply_checklist(checklist::parse(&text)),
+            Err(e) => {
+                self.toasts.error(format!("Editing failed: {}", e));
+                self.log("edit in editor failed", &e.to_string());
+            }
+        }
+    }
+
+    /// Replaces the current sticky note's todos with `lines`, lines with the id of
+    /// an existing todo update it and the todos without a line are removed.
+    fn apply_checklist(&mut self, lines: Vec<checklist::Line>) {
+        let mut next_id = self.sticky_note.next_todo_id();
+        let device = self.config.device_name.clone();
+        let remind = &mut self.sticky_note[self.tabs.index];
+        let before = remind.list.len();
+
+        let mut added = 0;
+        // a copied line becomes a new todo instead of a second todo with the same id
+        let mut seen = HashSet::new();
+        // statuses are set like they are from the list once the todos are in place
+        let mut changed = Vec::new();
+        let items = lines
+            .into_iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                let existing = line
+                    .id
+                    .filter(|id| seen.insert(*id))
+                    .and_then(|id| remind.list.iter().find(|t| t.id == id).cloned());
+                let todo = match existing {
+                    Some(mut todo) => {
+                        if todo.task != line.task {
+                            todo.touch(&device);
+                        }
+                        todo.task = line.task;
+                        todo
+                    }
+                    None => {
+                        added += 1;
+                        next_id += 1;
+                        Todo {
+                            created_on: Some(device.clone()),
+                            edited: Some(Edited {
+                                device: device.clone(),
+                                at: Local::now().timestamp(),
+                            }),
+                            ..Todo::new(next_id - 1, line.task)
+                        }
+                    }
+                };
+                if todo.status != line.status {
+                    changed.push((idx, line.status));
+                }
+                todo
+            })
+            .collect::<Vec<_>>();
+
+        let removed = (before + added).saturating_sub(items.len());
+        let selected = remind.list.selected.min(items.len().saturating_sub(1));
+        remind.list.items = items;
+        // a blocked todo stays open and finished ones are announced
+        for (idx, status) in changed {
+            self.sticky_note[self.tabs.index].list.selected = idx;
+            self.set_status(status);
+        }
+        self.sticky_note[self.tabs.index].list.selected = selected;
+        let detail = format!("{} added, {} removed", added, removed);
+        self.toasts.info(detail.clone());
+        self.log("edit in editor", &detail);
+    }
+
+    fn submit_passphrase(&mut self) {
+        let locked = match self.sticky_note.items.get(self.tabs.index) {
+            Some(remind) => remind.is_locked(),
+            None => return,
+        };
+        // a typo in a new passphrase would lock the note away for good
+        if !locked && !self.add_passphrase.confirming {
+            self.add_passphrase.confirming = true;
+            return;
+        }
+        if !locked && self.add_passphrase.pass != self.add_passphrase.confirm {
+            self.add_passphrase = AddPassphrase {
+                error: Some("the passphrases don't match".into()),
+                ..AddPassphrase::default()
+            };
+            return;
+        }
+        let pass = std::mem::take(&mut self.add_passphrase.pass);
+        self.add_passphrase.confirm.clear();
+        self.add_passphrase.confirming = false;
+        if let Some(remind) = self.sticky_note.items.get_mut(self.tabs.index) {
+            let (action, res) = if remind.is_locked() {
+                ("unlock", remind.unlock(&pass))
+            } else {
+                ("make secret", remind.make_secret(&pass))
+            };
+            match res {
+                Ok(()) => {
+                    self.add_passphrase.error = None;
+                    self.passphrase = false;
+                    self.log(action, "");
+                }
+                Err(e) => self.add_passphrase.error = Some(e.to_string()),
+            }
+        }
+    }
+
+    /// Records `action` on the current sticky note in the activity log, the text
+    /// of secret sticky notes is never written out.
+    fn log(&mut self, action: &str, text: &str) {
+        if !UNCHANGED.contains(&action) {
+            if let Some(remind) = self.sticky_note.items.get_mut(self.tabs.index) {
+                remind.touch();
+            }
+        }
+        let detail = match self.sticky_note.items.get(self.tabs.index) {
+            Some(remind) if remind.secret.is_some() => format!("{}: <secret>", remind.title),
+            Some(remind) if text.is_empty() => remind.title.clone(),
+            Some(remind) => format!("{}: {}", remind.title, text),
+            None => text.to_string(),
+        };
+        self.activity.record(action, detail);
+    }
+
+    fn selected_task(&self) -> String {
+        self.sticky_note
+            .items
+            .get(self.tabs.index)
+            .and_then(|n| n.list.get_selected().map(|t| t.task.clone()))
+            .unwrap_or_default()
+    }
+
+    fn reset_addition(&mut self) {
+        self.add_remind.title.clear();
+        self.add_remind.cmd.clear();
+        self.add_remind.query.clear();
+        self.add_remind.wip_limit.clear();
+        self.add_remind.smart = false;
+        self.add_remind.question_index = 0;
+
+        self.add_todo.clear();
+
+        self.add_passphrase = AddPassphrase::default();
+    }
+
+    fn open_attachment(&mut self, tab_idx: usize, idx: usize) {
+        let path = match self
+            .sticky_note
+            .items
+            .get(tab_idx)
+            .and_then(|n| n.attachments.get(idx))
+        {
+            Some(path) => path.clone(),
+            None => return,
+        };
+        if !attach::exists(&path) {
+            self.toasts.error(format!("{} doesn't exist", path));
+        } else if let Err(e) = attach::open(&self.config.attachment_opener, &path) {
+            self.toasts.error(format!("Opening {} failed: {}", path, e));
+        }
+    }
+
+    /// Picks one of the current sticky note's attachments, or takes the only one.
+    fn pick_attachment(&mut self, title: &str, action: ModalAction) -> Option<usize> {
+        let remind = self.sticky_note.items.get(self.tabs.index)?;
+        match remind.attachments.len() {
+            0 => {
+                self.toasts.info("No attachments");
+                None
+            }
+            1 => Some(0),
+            _ => {
+                self.modal = Some(Modal::pick(title, remind.attachments.clone(), action));
+                None
+            }
+        }
+    }
+
+    /// Starts a new entry with the time in a journal sticky note.
+    fn stamp_journal(&mut self) {
+        let tab = self.tabs.index;
+        if !self.sticky_note.items.get(tab).is_some_and(|n| n.journal) {
+            return;
+        }
+        let time = self.journal_time();
+        let remind = &mut self.sticky_note[tab];
+        let before = remind.note.len();
+        remind
+            .note
+            .push_str(&format!("{}{}\n", entry_sep(&remind.note), time));
+        self.journal_stamp = Some((tab, before, remind.note.len()));
+    }
+
+    /// Now in the `journal_format`.
+    fn journal_time(&mut self) -> String {
+        // a bad format is an error from `Display`, which `format!` would panic on
+        let mut time = String::new();
+        if write!(time, "{}", Local::now().format(&self.config.journal_format)).is_err() {
+            time = Local::now().format("%Y-%m-%d %H:%M").to_string();
+            self.toasts
+                .error(format!("Bad journal_format {}", self.config.journal_format));
+        }
+        time
+    }
+
+    /// Takes the time back out of a journal entry nothing was written in.
+    fn unstamp_journal(&mut self) {
+        if let Some((tab, before, after)) = self.journal_stamp.take() {
+            if let Some(remind) = self.sticky_note.items.get_mut(tab) {
+                if remind.note.len() == after {
+                    remind.note.truncate(before);
+                }
+            }
+        }
+    }
+
+    /// Finishes a last note line like `= 23*4.5` as `23*4.5 = 103.5` and
+    /// copies the result.
+    fn calculate_line(&mut self) {
+        let note = &mut self.sticky_note[self.tabs.index].note;
+        let start = note.rfind('\n').map_or(0, |i| i + 1);
+        let expr = match note[start..].trim().strip_prefix('=') {
+            Some(expr) => expr.trim().to_string(),
+            None => return,
+        };
+        match calc::eval(&expr) {
+            Ok(value) => {
+                let result = calc::format(value);
+                note.replace_range(start.., &format!("{} = {}", expr, result));
+                if let Err(e) = clipboard::copy(&result) {
+                    tracing::warn!("copying {} failed: {}", result, e);
+                }
+            }
+            Err(e) => self.toasts.error(format!("{}: {}", expr, e)),
+        }
+    }
+
+    /// Queues the command of the todo with `id` and counts the run.
+    /// With `dry_run` on it only says what would run, commands not in the
+    /// `cmd_allowlist` are refused or have to be confirmed first.
+    fn run_cmd(&mut self, id: u64, label: String, cmd: String) {
+        if self.kiosk.is_some() {
+            tracing::info!("kiosk, not running {}", cmd);
+            return;
+        }
+        if self.dry_run {
+            self.toasts.info(format!("Would run: {}", cmd));
+            self.log("dry run", &cmd);
+            return;
+        }
+        if !jobs::is_allowed(&self.config.cmd_allowlist, &cmd) {
+            if self.config.refuse_unlisted_cmds {
+                self.toasts
+                    .error(format!("Not in the cmd_allowlist: {}", cmd));
+                self.log("refuse command", &cmd);
+            } else {
+                self.pending_cmds.push((id, label, cmd));
+                self.confirm_next_cmd();
+            }
+            return;
+        }
+        self.start_cmd(id, label, cmd);
+    }
+
+    /// Asks about the first command waiting on the allowlist.
+    fn confirm_next_cmd(&mut self) {
+        if self.modal.is_some() {
+            return;
+        }
+        if let Some((_, _, cmd)) = self.pending_cmds.first() {
+            self.modal = Some(Modal::confirm(
+                "Run command?".to_string(),
+                format!("{} isn't in the cmd_allowlist", cmd),
+                ModalAction::RunCommand,
+            ));
+        }
+    }
+
+    fn start_cmd(&mut self, id: u64, label: String, cmd: String) {
+        let interactive = jobs::is_interactive(&self.config.pty_cmds, &cmd);
+        if interactive && self.pty.as_ref().is_some_and(|pty| pty.exited.is_none()) {
+            self.toasts
+                .error("A command is already running in the terminal pane");
+            return;
+        }
+        if let Some((note, idx)) = self.find_todo(id) {
+            let todo = &mut self.sticky_note[note].list.items[idx];
+            todo.runs += 1;
+            todo.last_run = Some(Local::now().timestamp());
+        }
+        self.log("run command", &cmd);
+        if interactive {
+            self.open_pty(&cmd);
+        } else {
+            let report = self.find_todo(id).map_or(Report::Toast, |(note, idx)| {
+                self.sticky_note[note].list[idx].report
+            });
+            self.jobs.push(id, label, cmd, report);
+        }
+    }
+
+    fn open_pty(&mut self, cmd: &str) {
+        let (cols, rows) = self.pty_size();
+        match Pty::spawn(cmd, cols, rows) {
+            Ok(pty) => {
+                self.pty = Some(pty);
+                self.pty_focus = true;
+            }
+            Err(e) => self.toasts.error(format!("{}: {}", cmd, e)),
+        }
+    }
+
+    /// The inside of the terminal pane, a guess before it's first drawn.
+    fn pty_size(&self) -> (u16, u16) {
+        match self.areas.pty {
+            area if area.width > 2 && area.height > 2 => (area.width - 2, area.height - 2),
+            _ => (80, 24),
+        }
+    }
+
+    /// F10 gives the keys back to `forget`, or closes the pane once the
+    /// command is done. The exit key still quits.
+    fn on_pty_key(&mut self, key: Key) {
+        let pty = match self.pty.as_mut() {
+            Some(pty) => pty,
+            None => return,
+        };
+        match key {
+            Key::F(10) if pty.exited.is_some() => {
+                self.pty = None;
+                self.pty_focus = false;
+            }
+            Key::F(10) => self.pty_focus = false,
+            Key::Ctrl(c) if c == self.config.exit_key_char_ctrl => self.quit(),
+            key => {
+                if let Err(e) = pty.send_key(key) {
+                    self.toasts.error(format!("{}: {}", pty.cmd, e));
+                }
+            }
+        }
+    }
+
+    /// Takes the pane's output and says when its command exits.
+    fn poll_pty(&mut self) {
+        let size = self.pty_size();
+        let pty = match self.pty.as_mut() {
+            Some(pty) => pty,
+            None => return,
+        };
+        let running = pty.exited.is_none();
+        pty.poll();
+        pty.resize(size.0, size.1);
+        if let (true, Some(status)) = (running, pty.exited) {
+            let text = format!("{}: {}", pty.cmd, status);
+            if status.success() {
+                self.toasts.info(text);
+            } else {
+                self.toasts.error(text);
+            }
+        }
+    }
+
+    /// Queues the commands of every incomplete todo of the current sticky note,
+    /// they run in order `max_running_cmds` at a time.
+    fn run_all_cmds(&mut self) {
+        // remote notes have no commands, fetch them again instead
+        if let Some(url) = self
+            .sticky_note
+            .items
+            .get(self.tabs.index)
+            .and_then(|n| n.url.clone())
+        {
+            self.toasts.info("Fetching");
+            self.fetch_remote(&url);
+            return;
+        }
+        // and feeds are fetched again too
+        if let Some(url) = self
+            .sticky_note
+            .items
+            .get(self.tabs.index)
+            .and_then(|n| n.feed.clone())
+        {
+            self.toasts.info("Fetching");
+            self.fetch_feed(&url);
+            return;
+        }
+        if let Some(remind) = self.sticky_note.items.get(self.tabs.index) {
+            let cmds = remind
+                .list
+                .iter()
+                .filter(|t| !t.status.is_closed())
+                .filter_map(|t| remind.command_for(t).map(|cmd| (t.id, t.task.clone(), cmd)))
+                .collect::<Vec<_>>();
+            for (id, label, cmd) in cmds {
+                self.run_cmd(id, label, cmd);
+            }
+        }
+    }
+
+    /// Starts the runbook at the first incomplete todo of the current sticky note,
+    /// returns false if there is nothing left to do.
+    fn start_runbook(&mut self) -> bool {
+        if let Some(remind) = self.sticky_note.items.get_mut(self.tabs.index) {
+            let first = remind.list.iter().position(|t| !t.status.is_closed());
+            if let Some(idx) = first {
+                remind.list.selected = idx;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Moves the runbook to the next incomplete todo after the current step,
+    /// the runbook ends when there are none.
+    fn advance_runbook(&mut self) {
+        let remind = &mut self.sticky_note[self.tabs.index];
+        let next = remind
+            .list
+            .iter()
+            .enumerate()
+            .skip(remind.list.selected + 1)
+            .find(|(_, t)| !t.status.is_closed())
+            .map(|(i, _)| i);
+        match next {
+            Some(idx) => remind.list.selected = idx,
+            None => self.runbook = false,
+        }
+    }
+
+    fn runbook_key(&mut self, c: char) {
+        match c {
+            // confirm the step is done
+            '\n' | 'y' => {
+                if self.refuse_blocked() {
+                    return;
+                }
+                if let Some(todo) = self.sticky_note[self.tabs.index].list.get_selected_mut() {
+                    todo.set_status(Status::Done);
+                    todo.touch(&self.config.device_name);
+                }
+                let task = self.selected_task();
+                self.log("complete todo", &task);
+                self.announce_done();
+                self.advance_runbook();
+            }
+            // run the step's command
+            'r' => {
+                let remind = &self.sticky_note[self.tabs.index];
+                if let Some(todo) = remind.list.get_selected() {
+                    if let Some(cmd) = remind.command_for(todo) {
+                        self.run_cmd(todo.id, todo.task.clone(), cmd);
+                    }
+                }
+            }
+            // skip the step, leaving it incomplete
+            's' | 'n' => self.advance_runbook(),
+            _ => {}
+        }
+    }
+
+    fn add_char(&mut self, c: char) {
+        if self.activity_view {
+            self.activity.filter.push(c);
+            self.activity.selected = 0;
+            return;
+        }
+        if self.passphrase {
+            self.add_passphrase.focused_mut().push(c);
+            return;
+        }
+        if !self.editing()
+            && (c == self.config.narrow_list_char || c == self.config.widen_list_char)
+        {
+            let step = if c == self.config.widen_list_char {
+                RESIZE_STEP
+            } else {
+                -RESIZE_STEP
+            };
+            self.set_split(self.split() as i16 + step);
+            self.save_config();
+            return;
+        }
+        if self.current_read_only() && !(self.new_reminder || self.edit_reminder) {
+            return;
+        }
+        if self.runbook {
+            if !self.sticky_note.is_empty() {
+                self.runbook_key(c);
+            }
+        } else if self.new_reminder || self.edit_reminder {
+            self.add_remind.focused_mut().push(c);
+        } else if self.new_todo || self.edit_todo {
+            self.add_todo.focused_mut().push(c);
+        } else if self.new_note {
+            if let Some(remind) = self.sticky_note.items.get_mut(self.tabs.index) {
+                remind.note.push(c);
+                remind.note_changed();
+            }
+        } else if c == '\t' && !self.sticky_note.is_empty() {
+            self.notes_focus = !self.notes_focus;
+        }
+    }
+
+    /// Enter finishes whatever has the keyboard, it only ever adds to or
+    /// submits the form open. Commands run from the `RunSelected` action.
+    fn on_enter(&mut self) {
+        if self.activity_view {
+            return;
+        }
+        if self.passphrase {
+            self.submit_passphrase();
+            return;
+        }
+        if self.current_read_only() && !(self.new_reminder || self.edit_reminder) {
+            // smart sticky notes can't be changed, their todos still run
+            if self.sticky_note[self.tabs.index].is_smart() && !self.repeated_enter() {
+                self.run_selected();
+            }
+            return;
+        }
+        if self.sticky_note.is_empty() && !self.new_reminder {
+            return;
+        }
+        if !self.new_note && self.repeated_enter() {
+            return;
+        }
+        if self.runbook {
+            self.runbook_key('\n');
+        } else if self.new_reminder {
+            self.submit_new_reminder();
+        } else if self.edit_reminder {
+            self.submit_edit_reminder();
+        } else if self.new_todo {
+            self.submit_new_todo();
+        } else if self.edit_todo {
+            self.submit_edit_todo();
+        } else if self.new_note {
+            self.calculate_line();
+            self.add_char('\n');
+        } else {
+            self.run_action(Action::RunSelected);
+        }
+    }
+
+    /// A held Enter or one a slow connection delivers twice arrives as a
+    /// burst, only the first of it saves or runs anything.
+    fn repeated_enter(&mut self) -> bool {
+        let window = Duration::from_millis(self.config.enter_debounce_ms);
+        let now = Instant::now();
+        // every Enter of the burst pushes the window on, a held key fires once
+        let repeated = self.last_enter.is_some_and(|last| now - last < window);
+        self.last_enter = Some(now);
+        repeated
+    }
+
+    fn submit_new_reminder(&mut self) {
+        let title = if self.add_remind.title.trim().is_empty() {
+            &self.config.default_title
+        } else {
+            &self.add_remind.title
+        };
+        let title = title::expand(title, Local::now(), &self.dates);
+        self.sticky_note.items.push(Remind {
+            default_cmd: self.add_remind.cmd.clone(),
+            wip_limit: self.add_remind.wip_limit(),
+            instance: None,
+            query: if self.add_remind.smart {
+                Some(self.add_remind.query.clone())
+            } else {
+                None
+            },
+            ..Remind::new(title.clone())
+        });
+        self.tabs.titles.push(title.clone());
+        self.activity.record("add sticky note", title);
+        self.reset_addition();
+        self.new_reminder = false;
+    }
+
+    fn submit_edit_reminder(&mut self) {
+        let title = title::expand(&self.add_remind.title, Local::now(), &self.dates);
+        let remind = &mut self.sticky_note[self.tabs.index];
+        let old_title = std::mem::replace(&mut remind.title, title.clone());
+        if remind.is_smart() {
+            remind.query = Some(self.add_remind.query.clone());
+        } else {
+            remind.default_cmd = self.add_remind.cmd.clone();
+        }
+        remind.wip_limit = self.add_remind.wip_limit();
+        self.tabs.titles[self.tabs.index] = title;
+        self.log("edit sticky note", &format!("was {}", old_title));
+        self.reset_addition();
+        self.edit_reminder = false;
+    }
+
+    fn submit_new_todo(&mut self) {
+        let id = self.sticky_note.next_todo_id();
+        self.sticky_note[self.tabs.index].list.items.push(Todo {
+            cmd: self.add_todo.cmd.clone(),
+            blocked_by: self.add_todo.blocker(),
+            estimate: Estimate::parse(&self.add_todo.estimate),
+            due: due::parse(&self.add_todo.due, Local::now(), &self.dates).map(|d| d.timestamp()),
+            created_on: Some(self.config.device_name.clone()),
+            edited: Some(Edited {
+                device: self.config.device_name.clone(),
+                at: Local::now().timestamp(),
+            }),
+            report: Report::parse(&self.add_todo.report).unwrap_or_default(),
+            ..Todo::new(id, self.add_todo.task.clone())
+        });
+        let task = self.add_todo.task.clone();
+        self.log("add todo", &task);
+        self.fetch_link_title(id, &task);
+        self.add_todo.clear();
+        self.new_todo = false;
+    }
+
+    fn submit_edit_todo(&mut self) {
+        let id = self.add_todo.editing;
+        let found = id.and_then(|id| self.find_todo(id).map(|(tab, _)| (tab, id)));
+        let (tab, id) = match found {
+            Some(found) => found,
+            None => {
+                self.toasts.error("The todo being edited is gone");
+                self.add_todo.clear();
+                self.edit_todo = false;
+                return;
+            }
+        };
+        self.tabs.index = tab;
+        self.sticky_note.selected = tab;
+        let device = self.config.device_name.clone();
+        let task = self.sticky_note[tab]
+            .list
+            .edit(id, &self.add_todo, Local::now(), &self.dates, &device)
+            .map(|t| t.task.clone())
+            .unwrap_or_default();
+        self.log("edit todo", &task);
+
+        self.add_todo.clear();
+        self.edit_todo = false;
+    }
+
+    /// Runs the selected todo's command, only from the list itself.
+    fn run_selected(&mut self) {
+        if self.editing() || self.sticky_note.is_empty() || self.current_locked() {
+            return;
+        }
+        let mut remind = &self.sticky_note[self.tabs.index];
+        let mut todo = match remind.list.get_selected() {
+            Some(todo) => todo,
+            None => return,
+        };
+        // a smart sticky note shows a copy, the note it's on has the command
+        if remind.is_smart() {
+            let (tab, idx) = match self.find_todo(todo.id) {
+                Some(found) => found,
+                None => return,
+            };
+            remind = &self.sticky_note[tab];
+            todo = &remind.list[idx];
+        }
+        if let Some(cmd) = remind.command_for(todo) {
+            self.run_cmd(todo.id, todo.task.clone(), cmd);
+        }
+    }
+
+    pub fn on_key(&mut self, c: char) {
+        self.add_char(c)
+    }
+
+    pub fn on_backspace(&mut self) {
+        if self.activity_view {
+            self.activity.filter.pop();
+            self.activity.selected = 0;
+        } else if self.passphrase {
+            self.add_passphrase.focused_mut().pop();
+        } else if self.new_reminder || self.edit_reminder {
+            self.add_remind.focused_mut().pop();
+        } else if self.current_read_only() {
+            // locked secrets and smart sticky notes can't be changed
+        } else if self.new_todo || self.edit_todo {
+            self.add_todo.focused_mut().pop();
+        } else if self.new_note && !self.sticky_note.is_empty() {
+            let remind = &mut self.sticky_note[self.tabs.index];
+            if remind.note.pop().is_some() {
+                remind.note_changed();
+            }
+        }
+    }
+
+    /// Checks off the selected todo, or opens it again if it was closed.
+    fn mark_done(&mut self) {
+        if self.editing() || self.sticky_note.is_empty() || self.current_read_only() {
+            return;
+        }
+        if let Some(todo) = self.sticky_note[self.tabs.index].list.get_selected() {
+            let flag = todo.status.is_closed();
+            if !flag && self.refuse_blocked() {
+                return;
+            }
+
+            let todo = self.sticky_note[self.tabs.index]
+                .list
+                .get_selected_mut()
+                .unwrap();
+            todo.set_status(if flag { Status::Open } else { Status::Done });
+            todo.touch(&self.config.device_name);
+
+            let task = self.selected_task();
+            let action = if flag { "reopen todo" } else { "complete todo" };
+            self.log(action, &task);
+            if !flag {
+                self.announce_done();
+            }
+        }
+    }
+
+    pub fn on_delete(&mut self) {
+        if self.activity_view {
+            self.activity.filter.clear();
+            self.activity.selected = 0;
+        } else if self.passphrase {
+            self.add_passphrase.focused_mut().clear();
+        } else if self.current_read_only() && !(self.new_reminder || self.edit_reminder) {
+            // locked secrets and smart sticky notes can't be changed
+        } else if self.new_reminder || self.edit_reminder || self.new_todo || self.edit_todo {
+            self.reset_addition();
+        } else if self.new_note && !self.sticky_note.is_empty() {
+            self.sticky_note[self.tabs.index].note.pop();
+        }
+    }
+
+    /// Deletes the selected todo, it can be put back with `Undelete`.
+    fn remove_todo(&mut self) {
+        if self.editing() || self.sticky_note.is_empty() || self.current_read_only() {
+            return;
+        }
+        let list = &mut self.sticky_note[self.tabs.index].list;
+        let idx = list.selected;
+        let todo = match list.remove_selected() {
+            Some(todo) => todo,
+            None => return,
+        };
+        self.log("delete todo", &todo.task);
+        self.deleted.push(Deleted::Todo {
+            note: self.sticky_note[self.tabs.index].title.clone(),
+            idx,
+            todo,
+        });
+    }
+
+    /// Stops every command and ends the main loop.
+    fn quit(&mut self) {
+        self.should_quit = true;
+        self.jobs.kill_all();
+        self.save_draft(true);
+        self.see_current();
+        self.session.retain(&self.tabs.titles);
+        if let Err(e) = self.session.save() {
+            tracing::warn!("saving the session failed: {}", e);
+        }
+    }
+
+    /// Whatever the current sticky note shows now has been seen, called
+    /// before each key, click and tick.
+    fn see_current(&mut self) {
+        if let Some(remind) = self.sticky_note.items.get(self.tabs.index) {
+            self.session.see(remind);
+        }
+    }
+
+    /// Closes the form, runbook, activity log or notes that have the keyboard
+    /// and drops what was typed into a form, false when none is open.
+    fn cancel(&mut self) -> bool {
+        if !self.editing() && !self.notes_focus {
+            return false;
+        }
+        self.reset_new_flag();
+        self.reset_addition();
+        true
+    }
+
+    /// Quits from the top level, asking first with `confirm_quit`.
+    fn quit_top_level(&mut self) {
+        if self.config.confirm_quit {
+            self.modal = Some(Modal::confirm(
+                "Quit".to_string(),
+                "Quit forget?".to_string(),
+                ModalAction::Quit,
+            ));
+        } else {
+            self.quit();
+        }
+    }
+
+    /// The action a key set in the config stands for, in the list these win
+    /// over what the key does otherwise.
+    fn bound_action(&self, key: Key) -> Option<Action> {
+        let config = &self.config;
+        if key == Key::from(config.mark_done) {
+            Some(Action::MarkDone)
+        } else if key == Key::from(config.remove_todo) {
+            Some(Action::RemoveTodo)
+        } else if key == Key::Ctrl(config.exit_key_char_ctrl) {
+            Some(Action::Quit)
+        } else {
+            None
+        }
+    }
+
+    pub fn reset_new_flag(&mut self) {
+        if self.new_note {
+            self.unstamp_journal();
+        }
+        self.new_note = false;
+        self.new_reminder = false;
+        self.edit_reminder = false;
+        self.new_todo = false;
+        self.edit_todo = false;
+        self.runbook = false;
+        self.passphrase = false;
+        self.activity_view = false;
+        self.notes_focus = false;
+    }
+
+    pub fn on_ctrl_key(&mut self, c: char) {
+        let mut allowed_read_only = vec![
+            self.config.exit_key_char_ctrl,
+            self.config.new_sticky_note_char_ctrl,
+            self.config.new_smart_note_char_ctrl,
+            self.config.edit_sticky_note_char_ctrl,
+            self.config.remove_sticky_note_char_ctrl,
+            self.config.save_state_to_db_char_ctrl,
+            self.config.activity_char_ctrl,
+            self.config.goto_blocker_char_ctrl,
+            self.config.goto_due_char_ctrl,
+            self.config.find_char_ctrl,
+            self.config.board_char_ctrl,
+            self.config.debug_char_ctrl,
+        ];
+        if self.current_locked() {
+            allowed_read_only.push(self.config.secret_char_ctrl);
+        } else {
+            allowed_read_only.push(self.config.run_all_char_ctrl);
+        }
+        if self.current_read_only() && !allowed_read_only.contains(&c) {
+            return;
+        }
+
+        match c {
+            c if c == self.config.exit_key_char_ctrl => self.quit(),
+            // New Todo
+            c if c == self.config.new_todo_char_ctrl => {
+                let flag = self.new_todo;
+                self.reset_new_flag();
+                self.new_todo = !flag;
+            }
+            // Edit Todo
+            c if c == self.config.edit_todo_char_ctrl => {
+                let flag = self.edit_todo;
+                self.reset_new_flag();
+                self.edit_todo = !flag;
+
+                if self.edit_todo {
+                    let todo = self
+                        .sticky_note
+                        .items
+                        .get(self.tabs.index)
+                        .and_then(|n| n.list.get_selected());
+                    match todo {
+                        Some(todo) => self.add_todo = AddTodo::from_todo(todo, &self.dates),
+                        None => self.edit_todo = false,
+                    }
+                } else {
+                    self.add_todo.clear();
+                }
+            }
+            // New Sticky Note
+            c if c == self.config.new_sticky_note_char_ctrl => {
+                let flag = self.new_reminder;
+                self.reset_new_flag();
+                self.new_reminder = !flag;
+                self.add_remind.smart = false;
+            }
+            // New Smart Sticky Note
+            c if c == self.config.new_smart_note_char_ctrl => {
+                let flag = self.new_reminder && self.add_remind.smart;
+                self.reset_new_flag();
+                self.reset_addition();
+                self.new_reminder = !flag;
+                self.add_remind.smart = !flag;
+            }
+            // Edit Sticky Note title and default command
+            c if c == self.config.edit_sticky_note_char_ctrl => {
+                let flag = self.edit_reminder;
+                self.reset_new_flag();
+                self.edit_reminder = !flag;
+
+                if self.edit_reminder {
+                    if let Some(remind) = self.sticky_note.items.get(self.tabs.index) {
+                        self.add_remind.title = remind.title.clone();
+                        self.add_remind.cmd = remind.default_cmd.clone();
+                        self.add_remind.smart = remind.is_smart();
+                        self.add_remind.query = remind.query.clone().unwrap_or_default();
+                        self.add_remind.wip_limit =
+                            remind.wip_limit.map(|n| n.to_string()).unwrap_or_default();
+                    }
+                } else {
+                    self.reset_addition();
+                }
+            }
+            // Add to or New Note
+            c if c == self.config.new_note_char_ctrl => {
+                let flag = self.new_note;
+                self.reset_new_flag();
+                self.new_note = !flag;
+                if flag {
+                    self.log("edit note", "");
+                } else {
+                    self.stamp_journal();
+                }
+            }
+            // Remove Sticky Note, once confirmed
+            c if c == self.config.remove_sticky_note_char_ctrl && !self.sticky_note.is_empty() => {
+                let tab_idx = self.tabs.index;
+                let message = format!(
+                    "Remove \"{}\" and all of its todos?",
+                    self.sticky_note[tab_idx].title
+                );
+                self.reset_new_flag();
+                self.modal = Some(Modal::confirm(
+                    "Remove Sticky Note".to_string(),
+                    message,
+                    ModalAction::RemoveStickyNote(tab_idx),
+                ));
+            }
+            // Make the Sticky Note secret, unlock or lock it again
+            c if c == self.config.secret_char_ctrl && !self.sticky_note.is_empty() => {
+                let unlocked = {
+                    let remind = &self.sticky_note[self.tabs.index];
+                    remind.secret.is_some() && !remind.is_locked()
+                };
+                let flag = self.passphrase;
+                self.reset_new_flag();
+                self.reset_addition();
+                if unlocked {
+                    match self.sticky_note[self.tabs.index].lock() {
+                        Ok(()) => self.log("lock", ""),
+                        Err(e) => {
+                            self.add_passphrase.error = Some(e.to_string());
+                            self.passphrase = true;
+                        }
+                    }
+                } else {
+                    self.passphrase = !flag;
+                }
+            }
+            // Step through the todos one at a time
+            c if c == self.config.runbook_char_ctrl => {
+                let flag = self.runbook;
+                self.reset_new_flag();
+                self.reset_addition();
+                self.runbook = !flag && self.start_runbook();
+            }
+            // Run the commands of every incomplete todo
+            c if c == self.config.run_all_char_ctrl => {
+                self.run_all_cmds();
+            }
+            // Save current Sticky Notes to DB
+            c if c == self.config.save_state_to_db_char_ctrl => {
+                self.save();
+            }
+            // Move the selected todo on to its next status
+            c if c == self.config.status_char_ctrl && !self.sticky_note.is_empty() => {
+                self.cycle_status();
+            }
+            // Edit the todos as a checklist in $EDITOR
+            c if c == self.config.editor_char_ctrl && !self.sticky_note.is_empty() => {
+                self.reset_new_flag();
+                self.start_external_edit();
+            }
+            // Switch between the list and the board view
+            c if c == self.config.board_char_ctrl && !self.sticky_note.is_empty() => {
+                self.reset_new_flag();
+                let remind = &mut self.sticky_note[self.tabs.index];
+                remind.board = !remind.board;
+            }
+            // Jump to the next due todo
+            c if c == self.config.goto_due_char_ctrl => {
+                self.goto_due();
+            }
+            // Find and replace in the notes
+            c if c == self.config.find_char_ctrl => {
+                self.find = Some(Find::default());
+            }
+            // Jump to the todo blocking the selected one
+            c if c == self.config.goto_blocker_char_ctrl => {
+                self.goto_blocker();
+            }
+            // Show frame times, the event queue and the last errors
+            c if c == self.config.debug_char_ctrl => {
+                self.debug_overlay = !self.debug_overlay;
+            }
+            // Show the activity log
+            c if c == self.config.activity_char_ctrl => {
+                let flag = self.activity_view;
+                self.reset_new_flag();
+                self.activity_view = !flag;
+                self.activity.selected = 0;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn on_tick(&mut self) {
+        self.see_current();
+        self.tabs.remember();
+        self.poll_pty();
+        self.make_recurring();
+        self.track_habits();
+        self.refresh_remote();
+        self.refresh_feeds();
+        self.sync_notes();
+        self.reload_kiosk();
+        self.save_draft(false);
+        let now = Local::now().time();
+        let quiet = self.quiet.is_quiet(now);
+        self.jobs.poll();
+        self.animations.tick();
+        if self.toasts.tick() {
+            self.animations.start_once(Anim::ToastsLeaving, TOAST_LEAVE);
+        }
+        for job in self.jobs.take_finished() {
+            self.report_job(job, quiet);
+        }
+        if let Some(summary) = self.quiet.summary(now) {
+            self.toasts.info(summary);
+        }
+
+        let idle = Duration::from_secs(self.config.idle_lock_minutes * 60);
+        if self.config.idle_lock_minutes != 0
+            && !self.config.idle_lock_hash.is_empty()
+            && self.last_input.elapsed() >= idle
+        {
+            self.locked = true;
+        }
+    }
+
+    /// Tells how a command went the way its todo's `Report` asks, toasts are
+    /// held back during quiet hours and so are desktop notifications.
+    fn report_job(&mut self, job: Finished, quiet: bool) {
+        let ok = job.status == JobStatus::Exited(0);
+        if ok {
+            tracing::info!("job finished: {}", job.label);
+        } else {
+            tracing::error!("{}: {}", job.label, job.status);
+        }
+        let toast = match job.report {
+            Report::Silent => false,
+            Report::Failures => !ok,
+            Report::Toast => true,
+            Report::Notify if quiet => true,
+            Report::Notify => {
+                let last = job.output.lines().rev().find(|l| !l.trim().is_empty());
+                let body = last.map_or_else(|| job.status.to_string(), str::to_string);
+                match notify::desktop(&job.label, &body) {
+                    Ok(()) => false,
+                    Err(e) => {
+                        tracing::warn!("notifying failed: {}", e);
+                        true
+                    }
+                }
+            }
+            Report::Note => !self.note_output(&job),
+        };
+        if !toast {
+            return;
+        }
+        match (ok, quiet) {
+            (true, true) => self.quiet.hold(Level::Info),
+            (true, false) => self.toasts.info(format!("Done: {}", job.label)),
+            (false, true) => self.quiet.hold(Level::Error),
+            (false, false) => self.toasts.error(format!("{}: {}", job.label, job.status)),
+        }
+    }
+
+    /// Adds what `job` printed to the notes of its todo's sticky note under
+    /// the time in the `journal_format`, so the notes keep a log of the runs.
+    /// False when the todo is gone or its sticky note is locked.
+    fn note_output(&mut self, job: &Finished) -> bool {
+        let note = match self.find_todo(job.id) {
+            Some((note, _)) if !self.sticky_note[note].is_locked() => note,
+            _ => return false,
+        };
+        let time = self.journal_time();
+        let remind = &mut self.sticky_note[note];
+        let sep = entry_sep(&remind.note);
+        let _ = writeln!(
+            remind.note,
+            "{}{} {} ({})",
+            sep, time, job.label, job.status
+        );
+        if !job.output.is_empty() {
+            let _ = writeln!(remind.note, "{}", job.output);
+        }
+        remind.touch();
+        true
+    }
+
+    /// Keeps the spinner going while tasks run, true while frames should be
+    /// drawn between the ticks.
+    pub fn animating(&mut self) -> bool {
+        if self.workers.is_busy() {
+            self.animations.start_loop(Anim::Spinner, worker::FRAME);
+        } else {
+            self.animations.stop(Anim::Spinner);
+        }
+        self.config.frame_ms != 0 && !self.low_bandwidth && self.animations.is_running()
+    }
+
+    /// What is being typed, empty forms and the notes and todos of secret
+    /// sticky notes are left out.
+    fn current_draft(&self) -> Option<Draft> {
+        let tab = self.tabs.index;
+        let remind = self.sticky_note.items.get(tab);
+        if self.new_todo || self.edit_todo {
+            let idx = remind.filter(|_| self.edit_todo).map(|n| n.list.selected);
+            let secret = remind.is_none_or(|n| n.secret.is_some());
+            (!secret && self.add_todo != AddTodo::default()).then(|| Draft::Todo {
+                tab,
+                idx,
+                todo: self.add_todo.clone(),
+            })
+        } else if self.new_reminder || self.edit_reminder {
+            let editing = remind.filter(|_| self.edit_reminder).map(|_| tab);
+            (self.add_remind != AddRemind::default()).then(|| Draft::StickyNote {
+                tab: editing,
+                remind: self.add_remind.clone(),
+            })
+        } else if self.new_note {
+            remind.filter(|n| n.secret.is_none()).map(|n| Draft::Note {
+                tab,
+                title: n.title.clone(),
+                note: n.note.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Writes the draft every `draft_secs`, or `now`, and removes it once
+    /// nothing is being typed.
+    fn save_draft(&mut self, now: bool) {
+        if self.config.draft_secs == 0 || self.restore.is_some() {
+            return;
+        }
+        let current = self.current_draft();
+        if current == self.last_draft {
+            return;
+        }
+        let due = self.draft_saved.elapsed() >= Duration::from_secs(self.config.draft_secs);
+        let result = match &current {
+            Some(_) if !due && !now => return,
+            Some(draft) => draft::save(draft),
+            None => draft::remove(),
+        };
+        if let Err(e) = result {
+            tracing::error!("saving the draft failed: {}", e);
+        }
+        self.draft_saved = Instant::now();
+        self.last_draft = current;
+    }
+
+    /// Opens the form or the notes the draft was typed into again.
+    fn restore_draft(&mut self, draft: Draft) {
+        self.reset_new_flag();
+        match draft {
+            Draft::Todo { tab, idx, mut todo } => {
+                if tab >= self.sticky_note.len() {
+                    return;
+                }
+                self.tabs.index = tab;
+                self.sticky_note.selected = tab;
+                let list = &mut self.sticky_note[tab].list;
+                match idx {
+                    Some(idx) if idx < list.len() => {
+                        list.selected = idx;
+                        self.edit_todo = true;
+                    }
+                    _ => {
+                        todo.editing = None;
+                        self.new_todo = true;
+                    }
+                }
+                self.add_todo = todo;
+            }
+            Draft::StickyNote { tab, remind } => {
+                match tab {
+                    Some(tab) if tab < self.sticky_note.len() => {
+                        self.tabs.index = tab;
+                        self.sticky_note.selected = tab;
+                        self.edit_reminder = true;
+                    }
+                    _ => self.new_reminder = true,
+                }
+                self.add_remind = remind;
+            }
+            Draft::Note { tab, title, note } => {
+                // the sticky notes may have moved since, the title finds it again
+                let tab = match self.sticky_note.items.get(tab) {
+                    Some(remind) if remind.title == title => tab,
+                    _ => match self.sticky_note.iter().position(|n| n.title == title) {
+                        Some(tab) => tab,
+                        None => return,
+                    },
+                };
+                self.tabs.index = tab;
+                self.sticky_note.selected = tab;
+                let remind = &mut self.sticky_note[tab];
+                remind.note = note;
+                remind.note_changed();
+                self.new_note = true;
+            }
+        }
+    }
+
+    /// The percent of the width the todos get in the current view.
+    pub fn split(&self) -> u16 {
+        if self.current_board() {
+            self.config.pane_split.board
+        } else {
+            self.config.pane_split.list
+        }
+    }
+
+    fn set_split(&mut self, percent: i16) {
+        let percent = percent.clamp(MIN_SPLIT, 100 - MIN_SPLIT) as u16;
+        if self.current_board() {
+            self.config.pane_split.board = percent;
+        } else {
+            self.config.pane_split.list = percent;
+        }
+    }
+
+    fn save_config(&mut self) {
+        if let Err(e) = config::save_config(&self.config) {
+            self.toasts
+                .error(format!("Saving the config failed: {}", e));
+            self.log("save config failed", &e.to_string());
+        }
+    }
+
+    /// Dragging the border between the todos and the notes resizes them,
+    /// returns true if the mouse event was used for that.
+    fn on_divider_mouse(&mut self, mouse: MouseEvent) -> bool {
+        let main = self.areas.main;
+        let divider = main.x + main.width * self.split() / 100;
+        match mouse {
+            MouseEvent::Press(MouseButton::Left, x, y) => {
+                let (x, y) = (x.saturating_sub(1), y.saturating_sub(1));
+                // the todos' right border and the notes' left border
+                self.resizing =
+                    (x + 1 == divider || x == divider) && y >= main.y && y < main.y + main.height;
+                self.resizing
+            }
+            MouseEvent::Hold(x, _) if self.resizing && main.width > 0 => {
+                let x = x.saturating_sub(1).saturating_sub(main.x);
+                self.set_split((x as u32 * 100 / main.width as u32) as i16);
+                true
+            }
+            MouseEvent::Release(..) if self.resizing => {
+                self.resizing = false;
+                self.save_config();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The index of the todo drawn on terminal row `y`, or the last todo
+    /// below them.
+    fn row_at(&self, x: u16, y: u16) -> Option<usize> {
+        let area = self.areas.list;
+        if area.width < 2
+            || x <= area.x
+            || x + 1 >= area.x + area.width
+            || y <= area.y
+            || y + 1 >= area.y + area.height
+        {
+            return None;
+        }
+        let remind = self.sticky_note.items.get(self.tabs.index)?;
+        let rows = remind.visible_rows();
+        let selected = rows
+            .iter()
+            .position(|i| *i == remind.list.selected)
+            .unwrap_or(0);
+        // the same scrolling as `TodoList`
+        let height = (area.height - 2) as usize;
+        let offset = if selected >= height {
+            selected - height + 1
+        } else {
+            0
+        };
+        let row = (offset + (y - area.y - 1) as usize).min(rows.len().checked_sub(1)?);
+        Some(rows[row])
+    }
+
+    fn tab_at(&self, x: u16, y: u16) -> Option<usize> {
+        if y != self.areas.tabs_row {
+            return None;
+        }
+        self.areas
+            .tabs
+            .iter()
+            .position(|(start, width)| x >= *start && x < start + width)
+    }
+
+    /// Clicking a todo selects it, dragging it drops it before another todo
+    /// or onto another sticky note's tab.
+    pub fn on_mouse(&mut self, mouse: MouseEvent) {
+        self.smart_stale = true;
+        self.see_current();
+        self.tabs.remember();
+        if self.present.is_some() {
+            return;
+        }
+        if self.on_divider_mouse(mouse) {
+            return;
+        }
+        if self.editing() || self.current_board() || self.sticky_note.is_empty() {
+            return;
+        }
+        match mouse {
+            // termion's coordinates are one-based
+            MouseEvent::Press(MouseButton::Left, x, y) => {
+                let (x, y) = (x.saturating_sub(1), y.saturating_sub(1));
+                if let Some(tab) = self.tab_at(x, y) {
+                    self.reset_addition();
+                    self.tabs.index = tab;
+                    return;
+                }
+                let (due_x, due_width) = self.areas.due;
+                if y == self.areas.tabs_row && x >= due_x && x < due_x + due_width {
+                    self.goto_due();
+                    return;
+                }
+                if let Some(row) = self.row_at(x, y) {
+                    self.sticky_note[self.tabs.index].list.selected = row;
+                    if !self.current_read_only() {
+                        self.drag = Some(Drag {
+                            from: row,
+                            over: None,
+                        });
+                    }
+                }
+            }
+            MouseEvent::Hold(x, y) => {
+                let (x, y) = (x.saturating_sub(1), y.saturating_sub(1));
+                let over = self
+                    .row_at(x, y)
+                    .map(DropTarget::Row)
+                    .or_else(|| self.tab_at(x, y).map(DropTarget::Tab));
+                if let Some(drag) = self.drag.as_mut() {
+                    drag.over = over;
+                }
+            }
+            MouseEvent::Release(..) => {
+                if let Some(Drag {
+                    from,
+                    over: Some(over),
+                }) = self.drag.take()
+                {
+                    self.drop_todo(from, over);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn drop_todo(&mut self, from: usize, target: DropTarget) {
+        let tab = self.tabs.index;
+        match target {
+            DropTarget::Row(to) => {
+                let list = &mut self.sticky_note[tab].list;
+                if from >= list.len() || to >= list.len() || to == from {
+                    return;
+                }
+                let todo = list.items.remove(from);
+                list.items.insert(to, todo);
+                list.selected = to;
+                let task = self.selected_task();
+                self.log("reorder todo", &task);
+            }
+            DropTarget::Tab(to) => {
+                let movable = self
+                    .sticky_note
+                    .items
+                    .get(to)
+                    .map(|n| !n.is_view() && !n.is_locked())
+                    .unwrap_or(false);
+                if to == tab || !movable || from >= self.sticky_note[tab].list.len() {
+                    return;
+                }
+                let todo = self.sticky_note[tab].list.items.remove(from);
+                let list = &mut self.sticky_note[tab].list;
+                list.selected = list.selected.min(list.len().saturating_sub(1));
+                let detail = format!("{} to {}", todo.task, self.sticky_note[to].title);
+                self.log("move todo", &detail);
+                self.sticky_note[to].list.items.push(todo);
+                self.sticky_note[to].touch();
+            }
+        }
+    }
+
+    fn save(&mut self) {
+        self.track_habits();
+        // the tab in view is the one shown first next time
+        self.sticky_note.selected = self.tabs.index;
+        self.write_db();
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    fn write_db(&mut self) {
+        let saved = config::save_db(&self.sticky_note);
+        self.on_saved(saved);
+    }
+
+    /// Writes a copy of the sticky notes on the tokio runtime, one write at
+    /// a time so an older copy never lands after a newer one.
+    #[cfg(feature = "tokio")]
+    fn write_db(&mut self) {
+        if self.saving() {
+            self.save_again = true;
+            return;
+        }
+        let notes = self.sticky_note.clone();
+        let id = self.workers.spawn_async("Saving", async move {
+            Output::Saved(config::save_db_async(notes).await)
+        });
+        self.saving = Some(id);
+    }
+
+    /// True while the sticky notes are being written, quitting waits for it.
+    #[cfg(feature = "tokio")]
+    pub fn saving(&self) -> bool {
+        self.saving.is_some_and(|id| self.workers.is_running(id))
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    pub fn saving(&self) -> bool {
+        false
+    }
+
+    fn on_saved(&mut self, saved: io::Result<()>) {
+        match saved {
+            Ok(()) => {
+                tracing::info!("saved {} sticky notes", self.sticky_note.len());
+                self.toasts.info("Saved");
+                self.activity.record("save", "");
+            }
+            Err(e) => self.toasts.error(format!("Saving failed: {}", e)),
+        }
+    }
+
+    /// Every key press goes through here, it records keys and runs macros
+    /// before handing the key on.
+    pub fn on_key_press(&mut self, key: Key) {
+        self.smart_stale = true;
+        self.see_current();
+        self.tabs.remember();
+        if self.kiosk.is_some() {
+            if key == Key::Ctrl(self.config.exit_key_char_ctrl) {
+                self.quit();
+            }
+            return;
+        }
+        // the pane's command gets the keys macros and the recorder would
+        if self.pty_focus && self.modal.is_none() {
+            self.on_pty_key(key);
+            return;
+        }
+        if key == Key::Ctrl(self.config.record_macro_char_ctrl) && self.modal.is_none() {
+            self.toggle_recording();
+            return;
+        }
+        // the color picker moves between colors with shift-tab
+        if self.modal.is_none()
+            && self.colors.is_none()
+            && self.agenda.is_none()
+            && self.present.is_none()
+            && !self.editing()
+        {
+            let actions = if key == Key::Ctrl(self.config.play_macro_char_ctrl) {
+                self.find_macro(|m| m.name == action::RECORDED)
+            } else {
+                self.find_macro(|m| m.key.map(Key::from) == Some(key))
+            };
+            if let Some(actions) = actions {
+                if let Some(recording) = self.recording.as_mut() {
+                    recording.extend(actions.iter().copied());
+                }
+                for action in actions {
+                    self.run_action(action);
+                }
+                return;
+            }
+        }
+        // recordings are saved to config.json, passphrases and secrets stay out
+        let secret = self.passphrase
+            || self
+                .sticky_note
+                .items
+                .get(self.tabs.index)
+                .is_some_and(|n| n.secret.is_some());
+        if let Some(recording) = self.recording.as_mut().filter(|_| !secret) {
+            recording.push(Action::Key(key.into()));
+        }
+        self.dispatch_key(key);
+    }
+
+    fn find_macro<P: Fn(&Macro) -> bool>(&self, pred: P) -> Option<Vec<Action>> {
+        self.config
+            .macros
+            .iter()
+            .find(|m| pred(m))
+            .map(|m| m.actions.clone())
+    }
+
+    fn dispatch_key(&mut self, key: Key) {
+        if self.modal.is_some() {
+            self.on_modal_key(key);
+            return;
+        }
+        if self.help {
+            self.help = false;
+            return;
+        }
+        if self.find.is_some() {
+            self.on_find_key(key);
+            return;
+        }
+        if self.colors.is_some() {
+            self.on_color_key(key);
+            return;
+        }
+        if self.agenda.is_some() {
+            self.on_agenda_key(key);
+            return;
+        }
+        if self.present.is_some() {
+            self.on_present_key(key);
+            return;
+        }
+        if self.pty_focus {
+            self.on_pty_key(key);
+            return;
+        }
+        if key == Key::F(10) && self.pty.is_some() {
+            self.pty_focus = true;
+            return;
+        }
+        // the quit key backs out of a form first and only quits from the list
+        if key == Key::from(self.config.quit_key) {
+            if !self.cancel() {
+                self.quit_top_level();
+            }
+            return;
+        }
+        let bound = self.bound_action(key);
+        // the exit key works in the forms too, the others only in the list
+        if let Some(action) = bound.filter(|a| matches!(a, Action::Quit) || !self.editing()) {
+            self.run_action(action);
+            return;
+        }
+        match key {
+            Key::Char('\n') => self.on_enter(),
+            Key::Char(c) => self.on_key(c),
+            Key::Up => self.on_up(),
+            Key::Down => self.on_down(),
+            Key::Left => self.on_left(),
+            Key::Right => self.on_right(),
+            Key::Backspace => self.on_backspace(),
+            Key::Delete => self.on_delete(),
+            Key::Ctrl(c) => self.on_ctrl_key(c),
+            Key::PageUp => self.scroll_notes(-self.notes_page()),
+            Key::PageDown => self.scroll_notes(self.notes_page()),
+            Key::Home => self.scroll_notes_to(false),
+            Key::End => self.scroll_notes_to(true),
+            _ => {}
+        }
+    }
+
+    fn on_color_key(&mut self, key: Key) {
+        if key == Key::Ctrl(self.config.exit_key_char_ctrl) {
+            self.quit();
+            return;
+        }
+        let picker = match self.colors.as_mut() {
+            Some(picker) => picker,
+            None => return,
+        };
+        match picker.on_key(key, &mut self.config.app_colors) {
+            Some(PickerResult::Saved) => {
+                self.colors = None;
+                self.save_config();
+            }
+            Some(PickerResult::Cancelled) => self.colors = None,
+            None => {}
+        }
+    }
+
+    /// Space moves the highlight down the presented todos, backspace back up,
+    /// nothing can be changed.
+    fn on_present_key(&mut self, key: Key) {
+        let len = self
+            .sticky_note
+            .items
+            .get(self.tabs.index)
+            .map_or(0, |remind| remind.list.len());
+        let present = match self.present.as_mut() {
+            Some(present) => present,
+            None => return,
+        };
+        match key {
+            Key::Char(' ') | Key::Down | Key::Right => {
+                *present = (*present + 1).min(len.saturating_sub(1))
+            }
+            Key::Backspace | Key::Up | Key::Left => *present = present.saturating_sub(1),
+            Key::Home => *present = 0,
+            Key::End => *present = len.saturating_sub(1),
+            Key::Ctrl(c) if c == self.config.exit_key_char_ctrl => self.quit(),
+            key if key == Key::from(self.config.quit_key)
+                || Some(key) == hints::macro_key(&self.config, &Action::Present) =>
+            {
+                self.present = None
+            }
+            _ => {}
+        }
+    }
+
+    /// Presents the kiosk's sticky note from now on, an error when there's
+    /// no sticky note with its title.
+    pub fn start_kiosk(&mut self, kiosk: Kiosk) -> io::Result<()> {
+        if let Some(title) = &kiosk.title {
+            self.tabs.index = self
+                .sticky_note
+                .iter()
+                .position(|n| n.title == *title)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("there is no sticky note called {:?}", title),
+                    )
+                })?;
+        }
+        self.present = Some(0);
+        self.kiosk = Some(kiosk);
+        Ok(())
+    }
+
+    /// Reads the data base again once the kiosk is due, what another
+    /// `forget` saved shows up here.
+    fn reload_kiosk(&mut self) {
+        let kiosk = match self.kiosk.as_mut() {
+            Some(kiosk) if kiosk.is_due() => kiosk,
+            _ => return,
+        };
+        kiosk.reloaded = Instant::now();
+        let title = kiosk.title.clone().or_else(|| {
+            self.sticky_note
+                .items
+                .get(self.tabs.index)
+                .map(|n| n.title.clone())
+        });
+        match config::open_db() {
+            Ok(notes) => self.show_kiosk_notes(notes, title),
+            Err(e) => {
+                tracing::warn!("kiosk reload failed: {}", e);
+                self.toasts
+                    .error(format!("Reading the data base failed: {}", e));
+            }
+        }
+    }
+
+    fn show_kiosk_notes(&mut self, notes: ListState<Remind>, title: Option<String>) {
+        let index = title
+            .and_then(|title| notes.iter().position(|n| n.title == title))
+            .unwrap_or(self.tabs.index)
+            .min(notes.len().saturating_sub(1));
+        self.tabs = TabsState::new(notes.iter().map(|n| n.title.clone()).collect());
+        self.tabs.index = index;
+        self.sticky_note = notes;
+        self.smart_stale = true;
+    }
+
+    fn on_agenda_key(&mut self, key: Key) {
+        let agenda = match self.agenda.as_mut() {
+            Some(agenda) => agenda,
+            None => return,
+        };
+        let entries = agenda.entries(&self.sticky_note, &self.dates, self.config.countdown_days);
+        match agenda.on_key(key, &entries) {
+            Some(AgendaResult::Goto(tab, idx)) => {
+                self.agenda = None;
+                self.reset_new_flag();
+                self.reset_addition();
+                self.tabs.index = tab;
+                self.sticky_note[tab].list.selected = idx;
+            }
+            Some(AgendaResult::Closed) => self.agenda = None,
+            None => {}
+        }
+    }
+
+    fn on_find_key(&mut self, key: Key) {
+        let find = match self.find.as_mut() {
+            Some(find) => find,
+            None => return,
+        };
+        let note = self
+            .sticky_note
+            .items
+            .get(self.tabs.index)
+            .map(|n| n.note.as_str())
+            .unwrap_or_default();
+        match key {
+            Key::Esc => self.find = None,
+            Key::Ctrl(c) if c == self.config.exit_key_char_ctrl => self.quit(),
+            Key::Ctrl(c) if c == self.config.find_char_ctrl => self.find = None,
+            Key::Ctrl('a') => self.replace_in_notes(true),
+            Key::Char('\t') | Key::Up | Key::Down => find.replacing = !find.replacing,
+            Key::Char('\n') if find.replacing => self.replace_in_notes(false),
+            Key::Char('\n') => find.next(note),
+            Key::Char(c) => {
+                find.focused_mut().push(c);
+                find.current = 0;
+            }
+            Key::Backspace => {
+                find.focused_mut().pop();
+                find.current = 0;
+            }
+            _ => {}
+        }
+        self.show_match();
+    }
+
+    /// Replaces the selected match of the find prompt, or all of them.
+    fn replace_in_notes(&mut self, all: bool) {
+        if self.current_read_only() || self.sticky_note.is_empty() {
+            self.toasts.error("This sticky note can't be changed");
+            return;
+        }
+        let find = match self.find.as_mut() {
+            Some(find) => find,
+            None => return,
+        };
+        let remind = &mut self.sticky_note[self.tabs.index];
+        let count = if all {
+            find.replace_all(&mut remind.note)
+        } else {
+            find.replace_one(&mut remind.note) as usize
+        };
+        if count == 0 {
+            return;
+        }
+        remind.note_changed();
+        let detail = format!("{} with {} ({}×)", find.query, find.replace, count);
+        self.log("replace in notes", &detail);
+        if all {
+            self.toasts.info(format!("Replaced {}", count));
+        }
+    }
+
+    /// Scrolls the notes so the selected match of the find prompt is in view.
+    fn show_match(&mut self) {
+        let (width, height) = self.notes_size();
+        let find = match self.find.as_ref() {
+            Some(find) => find,
+            None => return,
+        };
+        if let Some(remind) = self.sticky_note.items.get_mut(self.tabs.index) {
+            if let Some(range) = find.selected(&remind.note) {
+                let line = remind.note[..range.start].matches('\n').count();
+                remind.note_scroll = line
+                    .saturating_sub(height as usize / 2)
+                    .min(remind.note_tail(width, height));
+                remind.follow_tail = false;
+            }
+        }
+    }
+
+    /// Rows of note text that fit in the notes pane.
+    fn notes_page(&self) -> isize {
+        (self.areas.notes.height.saturating_sub(3) as isize).max(1)
+    }
+
+    /// Moves the notes view by `lines`, it follows the end of the note once
+    /// scrolled all the way down.
+    fn scroll_notes(&mut self, lines: isize) {
+        let (width, height) = self.notes_size();
+        if let Some(remind) = self.sticky_note.items.get_mut(self.tabs.index) {
+            let tail = remind.note_tail(width, height);
+            let from = if remind.follow_tail {
+                tail
+            } else {
+                remind.note_scroll
+            };
+            let to = (from as isize + lines).clamp(0, tail as isize) as usize;
+            remind.note_scroll = to;
+            remind.follow_tail = to == tail && to > 0;
+        }
+    }
+
+    /// Jumps to the start of the note or follows its end.
+    fn scroll_notes_to(&mut self, end: bool) {
+        if let Some(remind) = self.sticky_note.items.get_mut(self.tabs.index) {
+            remind.note_scroll = 0;
+            remind.follow_tail = end;
+        }
+    }
+
+    /// The inside of the notes pane.
+    pub fn notes_size(&self) -> (u16, u16) {
+        (
+            self.areas.notes.width.saturating_sub(2),
+            self.areas.notes.height.saturating_sub(2),
+        )
+    }
+
+    fn run_action(&mut self, action: Action) {
+        match action {
+            Action::Up => self.on_up(),
+            Action::Down => self.on_down(),
+            Action::PreviousNote => self.on_left(),
+            Action::NextNote => self.on_right(),
+            Action::MarkDone => self.mark_done(),
+            Action::RemoveTodo => self.remove_todo(),
+            Action::Quit => self.quit(),
+            Action::NextStatus => self.on_ctrl_key(self.config.status_char_ctrl),
+            Action::SetStatus(status) => {
+                if !self.sticky_note.is_empty() && !self.current_read_only() {
+                    self.set_status(status);
+                }
+            }
+            Action::Save => self.save(),
+            Action::RunSelected => self.run_selected(),
+            Action::RunAll => self.on_ctrl_key(self.config.run_all_char_ctrl),
+            Action::CarryOver => {
+                if !self.current_read_only() {
+                    self.carry_over_to_current();
+                }
+            }
+            Action::Publish => self.publish(),
+            Action::PickHighlight => {
+                self.modal = Some(Modal::pick(
+                    "Highlight symbol",
+                    symbols::choices(),
+                    ModalAction::SetHighlight,
+                ));
+            }
+            Action::Attach => {
+                if self.sticky_note.is_empty() || self.current_read_only() {
+                    return;
+                }
+                self.modal = Some(Modal::input(
+                    "Attach file",
+                    "Path of the file, ~ is your home",
+                    ModalAction::Attach(self.tabs.index),
+                ));
+            }
+            Action::OpenAttachment => {
+                let tab_idx = self.tabs.index;
+                let action = ModalAction::OpenAttachment(tab_idx);
+                if let Some(idx) = self.pick_attachment("Open attachment", action) {
+                    self.open_attachment(tab_idx, idx);
+                }
+            }
+            Action::RemoveAttachment => {
+                if self.sticky_note.is_empty() || self.current_read_only() {
+                    return;
+                }
+                let action = ModalAction::RemoveAttachment(self.tabs.index);
+                let remind = &self.sticky_note[self.tabs.index];
+                if !remind.attachments.is_empty() {
+                    self.modal = Some(Modal::pick(
+                        "Remove attachment",
+                        remind.attachments.clone(),
+                        action,
+                    ));
+                }
+            }
+            Action::Agenda => {
+                self.agenda = match self.agenda {
+                    Some(_) => None,
+                    None => Some(Agenda::default()),
+                };
+            }
+            Action::Help => self.help = true,
+            Action::Present => {
+                let shown = self
+                    .sticky_note
+                    .items
+                    .get(self.tabs.index)
+                    .is_some_and(|remind| !remind.is_locked());
+                self.present = match self.present {
+                    None if shown => Some(0),
+                    _ => None,
+                };
+            }
+            Action::LastTab => {
+                self.reset_addition();
+                self.runbook = false;
+                if !self.tabs.flip() {
+                    self.toasts.info("No other sticky note viewed yet");
+                }
+            }
+            Action::Undelete => self.undelete(),
+            Action::ToggleHideDone => {
+                if let Some(remind) = self.sticky_note.items.get_mut(self.tabs.index) {
+                    remind.hide_done = !remind.hide_done;
+                }
+            }
+            Action::ToggleGoal => {
+                if self.sticky_note.is_empty() || self.current_read_only() {
+                    return;
+                }
+                let remind = &mut self.sticky_note[self.tabs.index];
+                remind.goal = !remind.goal;
+                let text = if remind.goal { "on" } else { "off" };
+                self.toasts.info(format!("Goal {}", text));
+                self.log("goal", text);
+            }
+            Action::WeightUp | Action::WeightDown => {
+                if self.sticky_note.is_empty() || self.current_read_only() {
+                    return;
+                }
+                let device = self.config.device_name.clone();
+                let todo = match self.sticky_note[self.tabs.index].list.get_selected_mut() {
+                    Some(todo) => todo,
+                    None => return,
+                };
+                let weight = todo.weight.max(1);
+                todo.weight = if matches!(action, Action::WeightUp) {
+                    weight + 1
+                } else {
+                    (weight - 1).max(1)
+                };
+                todo.touch(&device);
+                let text = format!("{} ×{}", todo.task, todo.weight);
+                self.log("weight", &text);
+            }
+            Action::ToggleHabit => {
+                if self.sticky_note.is_empty() || self.current_read_only() {
+                    return;
+                }
+                let remind = &mut self.sticky_note[self.tabs.index];
+                remind.habit = !remind.habit;
+                if remind.habit {
+                    habit::sync(remind, self.habit_day);
+                }
+                let text = if remind.habit { "on" } else { "off" };
+                self.toasts.info(format!("Habit tracker {}", text));
+                self.log("habit", text);
+            }
+            Action::ToggleCountdown => {
+                if self.sticky_note.is_empty() || self.current_read_only() {
+                    return;
+                }
+                let device = self.config.device_name.clone();
+                let todo = match self.sticky_note[self.tabs.index].list.get_selected_mut() {
+                    Some(todo) if todo.due.is_some() => todo,
+                    Some(_) => {
+                        self.toasts.error("A countdown needs a due time");
+                        return;
+                    }
+                    None => return,
+                };
+                todo.countdown = !todo.countdown;
+                todo.touch(&device);
+                let task = todo.task.clone();
+                self.log("countdown", &task);
+            }
+            Action::ToggleJournal => {
+                if self.sticky_note.is_empty() || self.current_read_only() {
+                    return;
+                }
+                let remind = &mut self.sticky_note[self.tabs.index];
+                remind.journal = !remind.journal;
+                let text = if remind.journal { "on" } else { "off" };
+                self.toasts.info(format!("Journal {}", text));
+                self.log("journal", text);
+            }
+            Action::ToggleDryRun => {
+                self.dry_run = !self.dry_run;
+                if self.dry_run {
+                    self.toasts.info("Dry run, commands won't run");
+                } else {
+                    self.toasts.info("Commands run again");
+                }
+            }
+            Action::PickColors => {
+                self.colors = Some(ColorPicker::new(&self.config.app_colors));
+            }
+            Action::PickTheme => {
+                let mut choices = vec![theme::DEFAULT.to_string()];
+                choices.extend(theme::list());
+                self.modal = Some(Modal::pick("Theme", choices, ModalAction::SetTheme));
+            }
+            Action::PickIcon => {
+                if self.sticky_note.is_empty() || self.sticky_note[self.tabs.index].is_locked() {
+                    return;
+                }
+                let mut choices = vec!["None".to_string()];
+                choices.extend(symbols::choices());
+                self.modal = Some(Modal::pick(
+                    "Sticky note icon",
+                    choices,
+                    ModalAction::SetIcon(self.tabs.index),
+                ));
+            }
+            Action::Key(key) => self.dispatch_key(key.into()),
+        }
+    }
+
+    /// Starts recording, or stops and saves what was recorded as the
+    /// `recorded` macro in the config.
+    fn toggle_recording(&mut self) {
+        let actions = match self.recording.take() {
+            Some(actions) => actions,
+            None => {
+                self.recording = Some(Vec::new());
+                self.toasts.info(format!(
+                    "Recording, ctrl-{} stops",
+                    self.config.record_macro_char_ctrl
+                ));
+                return;
+            }
+        };
+        if actions.is_empty() {
+            self.toasts.info("Nothing recorded");
+            return;
+        }
+
+        let count = actions.len();
+        match self
+            .config
+            .macros
+            .iter_mut()
+            .find(|m| m.name == action::RECORDED)
+        {
+            Some(recorded) => recorded.actions = actions,
+            None => self.config.macros.push(Macro {
+                name: action::RECORDED.into(),
+                key: None,
+                actions,
+            }),
+        }
+        self.log("record macro", &format!("{} actions", count));
+        match config::save_config(&self.config) {
+            Ok(()) => self.toasts.info(format!(
+                "Recorded {} actions, ctrl-{} plays them",
+                count, self.config.play_macro_char_ctrl
+            )),
+            Err(e) => self
+                .toasts
+                .error(format!("Saving the config failed: {}", e)),
+        }
+    }
+
+    /// Makes a fresh sticky note for every `recurring` template that is due,
+    /// unfinished todos are carried over and the notes made before archived.
+    /// Records the habits done today and opens them all again once a new day starts.
+    fn track_habits(&mut self) {
+        let today = Local::now().naive_local().date();
+        let yesterday = std::mem::replace(&mut self.habit_day, today);
+        for remind in self
+            .sticky_note
+            .items
+            .iter_mut()
+            .filter(|n| n.habit && !n.is_locked())
+        {
+            self.smart_stale |= habit::sync(remind, yesterday);
+            if today != yesterday {
+                habit::reset(remind, today);
+                self.smart_stale = true;
+            }
+        }
+    }
+
+    fn make_recurring(&mut self) {
+        if self.config.recurring.is_empty() {
+            return;
+        }
+        let now = Local::now();
+        for recurring in self.config.recurring.clone() {
+            let due = match recurring.last_due(now, &self.holidays) {
+                Some(due) => due,
+                None => continue,
+            };
+            let old = self.instances_of(&recurring.name);
+            let latest = old.last().map(|(_, due)| *due);
+            if latest.is_some_and(|latest| latest >= due.timestamp()) {
+                continue;
+            }
+
+            let remind = recurring.make(due);
+            self.log("make recurring note", &remind.title);
+            self.toasts.info(format!("Made {}", remind.title));
+            self.tabs.titles.push(remind.title.clone());
+            self.sticky_note.items.push(remind);
+            self.smart_stale = true;
+            let new = self.sticky_note.len() - 1;
+            if let (Some(mode), Some((prev, _))) = (recurring.carry_over, old.last()) {
+                self.carry_over(*prev, new, mode);
+            }
+            self.assign_todo_ids();
+
+            if recurring.archive {
+                let notes = old
+                    .iter()
+                    .map(|(i, _)| self.sticky_note[*i].clone())
+                    .collect::<Vec<_>>();
+                if let Err(e) = config::archive_notes(&notes) {
+                    self.toasts.error(format!("Archiving failed: {}", e));
+                    continue;
+                }
+                for (idx, _) in old.into_iter().rev() {
+                    self.remove_note(idx);
+                }
+            }
+        }
+    }
+
+    /// Starts uploading the current sticky note, secret ones are never published.
+    fn publish(&mut self) {
+        let to = match &self.config.publish {
+            Some(to) => to,
+            None => {
+                self.toasts
+                    .error("Set `publish` in the config to publish sticky notes");
+                return;
+            }
+        };
+        let remind = match self.sticky_note.items.get(self.tabs.index) {
+            Some(remind) if remind.secret.is_some() => {
+                self.toasts.error("Secret sticky notes can't be published");
+                return;
+            }
+            Some(remind) => remind,
+            None => return,
+        };
+        if self
+            .publishing
+            .is_some_and(|id| self.workers.is_running(id))
+        {
+            self.toasts.info("Still publishing");
+            return;
+        }
+        let to = to.clone();
+        let title = remind.title.clone();
+        let text = publish::markdown(remind, &self.dates);
+        let upload = title.clone();
+        let id = self
+            .workers
+            .spawn(format!("Publishing {}", title), move |_| {
+                Output::Published(publish::upload(&to, &upload, &text))
+            });
+        self.publishing = Some(id);
+        self.log("publish sticky note", &title);
+        self.toasts.info(format!("Publishing {}", title));
+    }
+
+    /// Copies the link of a finished upload.
+    fn on_published(&mut self, link: io::Result<String>) {
+        self.publishing = None;
+        match link {
+            Ok(link) => match clipboard::copy(&link) {
+                Ok(()) => self
+                    .toasts
+                    .info(format!("Published {}, the link is copied", link)),
+                Err(e) => {
+                    tracing::warn!("copying {} failed: {}", link, e);
+                    self.toasts.info(format!("Published {}", link));
+                }
+            },
+            Err(e) => self.toasts.error(format!("Publishing failed: {}", e)),
+        }
+    }
+
+    /// Adds a tab for every `remote_notes` URL that has none yet and starts fetching them all.
+    fn make_remote(&mut self) {
+        for remote in self.config.remote_notes.clone() {
+            if !self
+                .sticky_note
+                .iter()
+                .any(|n| n.url.as_ref() == Some(&remote.url))
+            {
+                self.log("subscribe to sticky note", &remote.url);
+                self.tabs.titles.push(remote.title.clone());
+                self.sticky_note.items.push(Remind {
+                    url: Some(remote.url),
+                    ..Remind::new(remote.title)
+                });
+            }
+        }
+        let urls = self
+            .sticky_note
+            .iter()
+            .filter_map(|n| n.url.clone())
+            .collect::<Vec<_>>();
+        for url in urls {
+            self.fetch_remote(&url);
+        }
+    }
+
+    fn fetch_remote(&mut self, url: &str) {
+        if !self.remote.start(url) {
+            return;
+        }
+        let url = url.to_string();
+        self.workers
+            .spawn(format!("Fetching {}", url), move |progress| {
+                Output::Fetched(remote::fetch(&url, progress))
+            });
+    }
+
+    /// Fetches the remote sticky notes due a refresh.
+    fn refresh_remote(&mut self) {
+        let stale = self
+            .config
+            .remote_notes
+            .iter()
+            .filter(|r| r.refresh_minutes != 0 && self.remote.is_stale(&r.url, r.refresh_minutes))
+            .map(|r| r.url.clone())
+            .collect::<Vec<_>>();
+        for url in stale {
+            self.fetch_remote(&url);
+        }
+    }
+
+    /// Fills in the remote sticky notes of a fetch that finished.
+    fn on_fetched(&mut self, fetched: Fetched) {
+        self.remote.finished(&fetched.url);
+        let (note, todos) = match fetched.parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                tracing::warn!("fetching {} failed: {}", fetched.url, e);
+                self.toasts
+                    .error(format!("Fetching {} failed: {}", fetched.url, e));
+                return;
+            }
+        };
+        let url = Some(fetched.url);
+        for remind in self.sticky_note.items.iter_mut().filter(|n| n.url == url) {
+            // the first fetch fills an empty note, later ones that change it
+            // mark the tab unread
+            let fetched_before = !remind.list.is_empty() || !remind.note.is_empty();
+            let changed = remind.note != note
+                || remind.list.len() != todos.len()
+                || remind
+                    .list
+                    .iter()
+                    .zip(todos.iter())
+                    .any(|(old, new)| old.task != new.task || old.status != new.status);
+            let list = &mut remind.list;
+            list.selected = list.selected.min(todos.len().saturating_sub(1));
+            list.items = todos.clone();
+            remind.note = note.clone();
+            if fetched_before && changed {
+                remind.touch();
+            }
+        }
+    }
+
+    /// Adds a tab for every feed that has none yet and starts fetching them all.
+    fn make_feeds(&mut self) {
+        for feed in self.config.feeds.clone() {
+            if !self
+                .sticky_note
+                .iter()
+                .any(|n| n.feed.as_ref() == Some(&feed.url))
+            {
+                self.log("subscribe to feed", &feed.url);
+                self.tabs.titles.push(feed.title.clone());
+                self.sticky_note.items.push(Remind {
+                    feed: Some(feed.url),
+                    ..Remind::new(feed.title)
+                });
+            }
+        }
+        let urls = self
+            .sticky_note
+            .iter()
+            .filter_map(|n| n.feed.clone())
+            .collect::<Vec<_>>();
+        for url in urls {
+            self.fetch_feed(&url);
+        }
+    }
+
+    fn fetch_feed(&mut self, url: &str) {
+        if !self.remote.start(url) {
+            return;
+        }
+        let url = url.to_string();
+        self.workers
+            .spawn(format!("Fetching {}", url), move |progress| {
+                Output::FeedFetched(feed::fetch(&url, progress))
+            });
+    }
+
+    /// Fetches the feeds due a refresh.
+    fn refresh_feeds(&mut self) {
+        let stale = self
+            .config
+            .feeds
+            .iter()
+            .filter(|f| f.refresh_minutes != 0 && self.remote.is_stale(&f.url, f.refresh_minutes))
+            .map(|f| f.url.clone())
+            .collect::<Vec<_>>();
+        for url in stale {
+            self.fetch_feed(&url);
+        }
+    }
+
+    /// Adds the new entries of a feed that was fetched as todos.
+    fn on_feed_fetched(&mut self, fetched: FeedFetched) {
+        self.remote.finished(&fetched.url);
+        let entries = match fetched.entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("fetching {} failed: {}", fetched.url, e);
+                self.toasts
+                    .error(format!("Fetching {} failed: {}", fetched.url, e));
+                return;
+            }
+        };
+        let next_id = self.sticky_note.next_todo_id();
+        let device = self.config.device_name.clone();
+        let url = fetched.url;
+        let added = match self
+            .sticky_note
+            .items
+            .iter_mut()
+            .find(|n| n.feed.as_ref() == Some(&url))
+        {
+            Some(remind) => feed::merge(remind, entries, next_id, &device),
+            None => return,
+        };
+        if added > 0 {
+            self.log("feed entries", &format!("{} new from {}", added, url));
+        }
+    }
+
+    /// The index and due time of every sticky note made from the template
+    /// `name`, by index.
+    fn instances_of(&self, name: &str) -> Vec<(usize, i64)> {
+        let mut found = self
+            .sticky_note
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| {
+                n.instance
+                    .as_ref()
+                    .filter(|inst| inst.name == name)
+                    .map(|inst| (i, inst.due))
+            })
+            .collect::<Vec<_>>();
+        found.sort_by_key(|(_, due)| *due);
+        found
+    }
+
+    /// Moves or copies the unfinished todos of sticky note `from` to `to`.
+    fn carry_over(&mut self, from: usize, to: usize, mode: CarryOver) -> usize {
+        // the list of a locked secret note is empty and written out in plain text
+        if self.sticky_note[from].is_locked() || self.sticky_note[to].is_locked() {
+            return 0;
+        }
+        let list = &mut self.sticky_note[from].list;
+        let unfinished = list
+            .iter()
+            .filter(|t| !t.status.is_closed())
+            .cloned()
+            .collect::<Vec<_>>();
+        if mode == CarryOver::Move {
+            list.items.retain(|t| t.status.is_closed());
+            list.selected = list.selected.min(list.items.len().saturating_sub(1));
+        }
+        let count = unfinished.len();
+        for mut todo in unfinished {
+            todo.carried += 1;
+            if mode == CarryOver::Copy {
+                // the copy is a todo of its own, the original keeps its timer
+                todo.id = 0;
+                todo.started = None;
+                todo.tracked = 0;
+            }
+            self.sticky_note[to].list.items.push(todo);
+        }
+        self.assign_todo_ids();
+        self.sticky_note[to].touch();
+        if mode == CarryOver::Move {
+            self.sticky_note[from].touch();
+        }
+        let title = self.sticky_note[from].title.clone();
+        self.log("carry over", &format!("{} todos from {}", count, title));
+        count
+    }
+
+    /// Carries the unfinished todos of the sticky note made before the
+    /// current one, from the same template, over to the current one.
+    fn carry_over_to_current(&mut self) {
+        let current = self.tabs.index;
+        let name = match self
+            .sticky_note
+            .items
+            .get(current)
+            .and_then(|n| n.instance.as_ref())
+        {
+            Some(instance) => instance.name.clone(),
+            None => {
+                self.toasts
+                    .error("Only sticky notes made from a recurring template carry over");
+                return;
+            }
+        };
+        let instances = self.instances_of(&name);
+        let prev = instances
+            .iter()
+            .take_while(|(i, _)| *i != current)
+            .last()
+            .map(|(i, _)| *i);
+        let prev = match prev {
+            Some(prev) => prev,
+            None => {
+                self.toasts
+                    .error("There is no earlier note to carry over from");
+                return;
+            }
+        };
+        if self.sticky_note[prev].is_locked() {
+            self.toasts
+                .error("Unlock the earlier note to carry its todos over");
+            return;
+        }
+        let mode = self
+            .config
+            .recurring
+            .iter()
+            .find(|r| r.name == name)
+            .and_then(|r| r.carry_over)
+            .unwrap_or(CarryOver::Move);
+        let count = self.carry_over(prev, current, mode);
+        self.toasts.info(format!("Carried over {} todos", count));
+    }
+
+    /// Puts back the last todo or sticky note deleted, a todo goes back to the
+    /// sticky note it came from.
+    fn undelete(&mut self) {
+        if self.editing() {
+            return;
+        }
+        let deleted = match self.deleted.pop() {
+            Some(deleted) => deleted,
+            None => {
+                self.toasts.info("Nothing deleted to restore");
+                return;
+            }
+        };
+        let text = deleted.describe();
+        match deleted {
+            Deleted::Todo {
+                note,
+                idx,
+                mut todo,
+            } => {
+                let tab = match self.sticky_note.iter().position(|n| n.title == note) {
+                    Some(tab) if !self.sticky_note[tab].is_view() => tab,
+                    _ => {
+                        self.toasts
+                            .error(format!("The sticky note \"{}\" is gone", note));
+                        return;
                     }
+                };
+                if self.sticky_note[tab].is_locked() {
+                    self.toasts
+                        .error(format!("Unlock \"{}\" to restore the todo", note));
+                    // kept so it can be restored once the note is unlocked
+                    self.deleted.push(Deleted::Todo { note, idx, todo });
+                    return;
+                }
+                if self.find_todo(todo.id).is_some() {
+                    todo.id = self.sticky_note.next_todo_id();
                 }
+                self.tabs.index = tab;
+                self.sticky_note.selected = tab;
+                let list = &mut self.sticky_note[tab].list;
+                let idx = idx.min(list.len());
+                list.items.insert(idx, todo);
+                list.selected = idx;
             }
-            // New Todo
-            c if c == self.config.new_todo_char_ctrl => {
-                let flag = self.new_todo;
-                self.reset_new_flag();
-                self.new_todo = !flag;
+            Deleted::StickyNote { idx, mut remind } => {
+                let taken = self
+                    .sticky_note
+                    .iter()
+                    .flat_map(|n| n.list.iter().map(|t| t.id))
+                    .collect::<HashSet<_>>();
+                for todo in remind.list.items.iter_mut() {
+                    if taken.contains(&todo.id) {
+                        todo.id = 0;
+                    }
+                }
+                let idx = idx.min(self.sticky_note.len());
+                self.tabs.titles.insert(idx, remind.title.clone());
+                self.tabs.inserted(idx);
+                self.sticky_note.items.insert(idx, remind);
+                self.tabs.index = idx;
+                self.sticky_note.selected = idx;
+                self.assign_todo_ids();
+                self.refresh_smart_notes();
             }
-            // Edit Todo
-            c if c == self.config.edit_todo_char_ctrl => {
-                let flag = self.edit_todo;
-                self.reset_new_flag();
-                self.edit_todo = !flag;
+        }
+        self.toasts.info(format!("Restored the {}", text));
+        self.log("restore", &text);
+    }
 
-                if self.edit_todo {
-                    self.add_todo.task = self
-                        .sticky_note
-                        .items
-                        .get(self.tabs.index)
-                        .map(|n| n.list.get_selected().map(|t| t.task.clone()))
-                        .flatten()
-                        .unwrap_or_default();
+    /// Removes the sticky note at `idx` keeping the same one selected if it's still there.
+    fn remove_note(&mut self, idx: usize) -> Remind {
+        let remind = self.sticky_note.items.remove(idx);
+        self.tabs.titles.remove(idx);
+        self.tabs.removed(idx);
+        if self.tabs.index > idx || self.tabs.index >= self.tabs.titles.len() {
+            self.tabs.index = self.tabs.index.saturating_sub(1);
+        }
+        self.sticky_note.selected = self.tabs.index;
+        remind
+    }
 
-                    self.add_todo.cmd = self
-                        .sticky_note
-                        .items
-                        .get(self.tabs.index)
-                        .map(|n| n.list.get_selected().map(|t| t.cmd.clone()))
-                        .flatten()
-                        .unwrap_or_default();
+    /// Handles keys while a modal is open, only quitting gets past it.
+    pub fn on_modal_key(&mut self, key: Key) {
+        if key == Key::Ctrl(self.config.exit_key_char_ctrl) {
+            self.quit();
+            return;
+        }
+        let result = match self.modal.as_mut() {
+            Some(modal) => modal.on_key(key),
+            None => return,
+        };
+        if let Some(result) = result {
+            if let Some(modal) = self.modal.take() {
+                self.on_modal_result(modal.action, result, modal.input.unwrap_or_default());
+            }
+        }
+    }
+
+    fn on_modal_result(&mut self, action: ModalAction, result: ModalResult, input: String) {
+        match action {
+            ModalAction::RemoveStickyNote(tab_idx) => {
+                if result != ModalResult::Chosen(0) || tab_idx >= self.sticky_note.len() {
+                    return;
+                }
+                self.log("remove sticky note", "");
+                let remind = self.remove_note(tab_idx);
+                self.deleted.push(Deleted::StickyNote {
+                    idx: tab_idx,
+                    remind,
+                });
+            }
+            ModalAction::Attach(tab_idx) => {
+                let path = input.trim();
+                if result != ModalResult::Chosen(0)
+                    || path.is_empty()
+                    || tab_idx >= self.sticky_note.len()
+                {
+                    return;
                 }
+                if !attach::exists(path) {
+                    self.toasts.error(format!("{} doesn't exist", path));
+                    return;
+                }
+                self.tabs.index = tab_idx;
+                self.sticky_note.selected = tab_idx;
+                self.sticky_note[tab_idx].attachments.push(path.to_string());
+                self.log("attach", path);
             }
-            // New Sticky Note
-            c if c == self.config.new_sticky_note_char_ctrl => {
-                let flag = self.new_reminder;
-                self.reset_new_flag();
-                self.new_reminder = !flag;
+            ModalAction::OpenAttachment(tab_idx) => {
+                if let ModalResult::Chosen(idx) = result {
+                    self.open_attachment(tab_idx, idx);
+                }
             }
-            // Add to or New Note
-            c if c == self.config.new_note_char_ctrl => {
-                let flag = self.new_note;
-                self.reset_new_flag();
-                self.new_note = !flag;
+            ModalAction::RemoveAttachment(tab_idx) => {
+                let idx = match result {
+                    ModalResult::Chosen(idx) => idx,
+                    ModalResult::Cancelled => return,
+                };
+                let attachments = match self.sticky_note.items.get_mut(tab_idx) {
+                    Some(remind) if idx < remind.attachments.len() => &mut remind.attachments,
+                    _ => return,
+                };
+                let path = attachments.remove(idx);
+                self.tabs.index = tab_idx;
+                self.sticky_note.selected = tab_idx;
+                self.log("remove attachment", &path);
             }
-            // Remove Sticky Note
-            c if c == self.config.remove_sticky_note_char_ctrl => {
-                if !self.sticky_note.is_empty() {
-                    let tab_idx = self.tabs.index;
-                    self.sticky_note.items.remove(tab_idx);
-                    self.sticky_note.select_previous();
-                    self.tabs.titles.remove(tab_idx);
-                    self.tabs.previous();
+            ModalAction::RunCommand => {
+                if self.pending_cmds.is_empty() {
+                    return;
                 }
+                let (id, label, cmd) = self.pending_cmds.remove(0);
+                if result == ModalResult::Chosen(0) {
+                    self.start_cmd(id, label, cmd);
+                } else {
+                    self.log("refuse command", &cmd);
+                }
+                self.confirm_next_cmd();
             }
-            // Save current Sticky Notes to DB
-            c if c == self.config.save_state_to_db_char_ctrl => {
-                config::save_db(&self.sticky_note).expect("save to DB failed");
+            ModalAction::SetHighlight => {
+                if let ModalResult::Chosen(idx) = result {
+                    if let Some((symbol, _)) = symbols::SYMBOLS.get(idx) {
+                        self.config.highlight_string = symbol.to_string();
+                        self.save_config();
+                    }
+                }
+            }
+            ModalAction::Quit => {
+                if result == ModalResult::Chosen(0) {
+                    self.quit();
+                }
+            }
+            ModalAction::SetTheme => {
+                let name = match result {
+                    ModalResult::Chosen(0) => theme::DEFAULT.to_string(),
+                    ModalResult::Chosen(idx) => match theme::list().get(idx - 1) {
+                        Some(name) => name.clone(),
+                        None => return,
+                    },
+                    ModalResult::Cancelled => return,
+                };
+                match theme::load(&name) {
+                    Ok(colors) => {
+                        self.config.app_colors = colors;
+                        self.save_config();
+                        self.toasts.info(format!("Switched to the {} theme", name));
+                    }
+                    Err(e) => self
+                        .toasts
+                        .error(format!("Loading the {} theme failed: {}", name, e)),
+                }
+            }
+            ModalAction::RestoreDraft => {
+                let draft = match self.restore.take() {
+                    Some(draft) => draft,
+                    None => return,
+                };
+                if result == ModalResult::Chosen(0) {
+                    self.restore_draft(draft);
+                } else if let Err(e) = draft::remove() {
+                    self.toasts
+                        .error(format!("Removing the draft failed: {}", e));
+                }
+            }
+            ModalAction::SetIcon(tab_idx) => {
+                let idx = match result {
+                    ModalResult::Chosen(idx) if tab_idx < self.sticky_note.len() => idx,
+                    _ => return,
+                };
+                let icon = idx
+                    .checked_sub(1)
+                    .and_then(|i| symbols::SYMBOLS.get(i))
+                    .map(|(symbol, _)| symbol.to_string());
+                self.tabs.index = tab_idx;
+                self.sticky_note.selected = tab_idx;
+                self.sticky_note[tab_idx].icon = icon.clone();
+                self.log("set icon", &icon.unwrap_or_default());
             }
-            _ => {}
         }
     }
 
-    pub fn on_tick(&mut self) {
-        // self.cmd_handle
+    /// Called for every key press, keeps the idle lock from firing.
+    pub fn on_input(&mut self) {
+        self.last_input = Instant::now();
+    }
+
+    /// Handles keys while the idle lock screen is up, only the passphrase
+    /// and quitting get through.
+    pub fn on_lock_key(&mut self, key: Key) {
+        match key {
+            Key::Char('\n') => {
+                let pass = std::mem::take(&mut self.unlock.pass);
+                match secret::verify_passphrase(&pass, &self.config.idle_lock_hash) {
+                    Ok(true) => {
+                        self.locked = false;
+                        self.unlock.error = None;
+                        // keys on the lock screen don't count as input
+                        self.last_input = Instant::now();
+                    }
+                    Ok(false) => self.unlock.error = Some("wrong passphrase".into()),
+                    Err(e) => self.unlock.error = Some(e.to_string()),
+                }
+            }
+            Key::Char(c) => self.unlock.pass.push(c),
+            Key::Backspace => {
+                self.unlock.pass.pop();
+            }
+            Key::Ctrl(c) if c == self.config.exit_key_char_ctrl => self.quit(),
+            _ => {}
+        }
     }
 }
 
@@ -498,3 +4631,956 @@ mod date_fmt {
             .map_err(serde::de::Error::custom)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::hooks;
+    use crate::paths;
+    use crate::share;
+
+    fn dates() -> Dates {
+        config::CFG.with(|cfg| Dates::new(cfg).unwrap())
+    }
+
+    fn todo(id: u64, task: &str) -> Todo {
+        Todo::new(id, task.to_string())
+    }
+
+    fn list(tasks: &[&str]) -> ListState<Todo> {
+        ListState::new(
+            tasks
+                .iter()
+                .enumerate()
+                .map(|(i, task)| todo(i as u64 + 1, task))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn edit_keeps_history() {
+        let dates = dates();
+        let created = Local.ymd(2020, 3, 1).and_hms(8, 0, 0);
+        let mut todo = Todo {
+            date: created,
+            status: Status::InProgress,
+            runs: 3,
+            last_run: Some(42),
+            tracked: 600,
+            created_on: Some("desk".into()),
+            ..todo(7, "old")
+        };
+        let form = AddTodo {
+            task: "new".into(),
+            cmd: "make".into(),
+            estimate: "2h".into(),
+            ..AddTodo::from_todo(&todo, &dates)
+        };
+        todo.apply_edit(&form, Local::now(), &dates, "laptop");
+
+        assert_eq!(todo.task, "new");
+        assert_eq!(todo.cmd, "make");
+        assert_eq!(todo.estimate.map(|e| e.to_string()), Some("2h".into()));
+        assert_eq!(todo.id, 7);
+        assert_eq!(todo.date, created);
+        assert_eq!(todo.status, Status::InProgress);
+        assert_eq!(todo.runs, 3);
+        assert_eq!(todo.last_run, Some(42));
+        assert_eq!(todo.tracked, 600);
+        assert_eq!(todo.created_on.as_deref(), Some("desk"));
+        assert_eq!(todo.edited.map(|e| e.device), Some("laptop".into()));
+    }
+
+    #[test]
+    fn unchanged_form_changes_nothing() {
+        let dates = dates();
+        let due = Local.ymd(2030, 1, 2).and_hms(9, 30, 0).timestamp();
+        let mut todo = Todo {
+            cmd: "echo hi".into(),
+            blocked_by: Some(3),
+            due: Some(due),
+            ..todo(7, "task")
+        };
+        let form = AddTodo::from_todo(&todo, &dates);
+        assert_eq!(form.editing, Some(7));
+        todo.apply_edit(&form, Local::now(), &dates, "laptop");
+
+        assert_eq!(todo.task, "task");
+        assert_eq!(todo.cmd, "echo hi");
+        assert_eq!(todo.blocked_by, Some(3));
+        assert_eq!(todo.due, Some(due));
+    }
+
+    #[test]
+    fn status_survives_a_round_trip() {
+        for status in Status::ALL {
+            let todo = Todo {
+                status,
+                ..todo(1, "task")
+            };
+            let json = serde_json::to_string(&todo).unwrap();
+            let back = serde_json::from_str::<Todo>(&json).unwrap();
+            assert_eq!(back.status, status, "{}", json);
+        }
+    }
+
+    #[test]
+    fn completed_is_read_from_old_data_bases() {
+        let old = |completed| {
+            let json = format!(
+                r#"{{"date":"2020-03-01 08:00:00","task":"t","cmd":"","completed":{}}}"#,
+                completed
+            );
+            serde_json::from_str::<Todo>(&json).unwrap().status
+        };
+        assert_eq!(old(true), Status::Done);
+        assert_eq!(old(false), Status::Open);
+    }
+
+    #[test]
+    fn edit_cannot_block_on_itself() {
+        let dates = dates();
+        let mut todo = todo(7, "task");
+        let form = AddTodo {
+            blocked_by: "#7".into(),
+            ..AddTodo::from_todo(&todo, &dates)
+        };
+        todo.apply_edit(&form, Local::now(), &dates, "laptop");
+        assert_eq!(todo.blocked_by, None);
+    }
+
+    #[test]
+    fn edit_stays_in_place() {
+        let dates = dates();
+        let mut list = list(&["one", "two", "three"]);
+        let form = AddTodo {
+            task: "2".into(),
+            ..AddTodo::from_todo(&list.items[1], &dates)
+        };
+        // the selection moved while the form was open
+        list.selected = 0;
+        let edited = list.edit(2, &form, Local::now(), &dates, "laptop");
+
+        assert_eq!(edited.map(|t| t.task.as_str()), Some("2"));
+        assert_eq!(list.selected, 1);
+        assert_eq!(tasks(&list), ["one", "2", "three"]);
+    }
+
+    fn tasks(list: &ListState<Todo>) -> Vec<&str> {
+        list.iter().map(|t| t.task.as_str()).collect()
+    }
+
+    #[test]
+    fn delete_first() {
+        let mut list = list(&["one", "two", "three"]);
+        let removed = list.remove_selected();
+
+        assert_eq!(removed.map(|t| t.task), Some("one".into()));
+        assert_eq!(tasks(&list), ["two", "three"]);
+        assert_eq!(list.selected, 0);
+    }
+
+    #[test]
+    fn delete_middle() {
+        let mut list = list(&["one", "two", "three"]);
+        list.selected = 1;
+        let removed = list.remove_selected();
+
+        assert_eq!(removed.map(|t| t.task), Some("two".into()));
+        assert_eq!(tasks(&list), ["one", "three"]);
+        assert_eq!(list.selected, 1);
+    }
+
+    #[test]
+    fn delete_last() {
+        let mut list = list(&["one", "two", "three"]);
+        list.selected = 2;
+        let removed = list.remove_selected();
+
+        assert_eq!(removed.map(|t| t.task), Some("three".into()));
+        assert_eq!(tasks(&list), ["one", "two"]);
+        assert_eq!(list.selected, 1);
+    }
+
+    #[test]
+    fn delete_only() {
+        let mut list = list(&["one"]);
+        let removed = list.remove_selected();
+
+        assert_eq!(removed.map(|t| t.task), Some("one".into()));
+        assert!(list.is_empty());
+        assert_eq!(list.selected, 0);
+        assert!(list.remove_selected().is_none());
+    }
+
+    #[test]
+    fn delete_past_the_end() {
+        let mut list = list(&["one", "two"]);
+        list.selected = 5;
+
+        assert!(list.remove_selected().is_none());
+        assert_eq!(tasks(&list), ["one", "two"]);
+        assert_eq!(list.selected, 1);
+    }
+
+    #[test]
+    fn edit_of_removed_todo() {
+        let dates = dates();
+        let mut list = list(&["one", "two"]);
+        let form = AddTodo::from_todo(&list.items[1], &dates);
+        list.items.pop();
+
+        assert!(list
+            .edit(2, &form, Local::now(), &dates, "laptop")
+            .is_none());
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.selected, 0);
+    }
+
+    #[derive(Clone, Debug)]
+    enum ListOp {
+        Up,
+        Down,
+        Remove,
+        Add(String),
+        Undelete,
+    }
+
+    fn list_op() -> impl Strategy<Value = ListOp> {
+        prop_oneof![
+            Just(ListOp::Up),
+            Just(ListOp::Down),
+            Just(ListOp::Remove),
+            "[a-z]{1,8}".prop_map(ListOp::Add),
+            Just(ListOp::Undelete),
+        ]
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.on_key_press(Key::Char(c));
+        }
+    }
+
+    fn tasks_of(app: &App) -> Vec<String> {
+        app.sticky_note[0]
+            .list
+            .iter()
+            .map(|t| t.task.clone())
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn list_selection_stays_in_bounds(
+            todos in 0..5usize,
+            ops in prop::collection::vec(list_op(), 1..60),
+        ) {
+            let mut app = app(1);
+            app.config.enter_debounce_ms = 0;
+            app.sticky_note[0].list.items.truncate(todos);
+            let mut model = tasks_of(&app);
+            // what undelete puts back, the oldest falls off like `RecentlyDeleted`
+            let mut deleted = Vec::new();
+            for op in ops {
+                let selected = app.sticky_note[0].list.selected;
+                match &op {
+                    ListOp::Up => app.on_key_press(Key::Up),
+                    ListOp::Down => app.on_key_press(Key::Down),
+                    ListOp::Remove => {
+                        app.on_key_press(Key::from(app.config.remove_todo));
+                        if selected < model.len() {
+                            if deleted.len() == 10 {
+                                deleted.remove(0);
+                            }
+                            deleted.push((selected, model.remove(selected)));
+                        }
+                    }
+                    ListOp::Add(task) => {
+                        app.on_key_press(Key::Ctrl(app.config.new_todo_char_ctrl));
+                        type_text(&mut app, task);
+                        app.on_key_press(Key::Char('\n'));
+                        model.push(task.clone());
+                    }
+                    ListOp::Undelete => {
+                        app.on_key_press(Key::Alt('u'));
+                        if let Some((idx, task)) = deleted.pop() {
+                            model.insert(idx.min(model.len()), task);
+                        }
+                    }
+                }
+                prop_assert_eq!(&tasks_of(&app), &model, "after {:?}", op);
+                let list = &app.sticky_note[0].list;
+                prop_assert!(
+                    list.selected < list.len().max(1),
+                    "after {:?}: selected {} of {}",
+                    op,
+                    list.selected,
+                    list.len()
+                );
+            }
+        }
+    }
+
+    fn app(notes: usize) -> App {
+        let items = (0..notes)
+            .map(|n| Remind {
+                list: list(&["a", "b", "c"]),
+                ..Remind::new(format!("Note {}", n))
+            })
+            .collect();
+        let config = config::CFG.with(|cfg| cfg.clone());
+        let mut app = App::with_notes(ListState::new(items), config).unwrap();
+        // nothing is written to the real activity log
+        app.activity = ActivityLog::default();
+        app
+    }
+
+    fn titles(app: &App) -> Vec<String> {
+        app.sticky_note.iter().map(|n| n.title.clone()).collect()
+    }
+
+    #[test]
+    fn editor_follows_the_blocking_rule() {
+        let mut app = app(1);
+        app.sticky_note[0].list.items[1].blocked_by = Some(1);
+        app.sticky_note[0].list.selected = 2;
+        let text = checklist::dump(&app.sticky_note[0])
+            .replace("[ ] b", "[x] b")
+            .replace("[ ] c", "[x] c");
+        app.apply_checklist(checklist::parse(&text));
+
+        let statuses = app.sticky_note[0]
+            .list
+            .iter()
+            .map(|t| t.status)
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, [Status::Open, Status::Open, Status::Done]);
+        assert_eq!(app.sticky_note[0].list.selected, 2);
+        assert!(app.toasts.list.iter().any(|t| t.text == "Blocked by #1"));
+    }
+
+    #[test]
+    fn secrets_are_not_edited_in_an_editor() {
+        let mut app = app(1);
+        app.sticky_note[0].secret = Some(String::new());
+        app.start_external_edit();
+        assert!(app.external_edit.is_none());
+    }
+
+    #[derive(Clone, Debug)]
+    enum TabOp {
+        Left,
+        Right,
+        LastTab,
+        Up,
+        Down,
+        RemoveTodo,
+        Undelete,
+        RemoveNote,
+        AddNote(String),
+    }
+
+    fn tab_op() -> impl Strategy<Value = TabOp> {
+        prop_oneof![
+            Just(TabOp::Left),
+            Just(TabOp::Right),
+            Just(TabOp::LastTab),
+            Just(TabOp::Up),
+            Just(TabOp::Down),
+            Just(TabOp::RemoveTodo),
+            Just(TabOp::Undelete),
+            Just(TabOp::RemoveNote),
+            "[a-z]{1,8}".prop_map(TabOp::AddNote),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn tabs_follow_the_sticky_notes(
+            notes in 0..4usize,
+            ops in prop::collection::vec(tab_op(), 1..60),
+        ) {
+            let mut app = app(notes);
+            app.config.enter_debounce_ms = 0;
+            for op in ops {
+                let before = app.sticky_note.len();
+                match &op {
+                    TabOp::Left => app.on_key_press(Key::Left),
+                    TabOp::Right => app.on_key_press(Key::Right),
+                    TabOp::LastTab => app.on_key_press(Key::BackTab),
+                    TabOp::Up => app.on_key_press(Key::Up),
+                    TabOp::Down => app.on_key_press(Key::Down),
+                    TabOp::RemoveTodo => app.on_key_press(Key::from(app.config.remove_todo)),
+                    TabOp::Undelete => app.on_key_press(Key::Alt('u')),
+                    TabOp::RemoveNote => {
+                        app.on_key_press(Key::Ctrl(app.config.remove_sticky_note_char_ctrl));
+                        app.on_key_press(Key::Char('y'));
+                        prop_assert_eq!(app.sticky_note.len(), before.saturating_sub(1));
+                    }
+                    TabOp::AddNote(title) => {
+                        app.on_key_press(Key::Ctrl(app.config.new_sticky_note_char_ctrl));
+                        type_text(&mut app, title);
+                        app.on_key_press(Key::Char('\n'));
+                        prop_assert_eq!(app.sticky_note.len(), before + 1);
+                    }
+                }
+                prop_assert_eq!(&app.tabs.titles, &titles(&app), "after {:?}", op);
+                if app.sticky_note.is_empty() {
+                    prop_assert_eq!(app.tabs.index, 0, "after {:?}", op);
+                    continue;
+                }
+                prop_assert!(app.tabs.index < app.sticky_note.len(), "after {:?}", op);
+                let list = &app.sticky_note[app.tabs.index].list;
+                prop_assert!(list.selected < list.len().max(1), "after {:?}", op);
+            }
+        }
+    }
+
+    #[test]
+    fn esc_closes_the_form_before_quitting() {
+        let mut app = app(1);
+        app.on_key_press(Key::Ctrl(app.config.new_todo_char_ctrl));
+        for c in "half typed".chars() {
+            app.on_key_press(Key::Char(c));
+        }
+        app.on_key_press(Key::Esc);
+        assert!(!app.new_todo);
+        assert!(app.add_todo.task.is_empty());
+        assert!(!app.should_quit);
+        assert_eq!(app.sticky_note[0].list.len(), 3);
+
+        app.on_key_press(Key::Esc);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn esc_asks_before_quitting_with_confirm_quit() {
+        let mut app = app(1);
+        app.config.confirm_quit = true;
+        app.on_key_press(Key::Esc);
+        assert!(app.modal.is_some());
+        app.on_key_press(Key::Esc);
+        assert!(app.modal.is_none());
+        assert!(!app.should_quit);
+
+        app.on_key_press(Key::Esc);
+        app.on_key_press(Key::Char('y'));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn enter_bursts_save_once() {
+        let mut app = app(1);
+        let add = |app: &mut App, task: &str| {
+            app.on_key_press(Key::Ctrl(app.config.new_todo_char_ctrl));
+            for c in task.chars() {
+                app.on_key_press(Key::Char(c));
+            }
+            app.on_key_press(Key::Char('\n'));
+        };
+        add(&mut app, "first");
+        add(&mut app, "second");
+        assert_eq!(app.sticky_note[0].list.len(), 4);
+        assert!(app.new_todo);
+
+        app.last_enter = Some(Instant::now() - Duration::from_secs(1));
+        app.on_key_press(Key::Char('\n'));
+        assert_eq!(app.sticky_note[0].list.len(), 5);
+    }
+
+    #[test]
+    fn expired_toasts_fold_away() {
+        let mut app = app(1);
+        app.toasts = Toasts::new(2);
+        app.toasts.info("saved");
+        assert!(!app.animating());
+
+        app.on_tick();
+        app.on_tick();
+        assert!(app.toasts.list[0].leaving);
+        assert!(app.animating());
+        let done = app.animations.progress(Anim::ToastsLeaving).unwrap();
+        assert!((0.0..1.0).contains(&done));
+
+        app.on_tick();
+        assert!(app.toasts.list.is_empty());
+    }
+
+    #[test]
+    fn bad_color_rules_are_skipped() {
+        let mut config = config::CFG.with(|cfg| cfg.clone());
+        let style = config.color_rules[0].style.clone();
+        config.color_rules = vec![
+            config::ColorRule {
+                pattern: "[oops".into(),
+                style: style.clone(),
+                max_runs: None,
+            },
+            config::ColorRule {
+                pattern: "a".into(),
+                style,
+                max_runs: None,
+            },
+        ];
+        let notes = ListState::new(vec![Remind::new("Note".into())]);
+        let app = App::with_notes(notes, config).unwrap();
+        assert!(app.toasts.list.iter().any(|t| t.text.contains("[oops")));
+        assert!(app.rules.style_for(&todo(1, "a"), false).is_some());
+    }
+
+    #[test]
+    fn saving_keeps_keys_of_other_builds() {
+        let config = config::CFG.with(|cfg| cfg.clone());
+        let mut json = serde_json::to_value(&config).unwrap();
+        json["from_another_build"] = serde_json::json!({ "url": "x" });
+        let config: config::AppConfig = serde_json::from_value(json).unwrap();
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["from_another_build"]["url"], "x");
+        assert_eq!(json["title"], "Forget It");
+    }
+
+    #[test]
+    fn changed_tabs_stay_unread_until_shown() {
+        let mut app = app(2);
+        assert!(!app.session.is_unread(&app.sticky_note[1]));
+        app.sticky_note[1].updated_at = Some(Local::now().timestamp() + 60);
+        app.on_key_press(Key::Down);
+        assert!(app.session.is_unread(&app.sticky_note[1]));
+
+        app.on_right();
+        app.on_key_press(Key::Down);
+        app.on_left();
+        assert!(!app.session.is_unread(&app.sticky_note[1]));
+    }
+
+    #[test]
+    fn config_paths_fill_in_variables() {
+        std::env::set_var("FORGET_TEST_DIR", "/srv/notes");
+        std::env::remove_var("FORGET_TEST_UNSET");
+        assert_eq!(
+            paths::interpolate("$FORGET_TEST_DIR/db.json ${FORGET_TEST_DIR}x $FORGET_TEST_UNSET."),
+            "/srv/notes/db.json /srv/notesx ."
+        );
+        assert_eq!(paths::interpolate("$$5 ${open $"), "$5 ${open $");
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(
+            paths::expand("~/.forget/db.json"),
+            home.join(".forget/db.json")
+        );
+        assert_eq!(paths::expand("~"), home);
+        assert_eq!(
+            paths::expand("$FORGET_TEST_DIR/~"),
+            PathBuf::from("/srv/notes/~")
+        );
+    }
+
+    #[test]
+    fn blocking_todos_for_git_hooks() {
+        let mut app = app(2);
+        app.sticky_note[0].list[0].task = "ship it #Blocking.".into();
+        app.sticky_note[0].list[1].task = "#blocking but done".into();
+        app.sticky_note[0].list[1].set_status(Status::Done);
+        app.sticky_note[1].list[2].task = "not #blockingish".into();
+        let blocking = hooks::blocking(&app.sticky_note)
+            .into_iter()
+            .map(|(note, t)| (note, t.task.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(blocking, vec![("Note 0", "ship it #Blocking.")]);
+    }
+
+    #[test]
+    fn feed_entries_become_todos_once() {
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Blog</title>
+            <item><title>Second &amp; last</title><link>https://blog.example/2</link></item>
+            <item><title><![CDATA[First <post>]]></title><link>https://blog.example/1</link></item>
+            </channel></rss>"#;
+        let entries = feed::parse(rss).unwrap();
+        assert_eq!(entries[0].title, "Second & last");
+        assert_eq!(entries[1].title, "First <post>");
+        assert_eq!(entries[1].link.as_deref(), Some("https://blog.example/1"));
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>News</title>
+            <entry><title type="html">Launch</title>
+            <link rel="replies" href="https://news.example/1#comments"/>
+            <link href="https://news.example/1"/></entry></feed>"#;
+        assert_eq!(
+            feed::parse(atom).unwrap(),
+            vec![feed::Entry {
+                title: "Launch".into(),
+                link: Some("https://news.example/1".into()),
+            }]
+        );
+        assert!(feed::parse("<html></html>").is_err());
+
+        let mut remind = Remind::new("Blog".into());
+        assert_eq!(feed::merge(&mut remind, entries, 7, "laptop"), 2);
+        assert_eq!(remind.list[0].task, "First <post>");
+        assert_eq!(remind.list[1].id, 8);
+        assert_eq!(remind.attachments.len(), 2);
+
+        // read entries stay read and only the new one is added
+        remind.list[0].set_status(Status::Done);
+        remind.list.items.remove(1);
+        let rss = rss.replace(
+            "<item>",
+            "<item><title>Third</title><link>https://blog.example/3</link></item><item>",
+        );
+        let entries = feed::parse(&rss).unwrap();
+        assert_eq!(feed::merge(&mut remind, entries, 9, "laptop"), 1);
+        assert_eq!(remind.list.len(), 2);
+        assert_eq!(remind.list[0].status, Status::Done);
+        assert_eq!(remind.list[1].task, "Third");
+    }
+
+    #[test]
+    fn new_secret_needs_the_passphrase_twice() {
+        let mut app = app(1);
+        let type_in = |app: &mut App, text: &str| {
+            for c in text.chars() {
+                app.on_key_press(Key::Char(c));
+            }
+        };
+        app.on_key_press(Key::Ctrl(app.config.secret_char_ctrl));
+        type_in(&mut app, "hunter2\n");
+        assert!(app.add_passphrase.confirming);
+        type_in(&mut app, "hunter3\n");
+        assert!(app.passphrase);
+        assert!(app.add_passphrase.error.is_some());
+        assert!(app.add_passphrase.pass.is_empty() && !app.add_passphrase.confirming);
+        assert!(app.sticky_note[0].secret.is_none());
+
+        type_in(&mut app, "hunter2\nhunter2\n");
+        assert!(!app.passphrase);
+        assert!(app.sticky_note[0].secret.is_some());
+        assert_eq!(app.sticky_note[0].passphrase.as_deref(), Some("hunter2"));
+    }
+
+    #[test]
+    fn unchanged_secret_is_not_encrypted_again() {
+        let mut remind = app(1).sticky_note[0].clone();
+        remind.make_secret("pass").unwrap();
+        // openssl salts every encryption, the same text means it was reused
+        let first = remind.sealed().unwrap().secret;
+        assert_eq!(remind.sealed().unwrap().secret, first);
+        remind.list[0].task = "changed".into();
+        let changed = remind.sealed().unwrap().secret;
+        assert_ne!(changed, first);
+        assert_eq!(remind.sealed().unwrap().secret, changed);
+
+        let mut locked = remind.sealed().unwrap();
+        locked.unlock("pass").unwrap();
+        assert_eq!(locked.list[0].task, "changed");
+        assert_eq!(locked.sealed().unwrap().secret, changed);
+    }
+
+    #[test]
+    fn todos_of_secrets_are_not_drafted() {
+        let mut app = app(1);
+        app.new_todo = true;
+        app.add_todo.task = "top secret".into();
+        assert!(app.current_draft().is_some());
+        app.sticky_note[0].make_secret("pass").unwrap();
+        assert!(app.current_draft().is_none());
+    }
+
+    #[test]
+    fn nothing_is_restored_into_a_locked_secret() {
+        let mut app = app(1);
+        app.remove_todo();
+        app.sticky_note[0].make_secret("pass").unwrap();
+        app.sticky_note[0].lock().unwrap();
+        app.run_action(Action::Undelete);
+        assert!(app.sticky_note[0].list.is_empty());
+
+        app.sticky_note[0].unlock("pass").unwrap();
+        app.run_action(Action::Undelete);
+        assert_eq!(app.sticky_note[0].list.len(), 3);
+    }
+
+    #[test]
+    fn locked_secret_ids_are_not_handed_out_again() {
+        let mut app = app(2);
+        let last = app.sticky_note.next_todo_id() - 1;
+        app.sticky_note[1].make_secret("pass").unwrap();
+        app.sticky_note[1].lock().unwrap();
+        assert_eq!(app.sticky_note.next_todo_id(), last + 1);
+
+        let sealed = app.sticky_note[1].sealed().unwrap();
+        assert_eq!(sealed.max_id(), last);
+    }
+
+    #[test]
+    fn unlocking_stays_unlocked_on_the_next_tick() {
+        let mut app = app(1);
+        app.config.idle_lock_minutes = 1;
+        // `openssl passwd -6 -salt forgettest pw`
+        app.config.idle_lock_hash = "$6$forgettest$vguSr55pcNNYA1h7mTNOZT.D5tFUrck33h82VUrafkyiFUGHmcvqNrOyAyx6N6xAXJttbXkcRwLeEfl9CIckP1".into();
+        app.last_input = Instant::now() - Duration::from_secs(120);
+        app.on_tick();
+        assert!(app.locked);
+        for c in "pw\n".chars() {
+            app.on_lock_key(Key::Char(c));
+        }
+        assert!(!app.locked);
+        app.on_tick();
+        assert!(!app.locked);
+    }
+
+    #[test]
+    fn smart_notes_are_filled_again_after_changes() {
+        let mut app = app(1);
+        app.sticky_note.items.push(Remind {
+            query: Some("b".into()),
+            ..Remind::new("Smart".into())
+        });
+        app.tabs.titles.push("Smart".into());
+        app.refresh_smart_notes();
+        assert!(!app.smart_stale);
+        app.on_tick();
+        assert!(!app.smart_stale);
+
+        app.on_key_press(Key::Down);
+        assert!(app.smart_stale);
+        app.refresh_smart_notes();
+        assert_eq!(app.sticky_note[1].list.len(), 1);
+
+        app.sticky_note[0].habit = true;
+        app.sticky_note[0].list[0].status = Status::Done;
+        app.on_tick();
+        assert!(app.smart_stale);
+    }
+
+    #[test]
+    fn smart_notes_run_the_commands_they_show() {
+        let mut app = app(1);
+        app.dry_run = true;
+        app.sticky_note[0].default_cmd = "echo {task}".into();
+        app.sticky_note.items.push(Remind {
+            query: Some("b".into()),
+            ..Remind::new("Smart".into())
+        });
+        app.tabs.titles.push("Smart".into());
+        app.refresh_smart_notes();
+        app.tabs.index = 1;
+        app.on_key_press(Key::Char('\n'));
+        let toasts = app
+            .toasts
+            .list
+            .iter()
+            .map(|t| t.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(toasts, vec!["Would run: echo b"]);
+    }
+
+    #[test]
+    fn command_reports_follow_the_todo() {
+        let mut app = app(1);
+        let finished = |report, status| Finished {
+            id: 1,
+            label: "a".into(),
+            status,
+            report,
+            output: "building\nall good".into(),
+        };
+        app.report_job(finished(Report::Silent, JobStatus::Exited(1)), false);
+        app.report_job(finished(Report::Failures, JobStatus::Exited(0)), false);
+        assert!(app.toasts.list.is_empty());
+        app.report_job(finished(Report::Failures, JobStatus::Exited(2)), false);
+        assert_eq!(app.toasts.list.len(), 1);
+
+        app.report_job(finished(Report::Note, JobStatus::Exited(0)), false);
+        assert_eq!(app.toasts.list.len(), 1);
+        app.report_job(finished(Report::Note, JobStatus::Exited(1)), false);
+        let note = &app.sticky_note[0].note;
+        assert!(note.starts_with("## "), "{}", note);
+        assert!(
+            note.ends_with(" a (exit 1)\nbuilding\nall good\n"),
+            "{}",
+            note
+        );
+        assert_eq!(note.matches(" a (exit ").count(), 2);
+        assert!(note.contains("all good\n\n## "), "{}", note);
+
+        let mut form = AddTodo::from_todo(&app.sticky_note[0].list[0], &app.dates);
+        assert_eq!(form.report, "");
+        form.report = "Notify".into();
+        let todo = &mut app.sticky_note[0].list.items[0];
+        todo.apply_edit(&form, Local::now(), &Dates::default(), "here");
+        assert_eq!(todo.report, Report::Notify);
+    }
+
+    #[test]
+    fn help_closes_on_any_key() {
+        let mut app = app(2);
+        app.run_action(Action::Help);
+        assert!(app.help);
+        app.on_key_press(Key::Right);
+        assert!(!app.help);
+        // the key only closed the help
+        assert_eq!(app.tabs.index, 0);
+    }
+
+    #[test]
+    fn removing_the_first_note_does_not_wrap() {
+        let mut app = app(3);
+        app.on_key_press(Key::Ctrl(app.config.remove_sticky_note_char_ctrl));
+        app.on_key_press(Key::Char('y'));
+        assert_eq!(titles(&app), ["Note 1", "Note 2"]);
+        assert_eq!((app.tabs.index, app.sticky_note.selected), (0, 0));
+        app.on_key_press(Key::Alt('u'));
+        assert_eq!(titles(&app), ["Note 0", "Note 1", "Note 2"]);
+    }
+
+    #[test]
+    fn kiosk_only_quits() {
+        let mut app = app(3);
+        let title = Some("Note 1".to_string());
+        app.start_kiosk(Kiosk::new(Duration::from_secs(60), title.clone()))
+            .unwrap();
+        assert_eq!((app.tabs.index, app.present), (1, Some(0)));
+        app.sticky_note[1].list[0].cmd = "echo hi".into();
+        for key in [
+            Key::Down,
+            Key::Right,
+            Key::Char('x'),
+            Key::Ctrl(app.config.run_all_char_ctrl),
+            Key::from(app.config.quit_key),
+        ] {
+            app.on_key_press(key);
+        }
+        assert_eq!((app.tabs.index, app.present), (1, Some(0)));
+        assert!(app.jobs.list.is_empty());
+        assert!(!app.should_quit);
+
+        // another forget added a sticky note in front of it
+        let mut notes = app.sticky_note.clone();
+        notes.items.insert(0, Remind::new("New".to_string()));
+        app.show_kiosk_notes(notes, title);
+        assert_eq!(app.tabs.titles[app.tabs.index], "Note 1");
+        assert_eq!(app.tabs.titles.len(), 4);
+
+        app.on_key_press(Key::Ctrl(app.config.exit_key_char_ctrl));
+        assert!(app.should_quit);
+        assert!(app
+            .start_kiosk(Kiosk::new(Duration::from_secs(1), Some("Gone".into())))
+            .is_err());
+    }
+
+    #[test]
+    fn shared_notes_come_in_as_new_notes() {
+        let mut mine = app(2).sticky_note;
+        mine[0].list[0].cmd = "rm -rf ~/trip".into();
+        mine[0].list[1].blocked_by = Some(mine[0].list[0].id);
+        mine[0].list[2].blocked_by = Some(99);
+        mine[0].attachments.push("/home/me/map.pdf".into());
+        let bundle = share::bundle(&mine, &["Note 0"], false).unwrap();
+        assert!(share::bundle(&mine, &["Trip"], false).is_err());
+
+        let mut theirs = app(1).sticky_note;
+        let added = share::add(&mut theirs, bundle);
+        let from = theirs[1].shared.as_ref().unwrap().from.clone();
+        assert_eq!(added, vec![format!("Note 0 (from {})", from)]);
+        let note = &theirs[1];
+        assert!(note.attachments.is_empty());
+        assert!(note.list.iter().all(|t| t.cmd.is_empty()));
+        assert_eq!(note.list[0].id, 4);
+        assert_eq!(note.list[1].blocked_by, Some(4));
+        assert_eq!(note.list[2].blocked_by, None);
+    }
+
+    #[cfg(feature = "github")]
+    #[test]
+    fn github_issues_import_once_and_close() {
+        use crate::github::{self, Imported, Issue};
+
+        let issue = |number: u64, title: &str| Issue {
+            number,
+            title: title.into(),
+            html_url: format!("https://github.com/o/r/issues/{}", number),
+            state: "open".into(),
+            pull_request: None,
+        };
+        let mut app = app(1);
+        let open = vec![issue(1, "crash"), issue(2, "typo")];
+        let imported = github::merge(&mut app.sticky_note, "o/r", "o/r", &open, |_| false);
+        assert_eq!(
+            imported,
+            Imported {
+                added: vec![1, 2],
+                closed: vec![]
+            }
+        );
+        let note = &app.sticky_note[1];
+        assert_eq!(note.title, "o/r");
+        assert_eq!(note.list[0].id, 4);
+        assert_eq!(note.list[1].issue.as_deref(), Some("o/r#2"));
+        assert_eq!(note.attachments.len(), 2);
+
+        // #1 was closed, #2 is still open and #3 is new
+        let open = vec![issue(2, "typo"), issue(3, "docs")];
+        let imported = github::merge(&mut app.sticky_note, "o/r", "o/r", &open, |n| n == 1);
+        assert_eq!(
+            imported,
+            Imported {
+                added: vec![3],
+                closed: vec![1]
+            }
+        );
+        let note = &app.sticky_note[1];
+        assert_eq!(note.list.len(), 3);
+        assert_eq!(note.list[0].status, Status::Done);
+        assert_eq!(note.list[1].status, Status::Open);
+
+        // #2 done here is closed on the next sync, #1 already is
+        app.sticky_note[1].list[1].set_status(Status::Done);
+        let to_close = github::done_open(&app.sticky_note, "o/r", "o/r", &open);
+        assert_eq!(to_close, vec![(2, "typo")]);
+        assert_eq!(github::parse_key("o/r#12"), Some(("o/r", 12)));
+        assert_eq!(github::parse_key("o/r"), None);
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn synced_notes_merge_both_ways() {
+        use crate::sync::{Channel, Op};
+
+        // the server is a list of ops, numbered as they come in
+        fn push(server: &mut Vec<Op>, ops: Vec<Op>) -> Vec<Op> {
+            for mut op in ops.clone() {
+                op.seq = server.len() as u64 + 1;
+                server.push(op);
+            }
+            ops
+        }
+        fn sync(server: &mut Vec<Op>, channel: &mut Channel, remind: &mut Remind, device: &str) {
+            let ops = channel.diff(remind, device);
+            let pushed = push(server, ops);
+            channel.pushed(&pushed);
+            let pulled = server[channel.since() as usize..].to_vec();
+            channel.merge(remind, pulled, device, 100);
+        }
+
+        let mut server = Vec::new();
+        let mut mine = app(1).sticky_note[0].clone();
+        let mut theirs = Remind::new("Note 0".into());
+        let (mut a, mut b) = (Channel::default(), Channel::default());
+        sync(&mut server, &mut a, &mut mine, "laptop");
+        sync(&mut server, &mut b, &mut theirs, "phone");
+        assert_eq!(theirs.list.len(), 3);
+
+        theirs.list[1].set_status(Status::Done);
+        theirs.list.items.remove(0);
+        theirs.note = "bring snacks".into();
+        sync(&mut server, &mut b, &mut theirs, "phone");
+        sync(&mut server, &mut a, &mut mine, "laptop");
+        assert_eq!(mine.list.len(), 2);
+        assert_eq!(mine.list[0].status, Status::Done);
+        assert_eq!(mine.note, "bring snacks");
+
+        // nothing changed, nothing is sent back
+        assert!(a.diff(&mine, "laptop").is_empty());
+    }
+}
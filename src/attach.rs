@@ -0,0 +1,36 @@
+//! Files attached to sticky notes, only their paths are kept.
+
+use std::io;
+use std::process::{Command, Stdio};
+
+use crate::paths::expand;
+
+/// A web page attached, not a file.
+pub fn is_link(path: &str) -> bool {
+    path.starts_with("https://") || path.starts_with("http://")
+}
+
+pub fn exists(path: &str) -> bool {
+    is_link(path) || expand(path).exists()
+}
+
+/// Opens `path` with `opener`, split on whitespace, without waiting for it.
+pub fn open(opener: &str, path: &str) -> io::Result<()> {
+    let mut words = opener.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no attachment_opener"))?;
+    let path = if is_link(path) {
+        path.into()
+    } else {
+        expand(path)
+    };
+    Command::new(program)
+        .args(words)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+}
@@ -0,0 +1,190 @@
+//! `forget gen` writes a large made up data base and `forget bench` times
+//! drawing, saving and searching one, so a feature that slows them down shows
+//! up before it's released. Neither touches `~/.forget/note_db.json`.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use chrono::{Duration as Days, Local};
+use tui::backend::TestBackend;
+use tui::Terminal;
+
+use crate::app::{App, ListState, Remind, Status, Todo};
+use crate::config::{self, CFG};
+use crate::query::Query;
+use crate::ux;
+
+/// Where `forget gen` writes unless given `--out`.
+const GEN_FILE: &str = "forget_gen.json";
+/// How many times each thing is timed, the median is reported.
+const ROUNDS: usize = 20;
+/// A frame at 60 frames a second.
+const RENDER_BUDGET: Duration = Duration::from_millis(16);
+/// A tick at the default tick rate.
+const SAVE_BUDGET: Duration = Duration::from_millis(250);
+const SEARCH_BUDGET: Duration = Duration::from_millis(50);
+/// What the search is timed with, every kind of term the smart notes use.
+pub const SEARCH: &str = "#work is:open note:3 -has:cmd report";
+
+const WORDS: &[&str] = &[
+    "call", "email", "review", "write", "fix", "plan", "report", "buy", "read", "clean", "book",
+    "pay", "ship", "test", "draft", "update",
+];
+const TAGS: &[&str] = &["#work", "#home", "#errand", "#later"];
+
+/// The value after `--name` in `args`.
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn count(args: &[String], name: &str, default: usize) -> io::Result<usize> {
+    match flag(args, name) {
+        Some(n) => n.parse().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} takes a number, not {:?}", name, n),
+            )
+        }),
+        None => Ok(default),
+    }
+}
+
+/// `forget gen --notes 100 --todos 10000 --out forget_gen.json`, the todos
+/// are spread evenly over the sticky notes.
+pub fn gen(args: &[String]) -> io::Result<()> {
+    let notes = count(args, "--notes", 100)?.max(1);
+    let todos = count(args, "--todos", 10_000)?;
+    let out = flag(args, "--out").unwrap_or(GEN_FILE);
+    if Path::new(out).exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} is already there, pass another --out", out),
+        ));
+    }
+    fs::write(out, serde_json::to_string(&generate(notes, todos))?)?;
+    println!(
+        "wrote {} sticky notes with {} todos to {}",
+        notes, todos, out
+    );
+    Ok(())
+}
+
+/// `forget bench [file]`, times a data base made by `forget gen` or one made
+/// on the spot. Fails when anything takes longer than its budget.
+pub fn run(args: &[String]) -> io::Result<()> {
+    let notes = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+        None => generate(100, 10_000),
+    };
+    let todos = notes.iter().map(|n| n.list.len()).sum::<usize>();
+    println!("{} sticky notes, {} todos", notes.len(), todos);
+
+    // the default config so timings don't depend on whose machine it is
+    let config = CFG.with(|cfg| cfg.clone());
+    let mut app = App::with_notes(notes.clone(), config)?;
+    let mut terminal = Terminal::new(TestBackend::new(200, 60))?;
+    let render = time(|| {
+        ux::draw(&mut terminal, &mut app)?;
+        app.on_right();
+        Ok(())
+    })?;
+
+    let path = std::env::temp_dir().join(format!("forget_bench_{}.json", std::process::id()));
+    let save = time(|| fs::write(&path, serde_json::to_string(&config::sealed(&notes)?)?));
+    let _ = fs::remove_file(&path);
+    let save = save?;
+
+    let query = &Query::parse(SEARCH);
+    let search = time(|| {
+        let found = notes
+            .iter()
+            .flat_map(|n| n.list.iter().filter(move |t| query.matches(n, t)))
+            .count();
+        std::hint::black_box(found);
+        Ok(())
+    })?;
+
+    let over = [
+        ("render", render, RENDER_BUDGET),
+        ("save", save, SAVE_BUDGET),
+        ("search", search, SEARCH_BUDGET),
+    ]
+    .iter()
+    .filter(|(name, took, budget)| {
+        let over = took > budget;
+        println!(
+            "{:<8}{:>10.2?} median of {} (budget {:?}){}",
+            name,
+            took,
+            ROUNDS,
+            budget,
+            if over { "  OVER" } else { "" }
+        );
+        over
+    })
+    .count();
+    if over > 0 {
+        return Err(io::Error::other(format!("{} over budget", over)));
+    }
+    Ok(())
+}
+
+/// The median time `work` takes over `ROUNDS` runs.
+fn time<F: FnMut() -> io::Result<()>>(mut work: F) -> io::Result<Duration> {
+    let mut took = Vec::with_capacity(ROUNDS);
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        work()?;
+        took.push(start.elapsed());
+    }
+    took.sort();
+    Ok(took[ROUNDS / 2])
+}
+
+/// The same notes every time for the same counts, so runs can be compared.
+pub fn generate(notes: usize, todos: usize) -> ListState<Remind> {
+    let now = Local::now();
+    // a small linear congruential generator is random enough for made up todos
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move |n: usize| {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 33) as usize % n
+    };
+
+    let mut items = (0..notes)
+        .map(|n| {
+            let mut remind = Remind::new(format!("Note {}", n + 1));
+            remind.note = format!("Made up by `forget gen`, sticky note {}.", n + 1);
+            remind
+        })
+        .collect::<Vec<_>>();
+    for id in 0..todos {
+        let words = (0..3 + next(5))
+            .map(|_| WORDS[next(WORDS.len())])
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut todo = Todo {
+            date: now - Days::minutes(next(60 * 24 * 90) as i64),
+            ..Todo::new(
+                id as u64 + 1,
+                format!("{} {}", words, TAGS[next(TAGS.len())]),
+            )
+        };
+        if next(5) == 0 {
+            todo.cmd = format!("echo {}", todo.id);
+        }
+        if next(4) == 0 {
+            todo.due = Some((now + Days::days(next(30) as i64 - 10)).timestamp());
+        }
+        todo.set_status(Status::ALL[next(Status::ALL.len())]);
+        items[id % notes].list.items.push(todo);
+    }
+    ListState::new(items)
+}
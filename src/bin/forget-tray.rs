@@ -0,0 +1,137 @@
+//! `forget-tray`, a system tray icon with the count of todos due today that
+//! opens `forget` in a terminal when clicked, only built with `--features tray`.
+//!
+//! It reads the data base through `forget status --watch` so the tray never
+//! has its own copy of how todos are stored or when they are due.
+
+use std::env;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{self, Command, Stdio};
+
+use ksni::blocking::TrayMethods;
+use ksni::menu::StandardItem;
+use ksni::{MenuItem, Status, ToolTip, Tray};
+use serde::Deserialize;
+
+/// One line of `forget status`.
+#[derive(Debug, Default, Deserialize)]
+struct DueToday {
+    text: String,
+    tooltip: String,
+    class: String,
+}
+
+#[derive(Debug, Default)]
+struct ForgetTray {
+    due: DueToday,
+}
+
+impl Tray for ForgetTray {
+    fn id(&self) -> String {
+        "forget".into()
+    }
+
+    fn title(&self) -> String {
+        if self.due.text.is_empty() {
+            "forget".into()
+        } else {
+            self.due.text.clone()
+        }
+    }
+
+    fn icon_name(&self) -> String {
+        "appointment-soon".into()
+    }
+
+    fn attention_icon_name(&self) -> String {
+        "appointment-missed".into()
+    }
+
+    fn status(&self) -> Status {
+        match self.due.class.as_str() {
+            "overdue" => Status::NeedsAttention,
+            "due" => Status::Active,
+            _ => Status::Passive,
+        }
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        let title = if self.due.text.is_empty() {
+            "Nothing due today".to_string()
+        } else {
+            format!("{} due today", self.due.text)
+        };
+        ToolTip {
+            title,
+            description: self.due.tooltip.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        open_forget();
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        vec![
+            StandardItem {
+                label: "Open forget".into(),
+                activate: Box::new(|_: &mut Self| open_forget()),
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: "Quit".into(),
+                icon_name: "application-exit".into(),
+                activate: Box::new(|_: &mut Self| process::exit(0)),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+}
+
+/// The `forget` next to this binary, the one on the `PATH` otherwise.
+fn forget() -> PathBuf {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join("forget")))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from("forget"))
+}
+
+/// Opens the TUI in `$TERMINAL`, or the distribution's default terminal.
+fn open_forget() {
+    let terminal = env::var("TERMINAL").unwrap_or_else(|_| "x-terminal-emulator".into());
+    if let Err(e) = Command::new(&terminal).arg("-e").arg(forget()).spawn() {
+        eprintln!("forget-tray: can't run {}: {}", terminal, e);
+    }
+}
+
+fn main() -> io::Result<()> {
+    let tray = ForgetTray::default()
+        .spawn()
+        .map_err(|e| io::Error::other(format!("no system tray: {}", e)))?;
+
+    let mut status = Command::new(forget())
+        .args(["status", "--watch"])
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let out = status.stdout.take().expect("stdout is piped");
+    for line in BufReader::new(out).lines() {
+        match serde_json::from_str::<DueToday>(&line?) {
+            Ok(due) => {
+                tray.update(|tray| tray.due = due);
+            }
+            Err(e) => eprintln!("forget-tray: bad status line: {}", e),
+        }
+    }
+    // `forget status --watch` only stops when it can't read the data base
+    let code = status.wait()?;
+    Err(io::Error::other(format!(
+        "`forget status` stopped, {}",
+        code
+    )))
+}
@@ -0,0 +1,182 @@
+//! A small calculator for lines like `= 23*4.5` in the notes.
+
+/// Evaluates `+ - * / % ^`, parentheses and decimal numbers, `x` works as
+/// `*` too so `3 x 2.50` reads like a shopping list.
+pub fn eval(expr: &str) -> Result<f64, String> {
+    let mut parser = Parser {
+        chars: expr.chars().filter(|c| !c.is_whitespace()).collect(),
+        pos: 0,
+    };
+    let value = parser.sum()?;
+    match parser.peek() {
+        None if value.is_finite() => Ok(value),
+        None => Err("the result isn't a number".into()),
+        Some(c) => Err(format!("unexpected `{}`", c)),
+    }
+}
+
+/// Like `103.5` or `4`, rounded to 10 decimals so floats don't show `0.30000000000000004`.
+pub fn format(value: f64) -> String {
+    let text = format!("{:.10}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".into()
+    } else {
+        text.into()
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut value = self.product()?;
+        loop {
+            if self.eat('+') {
+                value += self.product()?;
+            } else if self.eat('-') {
+                value -= self.product()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') || self.eat('x') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                value /= self.unary()?;
+            } else if self.eat('%') {
+                value %= self.unary()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// `-2^2` is `-(2^2)` like it's written on paper.
+    fn unary(&mut self) -> Result<f64, String> {
+        if self.eat('-') {
+            Ok(-self.unary()?)
+        } else if self.eat('+') {
+            self.unary()
+        } else {
+            self.power()
+        }
+    }
+
+    /// `^` groups to the right, `2^3^2` is `2^9`.
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            Ok(base.powf(self.unary()?))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<f64, String> {
+        if self.eat('(') {
+            let value = self.sum()?;
+            if !self.eat(')') {
+                return Err("missing `)`".into());
+            }
+            return Ok(value);
+        }
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || c == '.' || c == ',')
+        {
+            self.pos += 1;
+        }
+        // `1,250.5` is a thousands separator, not a decimal comma
+        let number = self.chars[start..self.pos]
+            .iter()
+            .filter(|c| **c != ',')
+            .collect::<String>();
+        match self.chars.get(start) {
+            _ if !number.is_empty() => number
+                .parse()
+                .map_err(|_| format!("`{}` isn't a number", number)),
+            Some(c) => Err(format!("unexpected `{}`", c)),
+            None => Err("missing a number".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("2 + 3 * 4"), Ok(14.0));
+        assert_eq!(eval("(2 + 3) * 4"), Ok(20.0));
+        assert_eq!(eval("10 - 4 - 3"), Ok(3.0));
+        assert_eq!(eval("2 * 3 ^ 2"), Ok(18.0));
+        assert_eq!(eval("7 % 4 + 1"), Ok(4.0));
+    }
+
+    #[test]
+    fn x_is_times() {
+        assert_eq!(eval("3 x 2.50"), Ok(7.5));
+        assert_eq!(eval("2x3+1"), Ok(7.0));
+    }
+
+    #[test]
+    fn commas_separate_thousands() {
+        assert_eq!(eval("1,250.5 + 1"), Ok(1251.5));
+        assert_eq!(eval("2 x 1,000"), Ok(2000.0));
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval("-3 + 5"), Ok(2.0));
+        assert_eq!(eval("4 * -2"), Ok(-8.0));
+        assert_eq!(eval("--2"), Ok(2.0));
+        assert_eq!(eval("-2^2"), Ok(-4.0));
+        assert_eq!(eval("2^-1"), Ok(0.5));
+        assert_eq!(eval("-(1 + 2)"), Ok(-3.0));
+    }
+
+    #[test]
+    fn power_groups_to_the_right() {
+        assert_eq!(eval("2^3^2"), Ok(512.0));
+        assert_eq!(eval("(2^3)^2"), Ok(64.0));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(eval("1 / 0"), Err("the result isn't a number".into()));
+        assert_eq!(eval("(1 + 2"), Err("missing `)`".into()));
+        assert_eq!(eval("2 +"), Err("missing a number".into()));
+        assert_eq!(eval("2 $ 3"), Err("unexpected `$`".into()));
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(format(0.1 + 0.2), "0.3");
+        assert_eq!(format(103.5), "103.5");
+        assert_eq!(format(-0.0), "0");
+    }
+}
@@ -0,0 +1,119 @@
+//! Tells a Slack channel or Matrix room when a tagged todo is done, only
+//! built with `--features chat`.
+
+use std::env;
+use std::io;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::curl;
+use crate::query;
+use crate::worker::Progress;
+
+/// Posts `message` to `to` when a todo tagged `tag` is done.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ChatHook {
+    /// With or without the `#`.
+    pub tag: String,
+    pub to: Chat,
+    /// `{task}` and `{note}` are replaced with the todo and its sticky note's title.
+    #[serde(default = "default_message")]
+    pub message: String,
+}
+
+fn default_message() -> String {
+    "✅ {task} ({note})".into()
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Chat {
+    /// An incoming webhook URL.
+    Slack { webhook: String },
+    /// The access token is read from the environment variable `token_env`.
+    Matrix {
+        homeserver: String,
+        room: String,
+        token_env: String,
+    },
+}
+
+impl ChatHook {
+    fn matches(&self, task: &str) -> bool {
+        let tag = self.tag.trim_start_matches('#').to_lowercase();
+        query::tags(task).any(|t| t.trim_start_matches('#') == tag)
+    }
+}
+
+/// The message for every hook whose tag `task` has.
+pub fn messages(hooks: &[ChatHook], note: &str, task: &str) -> Vec<(Chat, String)> {
+    hooks
+        .iter()
+        .filter(|h| h.matches(task))
+        .map(|hook| {
+            let text = hook.message.replace("{task}", task).replace("{note}", note);
+            (hook.to.clone(), text)
+        })
+        .collect()
+}
+
+/// Sends the messages one after the other, returns why the ones that failed did.
+pub fn post_all(messages: &[(Chat, String)], progress: &Progress) -> Vec<io::Error> {
+    let mut failed = Vec::new();
+    for (i, (to, text)) in messages.iter().enumerate() {
+        if let Err(e) = post(to, text) {
+            failed.push(e);
+        }
+        progress.report(((i + 1) * 100 / messages.len()) as u8);
+    }
+    failed
+}
+
+fn post(to: &Chat, text: &str) -> io::Result<()> {
+    let config = match to {
+        Chat::Slack { webhook } => format!(
+            "url = \"{}\"\nheader = \"Content-Type: application/json\"\ndata-binary = \"{}\"\n",
+            curl::quote(webhook),
+            curl::quote(&json!({ "text": text }).to_string())
+        ),
+        Chat::Matrix {
+            homeserver,
+            room,
+            token_env,
+        } => {
+            let token = env::var(token_env).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("${} is not set", token_env),
+                )
+            })?;
+            let url = format!(
+                "{}/_matrix/client/v3/rooms/{}/send/m.room.message/forget{}",
+                homeserver.trim_end_matches('/'),
+                encode(room),
+                Local::now().timestamp_nanos()
+            );
+            format!(
+                "url = \"{}\"\nrequest = \"PUT\"\nheader = \"Authorization: Bearer {}\"\nheader = \"Content-Type: application/json\"\ndata-binary = \"{}\"\n",
+                curl::quote(&url),
+                curl::quote(&token),
+                curl::quote(&json!({ "msgtype": "m.text", "body": text }).to_string())
+            )
+        }
+    };
+    curl::run(&config).map(|_| ())
+}
+
+/// Percent encodes a path segment, room ids are like `!abc:example.org`.
+fn encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
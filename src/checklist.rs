@@ -0,0 +1,88 @@
+//! A sticky note as a plain text checklist, for editing it in `$EDITOR`.
+//!
+//! Every todo is a line like `- [x] buy milk <!-- #12 -->`, the comment keeps
+//! the todo's id so edits update the same todo.
+
+use crate::app::{Remind, Status};
+
+const HEADER: &str = "# Lines are todos, deleting a line deletes the todo.
+# [ ] open  [~] in progress  [?] waiting  [x] done  [-] cancelled
+# Keep the <!-- #id --> comments, lines without one become new todos.";
+
+/// One todo line read back from the editor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    pub id: Option<u64>,
+    pub status: Status,
+    pub task: String,
+}
+
+pub fn marker(status: Status) -> char {
+    match status {
+        Status::Open => ' ',
+        Status::InProgress => '~',
+        Status::Waiting => '?',
+        Status::Done => 'x',
+        Status::Cancelled => '-',
+    }
+}
+
+fn from_marker(c: char) -> Option<Status> {
+    match c {
+        ' ' => Some(Status::Open),
+        '~' => Some(Status::InProgress),
+        '?' => Some(Status::Waiting),
+        'x' | 'X' => Some(Status::Done),
+        '-' => Some(Status::Cancelled),
+        _ => None,
+    }
+}
+
+pub fn dump(remind: &Remind) -> String {
+    let mut text = format!("{}\n# {}\n\n", HEADER, remind.title);
+    for todo in remind.list.iter() {
+        text.push_str(&format!(
+            "- [{}] {} <!-- #{} -->\n",
+            marker(todo.status),
+            todo.task,
+            todo.id
+        ));
+    }
+    text
+}
+
+pub fn parse(text: &str) -> Vec<Line> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(parse_line)
+        .filter(|l| !l.task.is_empty())
+        .collect()
+}
+
+fn parse_line(line: &str) -> Line {
+    let (line, id) = match (line.rfind("<!--"), line.ends_with("-->")) {
+        (Some(start), true) => {
+            let comment = line[start + 4..line.len() - 3].trim();
+            let id = comment.trim_start_matches('#').trim().parse().ok();
+            (line[..start].trim_end(), id)
+        }
+        _ => (line, None),
+    };
+
+    let line = line.strip_prefix("- ").unwrap_or(line);
+    let chars = line.chars().collect::<Vec<_>>();
+    let (status, task) = match chars.as_slice() {
+        ['[', c, ']', ..] if from_marker(*c).is_some() => (
+            from_marker(*c).unwrap_or_default(),
+            chars[3..].iter().collect::<String>(),
+        ),
+        _ => (Status::Open, line.to_string()),
+    };
+
+    Line {
+        id,
+        status,
+        task: task.trim().to_string(),
+    }
+}
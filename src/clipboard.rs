@@ -0,0 +1,41 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// The first of these found on the `PATH` is used.
+const COPY_CMDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+    &["pbcopy"],
+];
+
+/// Puts `text` on the system clipboard.
+pub fn copy(text: &str) -> io::Result<()> {
+    for cmd in COPY_CMDS {
+        let child = Command::new(cmd[0])
+            .args(&cmd[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(text.as_bytes())?;
+        return if child.wait()?.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!("{} failed", cmd[0])))
+        };
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no clipboard command found",
+    ))
+}
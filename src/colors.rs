@@ -0,0 +1,261 @@
+//! The color picker, it changes `app_colors` while it's open so the whole
+//! app shows what the colors look like before they are saved.
+
+use termion::event::Key;
+
+use crate::config::{AppColor, AppStyle, ColorCfg};
+
+/// The styles of `ColorCfg` in the order the picker goes through them.
+pub const PARTS: &[&str] = &[
+    "normal",
+    "highlight",
+    "tabs",
+    "titles",
+    "text",
+    "warning",
+    "completed",
+    "pending",
+    "command",
+    "countdown",
+];
+
+/// The named colors, after `Reset` they are in the order of the first 16
+/// indexed colors.
+const NAMED: &[AppColor] = &[
+    AppColor::Reset,
+    AppColor::Black,
+    AppColor::Red,
+    AppColor::Green,
+    AppColor::Yellow,
+    AppColor::Blue,
+    AppColor::Magenta,
+    AppColor::Cyan,
+    AppColor::Gray,
+    AppColor::DarkGray,
+    AppColor::LightRed,
+    AppColor::LightGreen,
+    AppColor::LightYellow,
+    AppColor::LightBlue,
+    AppColor::LightMagenta,
+    AppColor::LightCyan,
+    AppColor::White,
+];
+
+/// How much left and right move an RGB channel.
+const RGB_STEP: u8 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Named,
+    Indexed,
+    Rgb,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickerResult {
+    Saved,
+    Cancelled,
+}
+
+#[derive(Clone, Debug)]
+pub struct ColorPicker {
+    /// Index into `PARTS`.
+    pub part: usize,
+    /// Picking the background instead of the foreground.
+    pub bg: bool,
+    /// The RGB channel left and right change.
+    pub channel: usize,
+    /// Put back when the picker is cancelled.
+    saved: ColorCfg,
+}
+
+pub fn mode(color: AppColor) -> Mode {
+    match color {
+        AppColor::Rgb(..) => Mode::Rgb,
+        AppColor::Indexed(_) => Mode::Indexed,
+        _ => Mode::Named,
+    }
+}
+
+/// The name a config file uses for `color`.
+pub fn name(color: AppColor) -> String {
+    match color {
+        AppColor::Rgb(r, g, b) => format!("Rgb({}, {}, {})", r, g, b),
+        AppColor::Indexed(i) => format!("Indexed({})", i),
+        named => format!("{:?}", named),
+    }
+}
+
+/// The RGB value of an indexed color in the usual xterm palette.
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match i {
+        0..=15 => BASIC[i as usize],
+        16..=231 => {
+            let i = i - 16;
+            (
+                LEVELS[(i / 36) as usize],
+                LEVELS[(i / 6 % 6) as usize],
+                LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (i - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// The same color in the next mode, named goes to indexed goes to RGB.
+fn next_mode(color: AppColor) -> AppColor {
+    match color {
+        AppColor::Rgb(..) => AppColor::Reset,
+        AppColor::Indexed(i) => {
+            let (r, g, b) = indexed_rgb(i);
+            AppColor::Rgb(r, g, b)
+        }
+        named => {
+            let idx = NAMED.iter().position(|c| *c == named).unwrap_or(0);
+            AppColor::Indexed(idx.saturating_sub(1) as u8)
+        }
+    }
+}
+
+pub fn style(colors: &ColorCfg, part: usize) -> &AppStyle {
+    match part {
+        0 => &colors.normal,
+        1 => &colors.highlight,
+        2 => &colors.tabs,
+        3 => &colors.titles,
+        4 => &colors.text,
+        5 => &colors.warning,
+        6 => &colors.completed,
+        7 => &colors.pending,
+        8 => &colors.command,
+        _ => &colors.countdown,
+    }
+}
+
+fn style_mut(colors: &mut ColorCfg, part: usize) -> &mut AppStyle {
+    match part {
+        0 => &mut colors.normal,
+        1 => &mut colors.highlight,
+        2 => &mut colors.tabs,
+        3 => &mut colors.titles,
+        4 => &mut colors.text,
+        5 => &mut colors.warning,
+        6 => &mut colors.completed,
+        7 => &mut colors.pending,
+        8 => &mut colors.command,
+        _ => &mut colors.countdown,
+    }
+}
+
+impl ColorPicker {
+    pub fn new(colors: &ColorCfg) -> ColorPicker {
+        ColorPicker {
+            part: 0,
+            bg: false,
+            channel: 0,
+            saved: colors.clone(),
+        }
+    }
+
+    /// The color being picked.
+    pub fn color(&self, colors: &ColorCfg) -> AppColor {
+        let style = style(colors, self.part);
+        if self.bg {
+            style.bg
+        } else {
+            style.fg
+        }
+    }
+
+    fn color_mut<'a>(&self, colors: &'a mut ColorCfg) -> &'a mut AppColor {
+        let style = style_mut(colors, self.part);
+        if self.bg {
+            &mut style.bg
+        } else {
+            &mut style.fg
+        }
+    }
+
+    /// Handles a key and changes `colors` right away, returns the result
+    /// once the picker is done. Cancelling puts the old colors back.
+    pub fn on_key(&mut self, key: Key, colors: &mut ColorCfg) -> Option<PickerResult> {
+        match key {
+            Key::Char('\n') => return Some(PickerResult::Saved),
+            Key::Esc => {
+                *colors = self.saved.clone();
+                return Some(PickerResult::Cancelled);
+            }
+            Key::Char('\t') => {
+                if self.bg {
+                    self.part = (self.part + 1) % PARTS.len();
+                }
+                self.bg = !self.bg;
+            }
+            Key::BackTab => {
+                if !self.bg {
+                    self.part = (self.part + PARTS.len() - 1) % PARTS.len();
+                }
+                self.bg = !self.bg;
+            }
+            Key::Char('m') => {
+                let color = self.color_mut(colors);
+                *color = next_mode(*color);
+            }
+            Key::Up => self.channel = self.channel.saturating_sub(1),
+            Key::Down => self.channel = (self.channel + 1).min(2),
+            Key::Left => self.step(colors, false),
+            Key::Right => self.step(colors, true),
+            _ => {}
+        }
+        None
+    }
+
+    fn step(&mut self, colors: &mut ColorCfg, up: bool) {
+        let channel = self.channel;
+        let color = self.color_mut(colors);
+        *color = match *color {
+            AppColor::Rgb(r, g, b) => {
+                let mut rgb = [r, g, b];
+                rgb[channel] = if up {
+                    rgb[channel].saturating_add(RGB_STEP)
+                } else {
+                    rgb[channel].saturating_sub(RGB_STEP)
+                };
+                AppColor::Rgb(rgb[0], rgb[1], rgb[2])
+            }
+            AppColor::Indexed(i) if up => AppColor::Indexed(i.wrapping_add(1)),
+            AppColor::Indexed(i) => AppColor::Indexed(i.wrapping_sub(1)),
+            named => {
+                let idx = NAMED.iter().position(|c| *c == named).unwrap_or(0);
+                let idx = if up {
+                    (idx + 1) % NAMED.len()
+                } else {
+                    (idx + NAMED.len() - 1) % NAMED.len()
+                };
+                NAMED[idx]
+            }
+        };
+    }
+}
@@ -1,15 +1,32 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::fmt;
 use std::fs;
 use std::io;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-use chrono::Local;
 use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 use termion::event::Key;
 use tui::style::{Color, Modifier, Style};
 
+use crate::action::{Action, Macro};
 use crate::app::{ListState, Remind, Todo};
+#[cfg(feature = "chat")]
+use crate::chat::ChatHook;
+use crate::dates;
+use crate::feed::Feed;
+#[cfg(feature = "github")]
+use crate::github::GitHub;
+use crate::paths;
+use crate::publish::Publish;
+use crate::quiet::QuietHours;
+use crate::recurring::Recurring;
+use crate::remote::RemoteNote;
+#[cfg(feature = "sync")]
+use crate::sync::SyncServer;
+use crate::web_colors;
 
 /// A key.
 #[derive(Debug, Copy, Clone, Deserialize, Serialize)]
@@ -59,27 +76,52 @@ pub enum AppKey {
     __IsNotComplete,
 }
 
-impl Into<Key> for AppKey {
-    fn into(self) -> Key {
-        match self {
-            Self::Backspace => Key::Backspace,
-            Self::Left => Key::Left,
-            Self::Right => Key::Right,
-            Self::Up => Key::Up,
-            Self::Down => Key::Down,
-            Self::Home => Key::Home,
-            Self::End => Key::End,
-            Self::PageUp => Key::PageUp,
-            Self::PageDown => Key::PageDown,
-            Self::BackTab => Key::BackTab,
-            Self::Delete => Key::Delete,
-            Self::Insert => Key::Insert,
-            Self::F(int) => Key::F(int),
-            Self::Char(c) => Key::Char(c),
-            Self::Alt(c) => Key::Alt(c),
-            Self::Ctrl(c) => Key::Ctrl(c),
-            Self::Null => Key::Null,
-            Self::Esc => Key::Esc,
+impl From<Key> for AppKey {
+    fn from(key: Key) -> AppKey {
+        match key {
+            Key::Backspace => AppKey::Backspace,
+            Key::Left => AppKey::Left,
+            Key::Right => AppKey::Right,
+            Key::Up => AppKey::Up,
+            Key::Down => AppKey::Down,
+            Key::Home => AppKey::Home,
+            Key::End => AppKey::End,
+            Key::PageUp => AppKey::PageUp,
+            Key::PageDown => AppKey::PageDown,
+            Key::BackTab => AppKey::BackTab,
+            Key::Delete => AppKey::Delete,
+            Key::Insert => AppKey::Insert,
+            Key::F(int) => AppKey::F(int),
+            Key::Char(c) => AppKey::Char(c),
+            Key::Alt(c) => AppKey::Alt(c),
+            Key::Ctrl(c) => AppKey::Ctrl(c),
+            Key::Esc => AppKey::Esc,
+            _ => AppKey::Null,
+        }
+    }
+}
+
+impl From<AppKey> for Key {
+    fn from(key: AppKey) -> Key {
+        match key {
+            AppKey::Backspace => Key::Backspace,
+            AppKey::Left => Key::Left,
+            AppKey::Right => Key::Right,
+            AppKey::Up => Key::Up,
+            AppKey::Down => Key::Down,
+            AppKey::Home => Key::Home,
+            AppKey::End => Key::End,
+            AppKey::PageUp => Key::PageUp,
+            AppKey::PageDown => Key::PageDown,
+            AppKey::BackTab => Key::BackTab,
+            AppKey::Delete => Key::Delete,
+            AppKey::Insert => Key::Insert,
+            AppKey::F(int) => Key::F(int),
+            AppKey::Char(c) => Key::Char(c),
+            AppKey::Alt(c) => Key::Alt(c),
+            AppKey::Ctrl(c) => Key::Ctrl(c),
+            AppKey::Null => Key::Null,
+            AppKey::Esc => Key::Esc,
             _ => unreachable!("semver broken termion crate"),
         }
     }
@@ -99,32 +141,62 @@ bitflags::bitflags! {
     }
 }
 
+/// The name of every modifier, in the order they are written.
+const MODIFIERS: &[(AppMod, Modifier, &str)] = &[
+    (AppMod::BOLD, Modifier::BOLD, "BOLD"),
+    (AppMod::DIM, Modifier::DIM, "DIM"),
+    (AppMod::ITALIC, Modifier::ITALIC, "ITALIC"),
+    (AppMod::UNDERLINED, Modifier::UNDERLINED, "UNDERLINED"),
+    (AppMod::SLOW_BLINK, Modifier::SLOW_BLINK, "SLOW_BLINK"),
+    (AppMod::RAPID_BLINK, Modifier::RAPID_BLINK, "RAPID_BLINK"),
+    (AppMod::REVERSED, Modifier::REVERSED, "REVERSED"),
+    (AppMod::HIDDEN, Modifier::HIDDEN, "HIDDEN"),
+    (AppMod::CROSSED_OUT, Modifier::CROSSED_OUT, "CROSSED_OUT"),
+];
+
+/// What a config can say, for the error about an unknown one.
+const NAMES: &[&str] = &[
+    "BOLD",
+    "DIM",
+    "ITALIC",
+    "UNDERLINED",
+    "SLOW_BLINK",
+    "RAPID_BLINK",
+    "REVERSED",
+    "HIDDEN",
+    "CROSSED_OUT",
+    "RESET",
+];
+
 impl AppMod {
-    fn modifier(&self) -> &str {
-        match self.bits() {
-            1 => "BOLD",
-            2 => "DIM",
-            3 => "ITALIC",
-            4 => "UNDERLINED",
-            5 => "SLOW_BLINK",
-            6 => "RAPID_BLINK",
-            7 => "REVERSED",
-            8 => "HIDDEN",
-            9 => "CROSSED_OUT",
-            _ => "RESET",
+    fn from_name(name: &str) -> Option<AppMod> {
+        if name == "RESET" {
+            return Some(AppMod::empty());
         }
+        MODIFIERS
+            .iter()
+            .find(|(_, _, n)| *n == name)
+            .map(|(m, _, _)| *m)
     }
 }
 
+/// Written as a list of names like `["BOLD", "UNDERLINED"]`, an empty list
+/// for none.
 impl Serialize for AppMod {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(self.modifier())
+        serializer.collect_seq(
+            MODIFIERS
+                .iter()
+                .filter(|(m, _, _)| self.contains(*m))
+                .map(|(_, _, name)| name),
+        )
     }
 }
 
+/// Reads a list of names or, like older configs, a single name.
 impl<'de> Deserialize<'de> for AppMod {
     fn deserialize<D>(deserializer: D) -> Result<AppMod, D::Error>
     where
@@ -135,50 +207,42 @@ impl<'de> Deserialize<'de> for AppMod {
             type Value = AppMod;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("One of 9 ascii text modifiers `BOLD, ITALIC, DIM, ect")
+                formatter.write_str("a list of text modifiers like [\"BOLD\", \"ITALIC\"]")
             }
 
             fn visit_str<E>(self, value: &str) -> Result<AppMod, E>
             where
                 E: serde::de::Error,
             {
-                match value {
-                    "BOLD" => Ok(AppMod::BOLD),
-                    "DIM" => Ok(AppMod::DIM),
-                    "ITALIC" => Ok(AppMod::ITALIC),
-                    "UNDERLINED" => Ok(AppMod::UNDERLINED),
-                    "SLOW_BLINK" => Ok(AppMod::SLOW_BLINK),
-                    "RAPID_BLINK" => Ok(AppMod::RAPID_BLINK),
-                    "REVERSED" => Ok(AppMod::REVERSED),
-                    "HIDDEN" => Ok(AppMod::HIDDEN),
-                    "CROSSED_OUT" => Ok(AppMod::CROSSED_OUT),
-                    "RESET" => Ok(AppMod::empty()),
-                    _ => Err(serde::de::Error::unknown_field(value, &[""])),
+                AppMod::from_name(value).ok_or_else(|| E::unknown_variant(value, NAMES))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<AppMod, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut modifier = AppMod::empty();
+                while let Some(name) = seq.next_element::<String>()? {
+                    modifier |= AppMod::from_name(&name)
+                        .ok_or_else(|| serde::de::Error::unknown_variant(&name, NAMES))?;
                 }
+                Ok(modifier)
             }
         }
-        deserializer.deserialize_str(AppModVisit)
+        deserializer.deserialize_any(AppModVisit)
     }
 }
 
-impl Into<Modifier> for AppMod {
-    fn into(self) -> Modifier {
-        match self.bits() {
-            1 => Modifier::BOLD,
-            2 => Modifier::DIM,
-            3 => Modifier::ITALIC,
-            4 => Modifier::UNDERLINED,
-            5 => Modifier::SLOW_BLINK,
-            6 => Modifier::RAPID_BLINK,
-            7 => Modifier::REVERSED,
-            8 => Modifier::HIDDEN,
-            9 => Modifier::CROSSED_OUT,
-            _ => Modifier::empty(),
-        }
+impl From<AppMod> for Modifier {
+    fn from(modifier: AppMod) -> Modifier {
+        MODIFIERS
+            .iter()
+            .filter(|(m, _, _)| modifier.contains(*m))
+            .fold(Modifier::empty(), |all, (_, m, _)| all | *m)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum AppColor {
     Reset,
     Black,
@@ -201,28 +265,114 @@ pub enum AppColor {
     Indexed(u8),
 }
 
-impl Into<Color> for AppColor {
-    fn into(self) -> Color {
-        match self {
-            Self::Reset => Color::Reset,
-            Self::Black => Color::Black,
-            Self::Red => Color::Red,
-            Self::Green => Color::Green,
-            Self::Yellow => Color::Yellow,
-            Self::Blue => Color::Blue,
-            Self::Magenta => Color::Magenta,
-            Self::Cyan => Color::Cyan,
-            Self::Gray => Color::Gray,
-            Self::DarkGray => Color::DarkGray,
-            Self::LightRed => Color::LightRed,
-            Self::LightGreen => Color::LightGreen,
-            Self::LightYellow => Color::LightYellow,
-            Self::LightBlue => Color::LightBlue,
-            Self::LightMagenta => Color::LightMagenta,
-            Self::LightCyan => Color::LightCyan,
-            Self::White => Color::White,
-            Self::Indexed(i) => Color::Indexed(i),
-            Self::Rgb(r, g, b) => Color::Rgb(r, g, b),
+/// The terminal's own colors by the names a config uses.
+const COLOR_NAMES: &[(&str, AppColor)] = &[
+    ("Reset", AppColor::Reset),
+    ("Black", AppColor::Black),
+    ("Red", AppColor::Red),
+    ("Green", AppColor::Green),
+    ("Yellow", AppColor::Yellow),
+    ("Blue", AppColor::Blue),
+    ("Magenta", AppColor::Magenta),
+    ("Cyan", AppColor::Cyan),
+    ("Gray", AppColor::Gray),
+    ("DarkGray", AppColor::DarkGray),
+    ("LightRed", AppColor::LightRed),
+    ("LightGreen", AppColor::LightGreen),
+    ("LightYellow", AppColor::LightYellow),
+    ("LightBlue", AppColor::LightBlue),
+    ("LightMagenta", AppColor::LightMagenta),
+    ("LightCyan", AppColor::LightCyan),
+    ("White", AppColor::White),
+];
+
+impl AppColor {
+    /// A terminal color like `Red` first, so `"red"` is the web color.
+    fn from_name(name: &str) -> Option<AppColor> {
+        COLOR_NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, c)| *c)
+            .or_else(|| web_colors::rgb(name).map(|(r, g, b)| AppColor::Rgb(r, g, b)))
+    }
+}
+
+/// Reads a name like `"Red"`, `"#ff8800"`, `"#f80"` or `"orange"`, a number
+/// for an indexed color, or `{"Rgb": [255, 136, 0]}` and `{"Indexed": 208}`
+/// like older configs.
+impl<'de> Deserialize<'de> for AppColor {
+    fn deserialize<D>(deserializer: D) -> Result<AppColor, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct AppColorVisit;
+        impl<'de> Visitor<'de> for AppColorVisit {
+            type Value = AppColor;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a color like \"Red\", \"#ff8800\", \"orange\" or 208")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<AppColor, E>
+            where
+                E: serde::de::Error,
+            {
+                AppColor::from_name(value)
+                    .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<AppColor, E>
+            where
+                E: serde::de::Error,
+            {
+                u8::try_from(value)
+                    .map(AppColor::Indexed)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<AppColor, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let key = map
+                    .next_key::<String>()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+                match key.as_str() {
+                    "Rgb" => {
+                        let (r, g, b) = map.next_value()?;
+                        Ok(AppColor::Rgb(r, g, b))
+                    }
+                    "Indexed" => Ok(AppColor::Indexed(map.next_value()?)),
+                    _ => Err(serde::de::Error::unknown_variant(&key, &["Rgb", "Indexed"])),
+                }
+            }
+        }
+        deserializer.deserialize_any(AppColorVisit)
+    }
+}
+
+impl From<AppColor> for Color {
+    fn from(color: AppColor) -> Color {
+        match color {
+            AppColor::Reset => Color::Reset,
+            AppColor::Black => Color::Black,
+            AppColor::Red => Color::Red,
+            AppColor::Green => Color::Green,
+            AppColor::Yellow => Color::Yellow,
+            AppColor::Blue => Color::Blue,
+            AppColor::Magenta => Color::Magenta,
+            AppColor::Cyan => Color::Cyan,
+            AppColor::Gray => Color::Gray,
+            AppColor::DarkGray => Color::DarkGray,
+            AppColor::LightRed => Color::LightRed,
+            AppColor::LightGreen => Color::LightGreen,
+            AppColor::LightYellow => Color::LightYellow,
+            AppColor::LightBlue => Color::LightBlue,
+            AppColor::LightMagenta => Color::LightMagenta,
+            AppColor::LightCyan => Color::LightCyan,
+            AppColor::White => Color::White,
+            AppColor::Indexed(i) => Color::Indexed(i),
+            AppColor::Rgb(r, g, b) => Color::Rgb(r, g, b),
         }
     }
 }
@@ -234,16 +384,27 @@ pub struct AppStyle {
     pub modifier: AppMod,
 }
 
-impl Into<Style> for AppStyle {
-    fn into(self) -> Style {
+impl From<AppStyle> for Style {
+    fn from(style: AppStyle) -> Style {
         Style {
-            fg: self.fg.into(),
-            bg: self.bg.into(),
-            modifier: self.modifier.into(),
+            fg: style.fg.into(),
+            bg: style.bg.into(),
+            modifier: style.modifier.into(),
         }
     }
 }
 
+/// Todos whose task matches `pattern` are drawn with `style`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ColorRule {
+    pub pattern: String,
+    pub style: AppStyle,
+    /// Only match todos with a command that has run at most this many times,
+    /// `0` picks out the ones never run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runs: Option<u64>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ColorCfg {
     pub normal: AppStyle,
@@ -251,12 +412,48 @@ pub struct ColorCfg {
     pub tabs: AppStyle,
     pub titles: AppStyle,
     pub text: AppStyle,
+    /// Used for warnings, like a sticky note with too many todos in progress.
+    #[serde(default = "default_warning")]
+    pub warning: AppStyle,
+    /// Todos that are done or cancelled, a `Reset` color keeps the color of the list.
+    #[serde(default = "default_completed")]
+    pub completed: AppStyle,
+    /// Todos that are open or waiting, a `Reset` color keeps the color of the list.
+    #[serde(default = "default_pending")]
+    pub pending: AppStyle,
+    /// Todos that run a command on enter, a `Reset` color keeps the color of the list.
+    #[serde(default = "default_command")]
+    pub command: AppStyle,
+    /// Countdown todos, a `Reset` color keeps the color of the list.
+    #[serde(default = "default_countdown")]
+    pub countdown: AppStyle,
+}
+
+/// How the parts of a command are highlighted.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CmdColors {
+    pub program: AppStyle,
+    pub flag: AppStyle,
+    pub string: AppStyle,
+    pub operator: AppStyle,
+    pub variable: AppStyle,
+}
+
+/// How much of the width the todos get, in percent, next to the notes.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct PaneSplit {
+    pub list: u16,
+    pub board: u16,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AppConfig {
     pub title: String,
     pub new_sticky_note_char_ctrl: char,
+    #[serde(default = "default_new_smart_note_char_ctrl")]
+    pub new_smart_note_char_ctrl: char,
+    #[serde(default = "default_edit_sticky_note_char_ctrl")]
+    pub edit_sticky_note_char_ctrl: char,
     pub new_note_char_ctrl: char,
     pub new_todo_char_ctrl: char,
     pub edit_todo_char_ctrl: char,
@@ -264,15 +461,211 @@ pub struct AppConfig {
     pub remove_todo: AppKey,
     pub remove_sticky_note_char_ctrl: char,
     pub save_state_to_db_char_ctrl: char,
+    #[serde(default = "default_run_all_char_ctrl")]
+    pub run_all_char_ctrl: char,
+    #[serde(default = "default_activity_char_ctrl")]
+    pub activity_char_ctrl: char,
+    #[serde(default = "default_secret_char_ctrl")]
+    pub secret_char_ctrl: char,
+    #[serde(default = "default_runbook_char_ctrl")]
+    pub runbook_char_ctrl: char,
+    #[serde(default = "default_editor_char_ctrl")]
+    pub editor_char_ctrl: char,
+    #[serde(default = "default_status_char_ctrl")]
+    pub status_char_ctrl: char,
+    #[serde(default = "default_board_char_ctrl")]
+    pub board_char_ctrl: char,
+    #[serde(default = "default_goto_blocker_char_ctrl")]
+    pub goto_blocker_char_ctrl: char,
+    #[serde(default = "default_goto_due_char_ctrl")]
+    pub goto_due_char_ctrl: char,
+    #[serde(default = "default_find_char_ctrl")]
+    pub find_char_ctrl: char,
+    #[serde(default = "default_debug_char_ctrl")]
+    pub debug_char_ctrl: char,
+    #[serde(default = "default_record_macro_char_ctrl")]
+    pub record_macro_char_ctrl: char,
+    #[serde(default = "default_play_macro_char_ctrl")]
+    pub play_macro_char_ctrl: char,
+    #[serde(default = "default_narrow_list_char")]
+    pub narrow_list_char: char,
+    #[serde(default = "default_widen_list_char")]
+    pub widen_list_char: char,
+    /// Changed with `narrow_list_char`, `widen_list_char` or the mouse and saved right away.
+    #[serde(default = "default_pane_split")]
+    pub pane_split: PaneSplit,
+    #[serde(default = "default_max_running_cmds")]
+    pub max_running_cmds: usize,
+    /// Seconds a command may run before it's killed, 0 for no limit.
+    #[serde(default)]
+    pub cmd_timeout_secs: u64,
+    /// How many of the last lines a command printed its report keeps, 0 for all.
+    #[serde(default = "default_note_output_lines")]
+    pub note_output_lines: usize,
+    /// No toasts for finished commands between these times, a summary
+    /// shows once they are over.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<QuietHours>,
+    /// Ticks a toast stays up, 0 turns them off.
+    #[serde(default = "default_toast_ticks")]
+    pub toast_ticks: u64,
     pub exit_key_char_ctrl: char,
+    /// Closes the open form, or quits from the list, `Null` leaves it unbound.
+    #[serde(default = "default_quit_key")]
+    pub quit_key: AppKey,
+    /// Ask before `quit_key` quits, the exit key never asks.
+    #[serde(default)]
+    pub confirm_quit: bool,
+    /// Underline misspelled words in the notes and the todo being written.
+    #[serde(default)]
+    pub spellcheck: bool,
+    /// The hunspell dictionary to check against, like `en_US` or `de_DE`.
+    #[serde(default = "default_spell_language")]
+    pub spell_language: String,
+    /// `error`, `warn`, `info`, `debug` or `trace`, `FORGET_LOG` overrides it.
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Minutes without input before the app locks, 0 disables the lock.
+    #[serde(default)]
+    pub idle_lock_minutes: u64,
+    /// SHA-512 crypt hash of the lock passphrase, made with `openssl passwd -6`.
+    #[serde(default)]
+    pub idle_lock_hash: String,
+    /// Put the sticky notes changed most recently first when starting.
+    #[serde(default)]
+    pub sort_tabs_by_activity: bool,
+    /// The title of a new sticky note left without one, `@today` and the like are expanded.
+    #[serde(default)]
+    pub default_title: String,
+    /// Saved on the todos added and changed here, to tell machines apart when syncing.
+    #[serde(default = "default_device_name")]
+    pub device_name: String,
+    /// Programs, or command prefixes ending in `*`, that run without asking, empty allows all.
+    #[serde(default)]
+    pub cmd_allowlist: Vec<String>,
+    /// Commands run in the terminal pane so they can be typed in, matched like `cmd_allowlist`.
+    #[serde(default)]
+    pub pty_cmds: Vec<String>,
+    /// Refuse commands not in `cmd_allowlist` instead of asking.
+    #[serde(default)]
+    pub refuse_unlisted_cmds: bool,
+    /// Opens attachments, the path is added after it.
+    #[serde(default = "default_attachment_opener")]
+    pub attachment_opener: String,
+    /// Where the sticky notes are kept, `~` and `$VARS` are filled in.
+    #[serde(default = "default_db_path")]
+    pub db_path: String,
+    /// Data bases by name, `--profile <name>` uses one instead of `db_path`.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, String>,
+    /// Edits checklists instead of `$VISUAL` or `$EDITOR` when set, `~` and
+    /// `$VARS` are filled in.
+    #[serde(default)]
+    pub editor: String,
+    /// The time starting each entry of a journal sticky note, a chrono format.
+    #[serde(default = "default_journal_format")]
+    pub journal_format: String,
+    /// How dates are shown and typed, a chrono format.
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    /// How times are shown and typed, a chrono format, `clock_12h` picks one when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<String>,
+    #[serde(default)]
+    pub clock_12h: bool,
+    /// The day weeks start on for `@week` in titles and the agenda.
+    #[serde(default = "default_week_start")]
+    pub week_start: String,
+    /// Names of the days from Monday on, English when empty.
+    #[serde(default)]
+    pub day_names: Vec<String>,
+    /// Names of the months from January on, English when empty.
+    #[serde(default)]
+    pub month_names: Vec<String>,
+    /// Never run commands, enter only says what would have run.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Cross out finished todos, off for terminals that can't draw it.
+    #[serde(default = "default_strikethrough")]
+    pub strikethrough: bool,
     pub highlight_string: String,
+    /// The highlight symbol of sticky notes by title, the rest use `highlight_string`.
+    #[serde(default)]
+    pub note_highlights: BTreeMap<String, String>,
+    /// Show which keys do what in the borders of the todos, forms and notes.
+    #[serde(default = "default_key_hints")]
+    pub key_hints: bool,
     pub command_string: String,
+    #[serde(default = "default_blocked_string")]
+    pub blocked_string: String,
     pub app_colors: ColorCfg,
+    #[serde(default = "default_cmd_colors")]
+    pub cmd_colors: CmdColors,
+    #[serde(default)]
+    pub color_rules: Vec<ColorRule>,
+    #[serde(default)]
+    pub macros: Vec<Macro>,
+    #[serde(default)]
+    pub recurring: Vec<Recurring>,
+    /// Days shown in the grid of a habit sticky note's todos.
+    #[serde(default = "default_habit_grid_days")]
+    pub habit_grid_days: usize,
+    /// Countdown todos due within this many days come first in the agenda.
+    #[serde(default = "default_countdown_days")]
+    pub countdown_days: i64,
+    /// Seconds between saving what's being typed to `draft.json`, 0 turns drafts off.
+    #[serde(default = "default_draft_secs")]
+    pub draft_secs: u64,
+    /// Enters this many milliseconds after the last one that saved a form or
+    /// ran a command are dropped, 0 keeps them all.
+    #[serde(default = "default_enter_debounce_ms")]
+    pub enter_debounce_ms: u64,
+    /// Plain borders and fewer repaints for slow connections, also turned on
+    /// by `--low-bandwidth`.
+    #[serde(default)]
+    pub low_bandwidth: bool,
+    /// Milliseconds between the frames drawn while something animates, 0
+    /// only draws on ticks.
+    #[serde(default = "default_frame_ms")]
+    pub frame_ms: u64,
+    /// A date on each line that recurring notes treat like a weekend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holidays_file: Option<String>,
+    #[serde(default)]
+    pub remote_notes: Vec<RemoteNote>,
+    /// Sticky notes whose todos are the entries of a feed.
+    #[serde(default)]
+    pub feeds: Vec<Feed>,
+    /// Where the `Publish` action uploads sticky notes to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish: Option<Publish>,
+    /// Messages posted when tagged todos are done.
+    #[cfg(feature = "chat")]
+    #[serde(default)]
+    pub chat_hooks: Vec<ChatHook>,
+    /// Title todos that are only a link with the page's title, the link is attached.
+    #[cfg(feature = "links")]
+    #[serde(default)]
+    pub link_titles: bool,
+    /// Where the shared sticky notes are synced.
+    #[cfg(feature = "sync")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync: Option<SyncServer>,
+    /// The token and whether done todos close their GitHub issues.
+    #[cfg(feature = "github")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github: Option<GitHub>,
+    /// Keys this build doesn't know, like `chat_hooks` without the `chat`
+    /// feature, written back as they were read.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 thread_local! { pub static CFG: AppConfig = AppConfig {
     title: "Forget It".into(),
     new_sticky_note_char_ctrl: 'h',
+    new_smart_note_char_ctrl: 'f',
+    edit_sticky_note_char_ctrl: 't',
     new_note_char_ctrl: 'k',
     new_todo_char_ctrl: 'n',
     edit_todo_char_ctrl: 'e',
@@ -280,9 +673,62 @@ thread_local! { pub static CFG: AppConfig = AppConfig {
     remove_todo: AppKey::Delete,
     remove_sticky_note_char_ctrl: 'u',
     save_state_to_db_char_ctrl: 's',
+    run_all_char_ctrl: 'r',
+    runbook_char_ctrl: 'b',
+    secret_char_ctrl: 'l',
+    activity_char_ctrl: 'a',
+    goto_blocker_char_ctrl: 'g',
+    goto_due_char_ctrl: 'y',
+    find_char_ctrl: 'z',
+    debug_char_ctrl: 'd',
+    record_macro_char_ctrl: 'x',
+    play_macro_char_ctrl: 'p',
+    board_char_ctrl: 'v',
+    status_char_ctrl: 'w',
+    editor_char_ctrl: 'o',
+    narrow_list_char: '<',
+    widen_list_char: '>',
+    pane_split: PaneSplit {
+        list: 65,
+        board: 65,
+    },
+    max_running_cmds: 1,
+    cmd_timeout_secs: 0,
+    note_output_lines: 20,
+    quiet_hours: None,
+    toast_ticks: 12,
     exit_key_char_ctrl: 'q',
+    quit_key: AppKey::Esc,
+    confirm_quit: false,
+    spellcheck: false,
+    spell_language: "en_US".into(),
+    log_level: "warn".into(),
+    idle_lock_minutes: 0,
+    idle_lock_hash: String::new(),
+    device_name: hostname(),
+    sort_tabs_by_activity: false,
+    default_title: String::new(),
+    cmd_allowlist: Vec::new(),
+    refuse_unlisted_cmds: false,
+    pty_cmds: Vec::new(),
+    attachment_opener: if cfg!(target_os = "macos") { "open" } else { "xdg-open" }.into(),
+    db_path: "~/.forget/note_db.json".into(),
+    profiles: BTreeMap::new(),
+    editor: String::new(),
+    journal_format: "## %Y-%m-%d %H:%M".into(),
+    date_format: dates::DATE.into(),
+    time_format: None,
+    clock_12h: false,
+    week_start: "Monday".into(),
+    day_names: Vec::new(),
+    month_names: Vec::new(),
+    dry_run: false,
+    strikethrough: true,
     highlight_string: "✔️".into(),
-    command_string: "💾".into(),
+    note_highlights: BTreeMap::new(),
+    key_hints: true,
+    command_string: "⚙".into(),
+    blocked_string: "🔒".into(),
     app_colors: ColorCfg {
         normal: AppStyle {
             fg: AppColor::White,
@@ -309,62 +755,285 @@ thread_local! { pub static CFG: AppConfig = AppConfig {
             bg: AppColor::Reset,
             modifier: AppMod::ITALIC,
         },
+        warning: AppStyle {
+            fg: AppColor::Red,
+            bg: AppColor::Reset,
+            modifier: AppMod::BOLD,
+        },
+        completed: AppStyle {
+            fg: AppColor::Reset,
+            bg: AppColor::Reset,
+            modifier: AppMod::CROSSED_OUT,
+        },
+        pending: AppStyle {
+            fg: AppColor::Reset,
+            bg: AppColor::Reset,
+            modifier: AppMod::ITALIC,
+        },
+        command: AppStyle {
+            fg: AppColor::Cyan,
+            bg: AppColor::Reset,
+            modifier: AppMod::empty(),
+        },
+        countdown: AppStyle {
+            fg: AppColor::LightMagenta,
+            bg: AppColor::Reset,
+            modifier: AppMod::BOLD,
+        },
     },
+    cmd_colors: CmdColors {
+        program: AppStyle {
+            fg: AppColor::Yellow,
+            bg: AppColor::Reset,
+            modifier: AppMod::BOLD,
+        },
+        flag: AppStyle {
+            fg: AppColor::Cyan,
+            bg: AppColor::Reset,
+            modifier: AppMod::empty(),
+        },
+        string: AppStyle {
+            fg: AppColor::Magenta,
+            bg: AppColor::Reset,
+            modifier: AppMod::empty(),
+        },
+        operator: AppStyle {
+            fg: AppColor::Red,
+            bg: AppColor::Reset,
+            modifier: AppMod::BOLD,
+        },
+        variable: AppStyle {
+            fg: AppColor::Blue,
+            bg: AppColor::Reset,
+            modifier: AppMod::empty(),
+        },
+    },
+    color_rules: vec![
+        ColorRule {
+            pattern: "URGENT".into(),
+            max_runs: None,
+            style: AppStyle {
+                fg: AppColor::Red,
+                bg: AppColor::Reset,
+                modifier: AppMod::BOLD,
+            },
+        },
+    ],
+    recurring: Vec::new(),
+    habit_grid_days: 14,
+    countdown_days: 30,
+    draft_secs: 5,
+    enter_debounce_ms: 250,
+    low_bandwidth: false,
+    frame_ms: 50,
+    holidays_file: None,
+    remote_notes: Vec::new(),
+    feeds: Vec::new(),
+    publish: None,
+    #[cfg(feature = "chat")]
+    chat_hooks: Vec::new(),
+    #[cfg(feature = "links")]
+    link_titles: false,
+    #[cfg(feature = "sync")]
+    sync: None,
+    #[cfg(feature = "github")]
+    github: None,
+    extra: serde_json::Map::new(),
+    macros: vec![
+        Macro {
+            name: "finish".into(),
+            key: Some(AppKey::F(2)),
+            actions: vec![Action::MarkDone, Action::Down, Action::Save],
+        },
+        Macro {
+            name: "publish".into(),
+            key: Some(AppKey::F(3)),
+            actions: vec![Action::Publish],
+        },
+        Macro {
+            name: "icon".into(),
+            key: Some(AppKey::F(4)),
+            actions: vec![Action::PickIcon],
+        },
+        Macro {
+            name: "highlight symbol".into(),
+            key: Some(AppKey::F(5)),
+            actions: vec![Action::PickHighlight],
+        },
+        Macro {
+            name: "colors".into(),
+            key: Some(AppKey::F(6)),
+            actions: vec![Action::PickColors],
+        },
+        Macro {
+            name: "theme".into(),
+            key: Some(AppKey::Alt('t')),
+            actions: vec![Action::PickTheme],
+        },
+        Macro {
+            name: "dry run".into(),
+            key: Some(AppKey::F(7)),
+            actions: vec![Action::ToggleDryRun],
+        },
+        Macro {
+            name: "goal".into(),
+            key: Some(AppKey::Alt('g')),
+            actions: vec![Action::ToggleGoal],
+        },
+        Macro {
+            name: "more weight".into(),
+            key: Some(AppKey::Alt('+')),
+            actions: vec![Action::WeightUp],
+        },
+        Macro {
+            name: "less weight".into(),
+            key: Some(AppKey::Alt('-')),
+            actions: vec![Action::WeightDown],
+        },
+        Macro {
+            name: "habit".into(),
+            key: Some(AppKey::Alt('h')),
+            actions: vec![Action::ToggleHabit],
+        },
+        Macro {
+            name: "countdown".into(),
+            key: Some(AppKey::Alt('c')),
+            actions: vec![Action::ToggleCountdown],
+        },
+        Macro {
+            name: "agenda".into(),
+            key: Some(AppKey::Alt('a')),
+            actions: vec![Action::Agenda],
+        },
+        Macro {
+            name: "present".into(),
+            key: Some(AppKey::Alt('p')),
+            actions: vec![Action::Present],
+        },
+        Macro {
+            name: "undelete".into(),
+            key: Some(AppKey::Alt('u')),
+            actions: vec![Action::Undelete],
+        },
+        Macro {
+            name: "last tab".into(),
+            key: Some(AppKey::BackTab),
+            actions: vec![Action::LastTab],
+        },
+        Macro {
+            name: "hide done".into(),
+            key: Some(AppKey::F(1)),
+            actions: vec![Action::ToggleHideDone],
+        },
+        Macro {
+            name: "journal".into(),
+            key: Some(AppKey::F(8)),
+            actions: vec![Action::ToggleJournal],
+        },
+        Macro {
+            name: "attach".into(),
+            key: Some(AppKey::F(9)),
+            actions: vec![Action::Attach],
+        },
+        Macro {
+            name: "open attachment".into(),
+            key: Some(AppKey::F(11)),
+            actions: vec![Action::OpenAttachment],
+        },
+        Macro {
+            name: "remove attachment".into(),
+            key: Some(AppKey::F(12)),
+            actions: vec![Action::RemoveAttachment],
+        },
+        Macro {
+            name: "help".into(),
+            key: Some(AppKey::Alt('?')),
+            actions: vec![Action::Help],
+        },
+    ],
 }}
 
 thread_local! { pub static APP: ListState<Remind> = ListState {
     items: vec![ Remind {
             title: "Note One".into(),
             note: "You can add to the Notes by hitting ctrl-k.".into(),
+            default_cmd: String::new(),
+            secret: None,
+            passphrase: None,
+            seal: Default::default(),
+            sealed_max_id: 0,
+            query: None,
+            board: false,
+            wip_limit: None,
+            instance: None,
+            url: None,
+            feed: None,
+            note_scroll: 0,
+            follow_tail: false,
+            note_edited: None,
+            created_at: None,
+            updated_at: None,
+            icon: None,
+            journal: false,
+            hide_done: false,
+            habit: false,
+            goal: false,
+            attachments: Vec::new(),
+            shared: None,
             list: ListState {
                 items: vec![
+                    Todo::new(1, "You can add a Sticky Note by hitting ctrl-h".into()),
+                    Todo::new(2, "You can add a Todo by hitting ctrl-n".into()),
+                    Todo::new(3, "You can check off a Todo by hitting Backspace".into()),
+                    Todo::new(4, "You can delete a Todo by hitting Delete".into()),
+                    Todo::new(
+                        5,
+                        "You can add a Smart Sticky Note collecting #tagged Todos with ctrl-f".into(),
+                    ),
+                    Todo::new(
+                        6,
+                        "You can edit a Sticky Note's title and default command by hitting ctrl-t".into(),
+                    ),
+                    Todo::new(
+                        7,
+                        "You can make a Sticky Note secret, unlock and lock it with ctrl-l".into(),
+                    ),
+                    Todo::new(8, "You can delete a Sticky by hitting ctrl-u".into()),
+                    Todo::new(
+                        9,
+                        "You can see and search everything that happened with ctrl-a".into(),
+                    ),
+                    Todo::new(10, "You can save to the data base by hitting ctrl-s".into()),
+                    Todo::new(11, "Oh you can exit by ctrl-q or Esc".into()),
+                    Todo::new(12, "Run the commands of every unfinished Todo with ctrl-r".into()),
+                    Todo::new(13, "Walk through the Todos one step at a time with ctrl-b".into()),
+                    Todo::new(
+                        14,
+                        "A Todo can wait on another, ctrl-g jumps to the one blocking it".into(),
+                    ),
+                    Todo::new(
+                        15,
+                        "Switch between a list and a board of statuses with ctrl-v".into(),
+                    ),
+                    Todo::new(
+                        16,
+                        "Move a Todo through open, in progress, waiting, done and cancelled with ctrl-w".into(),
+                    ),
+                    Todo::new(
+                        17,
+                        "Give a Todo an estimate like 30m, 2h or 3p and see what's left in the title".into(),
+                    ),
+                    Todo::new(
+                        18,
+                        "Edit all the Todos of a Sticky Note at once in $EDITOR with ctrl-o".into(),
+                    ),
+                    Todo::new(
+                        19,
+                        "Record keys with ctrl-x, play them back with ctrl-p, F2 finishes a Todo and saves".into(),
+                    ),
                     Todo {
-                        date: Local::now(),
-                        task: "You can add a Sticky Note by hitting ctrl-h".into(),
-                        cmd: String::new(),
-                        completed: false
-                    },
-                    Todo {
-                        date: Local::now(),
-                        task: "You can add a Todo by hitting ctrl-n".into(),
-                        cmd: String::new(),
-                        completed: false
-                    },
-                    Todo {
-                        date: Local::now(),
-                        task: "You can check off a Todo by hitting Backspace".into(),
-                        cmd: String::new(),
-                        completed: false
-                    },
-                    Todo {
-                        date: Local::now(),
-                        task: "You can delete a Todo by hitting Delete".into(),
-                        cmd: String::new(),
-                        completed: false
-                    },
-                    Todo {
-                        date: Local::now(),
-                        task: "You can delete a Sticky by hitting ctrl-u".into(),
-                        cmd: String::new(),
-                        completed: false
-                    },
-                    Todo {
-                        date: Local::now(),
-                        task: "You can save to the data base by hitting ctrl-s".into(),
-                        cmd: String::new(),
-                        completed: false
-                    },
-                    Todo {
-                        date: Local::now(),
-                        task: "Oh you can exit by ctrl-q or Esc".into(),
-                        cmd: String::new(),
-                        completed: false
-                    },
-                    Todo {
-                        date: Local::now(),
-                        task: "Todo's can run commands when selected with Enter.".into(),
                         cmd: "sensible-browser https://github.com/DevinR528/forget".into(),
-                        completed: false
+                        ..Todo::new(20, "Todo's can run commands when selected with Enter.".into())
                     }
                 ],
                 selected: 0
@@ -373,26 +1042,37 @@ thread_local! { pub static APP: ListState<Remind> = ListState {
         Remind {
             title: "Note Two".into(),
             note: "".into(),
+            default_cmd: "".into(),
+            secret: None,
+            passphrase: None,
+            seal: Default::default(),
+            sealed_max_id: 0,
+            query: None,
+            board: false,
+            wip_limit: None,
+            instance: None,
+            url: None,
+            feed: None,
+            note_scroll: 0,
+            follow_tail: false,
+            note_edited: None,
+            created_at: None,
+            updated_at: None,
+            icon: None,
+            journal: false,
+            hide_done: false,
+            habit: false,
+            goal: false,
+            attachments: Vec::new(),
+            shared: None,
             list: ListState {
                 items: vec![
+                    Todo::new(21, "First".into()),
                     Todo {
-                        date: Local::now(),
-                        task: "First".into(),
-                        cmd: "".into(),
-                        completed: false
+                        blocked_by: Some(21),
+                        ..Todo::new(22, "Second".into())
                     },
-                    Todo {
-                        date: Local::now(),
-                        task: "Second".into(),
-                        cmd: "".into(),
-                        completed: false
-                    },
-                    Todo {
-                        date: Local::now(),
-                        task: "Third".into(),
-                        cmd: "".into(),
-                        completed: false
-                    }
+                    Todo::new(23, "Third".into())
                 ],
                 selected: 0
             }
@@ -401,6 +1081,193 @@ thread_local! { pub static APP: ListState<Remind> = ListState {
     selected: 0
 }}
 
+fn default_new_smart_note_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.new_smart_note_char_ctrl)
+}
+
+fn default_edit_sticky_note_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.edit_sticky_note_char_ctrl)
+}
+
+fn default_run_all_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.run_all_char_ctrl)
+}
+
+fn default_activity_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.activity_char_ctrl)
+}
+
+fn default_secret_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.secret_char_ctrl)
+}
+
+fn default_runbook_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.runbook_char_ctrl)
+}
+
+fn default_editor_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.editor_char_ctrl)
+}
+
+fn default_status_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.status_char_ctrl)
+}
+
+fn default_board_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.board_char_ctrl)
+}
+
+fn default_goto_blocker_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.goto_blocker_char_ctrl)
+}
+
+fn default_device_name() -> String {
+    CFG.with(|cfg| cfg.device_name.clone())
+}
+
+/// The machine's host name, `unknown` if there isn't one.
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    let res = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+    match String::from_utf8_lossy(&buf[..len]).trim() {
+        name if res == 0 && !name.is_empty() => name.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn default_goto_due_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.goto_due_char_ctrl)
+}
+
+fn default_find_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.find_char_ctrl)
+}
+
+fn default_debug_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.debug_char_ctrl)
+}
+
+fn default_spell_language() -> String {
+    CFG.with(|cfg| cfg.spell_language.clone())
+}
+
+fn default_log_level() -> String {
+    CFG.with(|cfg| cfg.log_level.clone())
+}
+
+fn default_record_macro_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.record_macro_char_ctrl)
+}
+
+fn default_play_macro_char_ctrl() -> char {
+    CFG.with(|cfg| cfg.play_macro_char_ctrl)
+}
+
+fn default_key_hints() -> bool {
+    CFG.with(|cfg| cfg.key_hints)
+}
+
+fn default_blocked_string() -> String {
+    CFG.with(|cfg| cfg.blocked_string.clone())
+}
+
+fn default_cmd_colors() -> CmdColors {
+    CFG.with(|cfg| cfg.cmd_colors.clone())
+}
+
+fn default_warning() -> AppStyle {
+    CFG.with(|cfg| cfg.app_colors.warning.clone())
+}
+
+fn default_completed() -> AppStyle {
+    CFG.with(|cfg| cfg.app_colors.completed.clone())
+}
+
+fn default_pending() -> AppStyle {
+    CFG.with(|cfg| cfg.app_colors.pending.clone())
+}
+
+fn default_command() -> AppStyle {
+    CFG.with(|cfg| cfg.app_colors.command.clone())
+}
+
+fn default_countdown() -> AppStyle {
+    CFG.with(|cfg| cfg.app_colors.countdown.clone())
+}
+
+fn default_habit_grid_days() -> usize {
+    CFG.with(|cfg| cfg.habit_grid_days)
+}
+
+fn default_countdown_days() -> i64 {
+    CFG.with(|cfg| cfg.countdown_days)
+}
+
+fn default_quit_key() -> AppKey {
+    CFG.with(|cfg| cfg.quit_key)
+}
+
+fn default_draft_secs() -> u64 {
+    CFG.with(|cfg| cfg.draft_secs)
+}
+
+fn default_enter_debounce_ms() -> u64 {
+    CFG.with(|cfg| cfg.enter_debounce_ms)
+}
+
+fn default_frame_ms() -> u64 {
+    CFG.with(|cfg| cfg.frame_ms)
+}
+
+fn default_attachment_opener() -> String {
+    CFG.with(|cfg| cfg.attachment_opener.clone())
+}
+
+fn default_journal_format() -> String {
+    CFG.with(|cfg| cfg.journal_format.clone())
+}
+
+fn default_date_format() -> String {
+    CFG.with(|cfg| cfg.date_format.clone())
+}
+
+fn default_week_start() -> String {
+    CFG.with(|cfg| cfg.week_start.clone())
+}
+
+fn default_strikethrough() -> bool {
+    CFG.with(|cfg| cfg.strikethrough)
+}
+
+fn default_narrow_list_char() -> char {
+    CFG.with(|cfg| cfg.narrow_list_char)
+}
+
+fn default_widen_list_char() -> char {
+    CFG.with(|cfg| cfg.widen_list_char)
+}
+
+fn default_pane_split() -> PaneSplit {
+    CFG.with(|cfg| cfg.pane_split)
+}
+
+fn default_toast_ticks() -> u64 {
+    CFG.with(|cfg| cfg.toast_ticks)
+}
+
+fn default_max_running_cmds() -> usize {
+    CFG.with(|cfg| cfg.max_running_cmds)
+}
+
+fn default_db_path() -> String {
+    CFG.with(|cfg| cfg.db_path.clone())
+}
+
+fn default_note_output_lines() -> usize {
+    CFG.with(|cfg| cfg.note_output_lines)
+}
+
 pub fn save_cfg_file() -> io::Result<()> {
     let mut home = dirs::home_dir().expect("home dir not found");
     home.push(".forget");
@@ -413,7 +1280,6 @@ pub fn save_cfg_file() -> io::Result<()> {
 
         CFG.with(move |cfg| {
             let home = home;
-            println!("{:?}", home);
             let json_str = serde_json::to_string_pretty(cfg).expect("serialization failed");
 
             let mut fd = fs::OpenOptions::new()
@@ -430,6 +1296,16 @@ pub fn save_cfg_file() -> io::Result<()> {
     }
 }
 
+/// Writes `cfg` over the config file, for settings changed while running.
+pub fn save_config(cfg: &AppConfig) -> io::Result<()> {
+    let mut home = dirs::home_dir().expect("home dir not found");
+    home.push(".forget");
+    home.push("config.json");
+
+    let json_str = serde_json::to_string_pretty(cfg)?;
+    fs::write(home, json_str)
+}
+
 pub fn open_cfg_file() -> io::Result<AppConfig> {
     let mut home = dirs::home_dir().unwrap();
     home.push(".forget");
@@ -439,10 +1315,120 @@ pub fn open_cfg_file() -> io::Result<AppConfig> {
     Ok(serde_json::from_str::<AppConfig>(&json_raw).expect("deserialization failed"))
 }
 
+/// A project's own sticky notes, found in it or a directory above it.
+pub const PROJECT_DB: &str = ".forget/notes.json";
+
+/// The data base in use, see `db_path` and `choose_db`.
+struct Db {
+    path: PathBuf,
+    /// What the title bar calls it, `None` for the `db_path` one.
+    label: Option<String>,
+}
+
+static DB: OnceLock<Db> = OnceLock::new();
+
+/// `key` of the config file. Only that key is looked at so everything that
+/// opens the data base finds it before the rest of the config is checked.
+fn configured(key: &str) -> Option<serde_json::Value> {
+    let mut cfg = dirs::home_dir().expect("home dir not found");
+    cfg.push(".forget");
+    cfg.push("config.json");
+    let raw = fs::read_to_string(cfg).ok()?;
+    serde_json::from_str::<serde_json::Value>(&raw)
+        .ok()?
+        .get(key)
+        .cloned()
+}
+
+/// `path` expanded, a relative one is in the current directory.
+fn resolve(path: &str) -> PathBuf {
+    let path = paths::expand(path);
+    match std::env::current_dir() {
+        Ok(cwd) if path.is_relative() => cwd.join(path),
+        _ => path,
+    }
+}
+
+/// The `PROJECT_DB` of the current directory or the closest one above it,
+/// like git finds a repository.
+pub fn project_db() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(PROJECT_DB))
+        .find(|path| path.is_file())
+}
+
+fn db() -> &'static Db {
+    DB.get_or_init(|| match project_db() {
+        Some(path) => Db {
+            // the project is the directory holding `.forget`
+            label: path
+                .parent()
+                .and_then(Path::parent)
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().into_owned()),
+            path,
+        },
+        None => Db {
+            path: resolve(
+                &configured("db_path")
+                    .and_then(|path| path.as_str().map(str::to_string))
+                    .unwrap_or_else(default_db_path),
+            ),
+            label: None,
+        },
+    })
+}
+
+/// The data base chosen by `choose_db`, or else the project's own, or else
+/// the `db_path` of the config file. Found once.
+pub fn db_path() -> PathBuf {
+    db().path.clone()
+}
+
+/// The name of the data base in use for the title bar, `None` when it's the
+/// `db_path` one.
+pub fn db_label() -> Option<String> {
+    db().label.clone()
+}
+
+/// Takes `--db <path>` and `--profile <name>` out of `args`, the data base
+/// they name is used from then on instead of `db_path`. A profile is a name
+/// for a data base in `profiles`.
+pub fn choose_db(args: &mut Vec<String>) -> io::Result<()> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidInput, msg);
+    let mut take = |flag: &str| match args.iter().position(|arg| arg == flag) {
+        Some(i) if i + 1 < args.len() => {
+            let value = args.remove(i + 1);
+            args.remove(i);
+            Ok(Some(value))
+        }
+        Some(_) => Err(invalid(format!("{} needs a value", flag))),
+        None => Ok(None),
+    };
+    let db = take("--db")?;
+    let profile = take("--profile")?;
+    // the title bar shows the profile or else the file
+    let (path, label) = match (db, profile) {
+        (Some(db), _) => {
+            let path = resolve(&db);
+            let file = path.file_name().map(|f| f.to_string_lossy().into_owned());
+            (path, file)
+        }
+        (None, Some(name)) => {
+            let path = configured("profiles")
+                .and_then(|profiles| profiles.get(&name)?.as_str().map(str::to_string))
+                .ok_or_else(|| invalid(format!("there is no profile called {:?}", name)))?;
+            (resolve(&path), Some(name))
+        }
+        (None, None) => return Ok(()),
+    };
+    let _ = DB.set(Db { path, label });
+    Ok(())
+}
+
 pub fn open_db() -> io::Result<ListState<Remind>> {
-    let mut home = dirs::home_dir().unwrap();
-    home.push(".forget");
-    home.push("note_db.json");
+    let home = db_path();
 
     if !Path::new(&home).exists() {
         let mut dir = home.clone();
@@ -464,12 +1450,41 @@ pub fn open_db() -> io::Result<ListState<Remind>> {
     Ok(serde_json::from_str::<ListState<Remind>>(&json_raw).expect("deserialization failed"))
 }
 
+/// Adds `notes` to the end of `archive.json` next to the data base, secret
+/// notes stay encrypted.
+pub fn archive_notes(notes: &[Remind]) -> io::Result<()> {
+    if notes.is_empty() {
+        return Ok(());
+    }
+    let home = db_path().with_file_name("archive.json");
+
+    let mut archive = if Path::new(&home).exists() {
+        serde_json::from_str::<Vec<Remind>>(&fs::read_to_string(&home)?)?
+    } else {
+        Vec::new()
+    };
+    for note in notes {
+        archive.push(note.sealed()?);
+    }
+    fs::write(home, serde_json::to_string(&archive)?)
+}
+
+/// The sticky notes as they are written, secret notes are only ever written
+/// encrypted.
+pub fn sealed(notes: &ListState<Remind>) -> io::Result<ListState<Remind>> {
+    Ok(ListState {
+        items: notes
+            .iter()
+            .map(Remind::sealed)
+            .collect::<io::Result<Vec<_>>>()?,
+        selected: notes.selected,
+    })
+}
+
 pub fn save_db(notes: &ListState<Remind>) -> io::Result<()> {
-    let mut home = dirs::home_dir().unwrap();
-    home.push(".forget");
-    home.push("note_db.json");
+    let home = db_path();
 
-    let json_str = serde_json::to_string(notes)?;
+    let json_str = serde_json::to_string(&sealed(notes)?)?;
     let mut fd = fs::OpenOptions::new()
         .write(true)
         .create(true)
@@ -477,3 +1492,10 @@ pub fn save_db(notes: &ListState<Remind>) -> io::Result<()> {
         .open(home)?;
     fd.write_all(json_str.as_bytes())
 }
+
+/// `save_db` with tokio's files, for saving from the background tasks.
+#[cfg(feature = "tokio")]
+pub async fn save_db_async(notes: ListState<Remind>) -> io::Result<()> {
+    let json_str = serde_json::to_string(&sealed(&notes)?)?;
+    tokio::fs::write(db_path(), json_str).await
+}
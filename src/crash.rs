@@ -0,0 +1,74 @@
+//! Puts the terminal back the way it was when anything panics, a panic in raw
+//! mode would otherwise leave the shell unusable.
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io::{self, Write};
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use chrono::Local;
+
+/// Turns off every mouse mode `MouseTerminal` turns on.
+const MOUSE_OFF: &str = "\x1b[?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l";
+
+static ORIGINAL: OnceLock<libc::termios> = OnceLock::new();
+
+/// Remembers the terminal settings and installs the panic hook, call it
+/// before switching to raw mode.
+pub fn install() {
+    let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+    if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } == 0 {
+        let _ = ORIGINAL.set(termios);
+    }
+
+    panic::set_hook(Box::new(|info| {
+        restore_terminal();
+        let report = write_report(info);
+        tracing::error!("{}", info);
+
+        eprintln!("forget crashed: {}", info);
+        match report {
+            Ok(path) => eprintln!("The crash report is in {}", path.display()),
+            Err(e) => eprintln!("Writing the crash report failed: {}", e),
+        }
+        // a panic on the input thread would leave the app running without keys
+        std::process::exit(101);
+    }));
+}
+
+fn restore_terminal() {
+    if let Some(termios) = ORIGINAL.get() {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, termios) };
+    }
+    let mut stdout = io::stdout();
+    let _ = write!(
+        stdout,
+        "{}{}{}",
+        MOUSE_OFF,
+        termion::screen::ToMainScreen,
+        termion::cursor::Show
+    );
+    let _ = stdout.flush();
+}
+
+fn write_report(info: &PanicHookInfo) -> io::Result<PathBuf> {
+    let mut path = dirs::home_dir().expect("home dir not found");
+    path.push(".forget");
+    fs::create_dir_all(&path)?;
+    path.push(format!(
+        "crash-{}.log",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    let report = format!(
+        "forget {} crashed at {}\n\n{}\n\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        info,
+        Backtrace::force_capture()
+    );
+    fs::write(&path, report)?;
+    Ok(path)
+}
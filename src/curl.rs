@@ -0,0 +1,41 @@
+//! Requests made with the `curl` command, so `forget` needs no HTTP library.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Escapes `text` for a quoted value in a curl config file.
+pub fn quote(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// Runs `curl` with `config` read from stdin so the token never shows up in `ps`.
+pub fn run(config: &str) -> io::Result<String> {
+    let mut child = Command::new("curl")
+        .args(["-fsS", "--max-time", "30", "--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // write from another thread so a full stdout pipe can't deadlock us
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let config = config.to_string();
+    let writer = thread::spawn(move || stdin.write_all(config.as_bytes()));
+
+    let output = child.wait_with_output()?;
+    writer
+        .join()
+        .map_err(|_| io::Error::other("curl writer panicked"))??;
+
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,179 @@
+//! How dates and times are shown and typed, set with `date_format`,
+//! `time_format`, `clock_12h`, `week_start`, `day_names` and `month_names`.
+//! The data base and activity log always store them the same way, so
+//! changing these never breaks them.
+
+use std::io;
+use std::str::FromStr;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+
+use crate::config::AppConfig;
+
+/// The formats used when none are set, always understood when typed too.
+pub const DATE: &str = "%Y-%m-%d";
+pub const TIME: &str = "%H:%M";
+const TIME_12H: &str = "%I:%M %p";
+
+const DAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+#[derive(Clone, Debug)]
+pub struct Dates {
+    date: String,
+    time: String,
+    week_start: Weekday,
+    /// Monday first.
+    day_names: Vec<String>,
+    month_names: Vec<String>,
+}
+
+impl Default for Dates {
+    fn default() -> Self {
+        Dates {
+            date: DATE.into(),
+            time: TIME.into(),
+            week_start: Weekday::Mon,
+            day_names: DAY_NAMES.iter().map(|d| d.to_string()).collect(),
+            month_names: MONTH_NAMES.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+}
+
+impl Dates {
+    /// An error names the setting that's wrong.
+    pub fn new(config: &AppConfig) -> io::Result<Dates> {
+        let time = match &config.time_format {
+            Some(time) => time.clone(),
+            None if config.clock_12h => TIME_12H.into(),
+            None => TIME.into(),
+        };
+        for (name, format) in [("date_format", &config.date_format), ("time_format", &time)] {
+            if StrftimeItems::new(format).any(|item| item == Item::Error) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} \"{}\" is not a chrono format", name, format),
+                ));
+            }
+        }
+        let week_start = Weekday::from_str(&config.week_start).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("week_start \"{}\" is not a day", config.week_start),
+            )
+        })?;
+        let names = |names: &[String], english: &[&str], setting: &str| match names.len() {
+            0 => Ok(english.iter().map(|n| n.to_string()).collect()),
+            n if n == english.len() => Ok(names.to_vec()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} needs {} names", setting, english.len()),
+            )),
+        };
+        Ok(Dates {
+            date: config.date_format.clone(),
+            time,
+            week_start,
+            day_names: names(&config.day_names, &DAY_NAMES, "day_names")?,
+            month_names: names(&config.month_names, &MONTH_NAMES, "month_names")?,
+        })
+    }
+
+    /// The `week_start` day on or before `date`.
+    pub fn start_of_week(&self, date: NaiveDate) -> NaiveDate {
+        let back = (date.weekday().num_days_from_monday() + 7
+            - self.week_start.num_days_from_monday())
+            % 7;
+        date - Duration::days(back as i64)
+    }
+
+    pub fn day_name(&self, day: Weekday) -> &str {
+        &self.day_names[day.num_days_from_monday() as usize]
+    }
+
+    pub fn month_name(&self, date: NaiveDate) -> &str {
+        &self.month_names[date.month0() as usize]
+    }
+
+    pub fn day(&self, date: NaiveDate) -> String {
+        date.format(&self.date).to_string()
+    }
+
+    pub fn date<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        at.format(&self.date).to_string()
+    }
+
+    pub fn time<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        at.format(&self.time).to_string()
+    }
+
+    pub fn date_time<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        format!("{} {}", self.date(at), self.time(at))
+    }
+
+    /// Like `date_time` for a unix time.
+    pub fn timestamp(&self, at: i64) -> String {
+        self.date_time(&Local.timestamp(at, 0))
+    }
+
+    /// The day of the week and the time, for dates in the coming week.
+    pub fn weekday_time(&self, at: &DateTime<Local>) -> String {
+        format!("{} {}", at.format("%a"), self.time(at))
+    }
+
+    /// Like `2020-W09`, ISO weeks when they start on Monday, otherwise the
+    /// weeks of the year counted from the first `week_start` day.
+    pub fn week(&self, at: &DateTime<Local>) -> String {
+        if self.week_start == Weekday::Mon {
+            return at.format("%G-W%V").to_string();
+        }
+        let date = at.naive_local().date();
+        let start = self.start_of_week(NaiveDate::from_ymd(date.year(), 1, 1));
+        format!("{}-W{:02}", date.year(), (date - start).num_days() / 7 + 1)
+    }
+
+    /// Parses a date typed in `date_format` or as `2020-03-01`.
+    pub fn parse_date(&self, text: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(text, &self.date)
+            .or_else(|_| NaiveDate::parse_from_str(text, DATE))
+            .ok()
+    }
+
+    /// Parses a time typed in `time_format` or as `14:30`.
+    pub fn parse_time(&self, text: &str) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(text, &self.time)
+            .or_else(|_| NaiveTime::parse_from_str(text, TIME))
+            .ok()
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// How many frames the average frame time is taken over.
+const FRAMES: usize = 60;
+
+/// What the debug overlay shows, `main` fills it in every loop.
+#[derive(Clone, Debug, Default)]
+pub struct DebugStats {
+    frames: VecDeque<Duration>,
+    /// Events sent by the input and tick threads that haven't been handled.
+    pub queue_depth: usize,
+}
+
+impl DebugStats {
+    pub fn record_frame(&mut self, frame: Duration) {
+        if self.frames.len() == FRAMES {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    pub fn last_frame(&self) -> Duration {
+        self.frames.back().copied().unwrap_or_default()
+    }
+
+    pub fn average_frame(&self) -> Duration {
+        if self.frames.is_empty() {
+            return Duration::default();
+        }
+        self.frames.iter().sum::<Duration>() / self.frames.len() as u32
+    }
+
+    pub fn slowest_frame(&self) -> Duration {
+        self.frames.iter().max().copied().unwrap_or_default()
+    }
+}
@@ -0,0 +1,62 @@
+//! The last few todos and sticky notes deleted this session, so a slip of the
+//! delete key can be taken back right away. Nothing here is saved.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::app::{Remind, Todo};
+
+/// How many deletions are kept, the oldest is forgotten first.
+const KEPT: usize = 10;
+/// How long the last deletion is shown under the todos.
+const PEEK: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug)]
+pub enum Deleted {
+    /// A todo deleted at `idx` from the sticky note titled `note`, found
+    /// again by title since the sticky notes may have moved.
+    Todo {
+        note: String,
+        idx: usize,
+        todo: Todo,
+    },
+    /// A sticky note removed at `idx` with everything in it.
+    StickyNote { idx: usize, remind: Remind },
+}
+
+impl Deleted {
+    pub fn describe(&self) -> String {
+        match self {
+            Deleted::Todo { todo, .. } => format!("todo \"{}\"", todo.task),
+            Deleted::StickyNote { remind, .. } => format!("sticky note \"{}\"", remind.title),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RecentlyDeleted {
+    items: VecDeque<(Deleted, Instant)>,
+}
+
+impl RecentlyDeleted {
+    pub fn push(&mut self, deleted: Deleted) {
+        if self.items.len() == KEPT {
+            self.items.pop_front();
+        }
+        self.items.push_back((deleted, Instant::now()));
+    }
+
+    /// Takes the last deletion back out to be restored.
+    pub fn pop(&mut self) -> Option<Deleted> {
+        self.items.pop_back().map(|(deleted, _)| deleted)
+    }
+
+    /// The last deletion while it's recent enough to show, with how many
+    /// more are kept behind it.
+    pub fn peek(&self) -> Option<(&Deleted, usize)> {
+        self.items
+            .back()
+            .filter(|(_, at)| at.elapsed() < PEEK)
+            .map(|(deleted, _)| (deleted, self.items.len() - 1))
+    }
+}
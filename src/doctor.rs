@@ -0,0 +1,292 @@
+//! `forget doctor`, checks the data base and config for problems and fixes
+//! the ones it can.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use chrono::Local;
+use serde_json::Value;
+
+use crate::app::{ListState, Remind, Status};
+use crate::attach;
+use crate::config::{self, AppConfig, CFG};
+use crate::dates::Dates;
+use crate::holidays::Holidays;
+
+/// Keys terminals send as other keys, see the README.
+const HIJACKED_CTRL: [&str; 3] = ["i", "j", "m"];
+
+struct Issue {
+    text: String,
+    fixable: bool,
+}
+
+#[derive(Default)]
+struct Report {
+    issues: Vec<Issue>,
+}
+
+impl Report {
+    fn fixable(&mut self, text: String) {
+        self.issues.push(Issue {
+            text,
+            fixable: true,
+        });
+    }
+
+    fn broken(&mut self, text: String) {
+        self.issues.push(Issue {
+            text,
+            fixable: false,
+        });
+    }
+
+    fn fixable_count(&self) -> usize {
+        self.issues.iter().filter(|i| i.fixable).count()
+    }
+}
+
+fn forget_file(name: &str) -> PathBuf {
+    let mut home = dirs::home_dir().expect("home dir not found");
+    home.push(".forget");
+    home.push(name);
+    home
+}
+
+/// Checks everything and prints what is wrong, the fixes are applied when
+/// `fix` is set or the user agrees to them.
+pub fn run(fix: bool) -> io::Result<()> {
+    let mut report = Report::default();
+    let config = check_config(&mut report)?;
+    let notes = check_db(&mut report)?;
+
+    if report.issues.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+    for issue in report.issues.iter() {
+        let tag = if issue.fixable { "fixable" } else { "manual" };
+        println!("[{}] {}", tag, issue.text);
+    }
+
+    let fixable = report.fixable_count();
+    if fixable == 0 {
+        println!("Nothing can be fixed automatically, edit the files in ~/.forget by hand.");
+        return Ok(());
+    }
+    if !fix && !ask(&format!("Fix {} problems? [y/N] ", fixable))? {
+        println!("Nothing changed, `forget doctor --fix` fixes them without asking.");
+        return Ok(());
+    }
+
+    if let Some(config) = config {
+        config::save_config(&config)?;
+    }
+    if let Some(notes) = notes {
+        config::save_db(&notes)?;
+    }
+    println!("Fixed {} problems.", fixable);
+    Ok(())
+}
+
+fn ask(question: &str) -> io::Result<bool> {
+    print!("{}", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Returns the repaired config when something in it can be fixed.
+fn check_config(report: &mut Report) -> io::Result<Option<AppConfig>> {
+    let path = forget_file("config.json");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut value = match serde_json::from_str::<Value>(&fs::read_to_string(&path)?) {
+        Ok(value) => value,
+        Err(e) => {
+            report.broken(format!("config.json is not valid JSON: {}", e));
+            return Ok(None);
+        }
+    };
+
+    let known = CFG
+        .with(|cfg| serde_json::to_value(cfg))
+        .map_err(io::Error::other)?;
+    let known = known
+        .as_object()
+        .map(|o| o.keys().cloned().collect::<HashSet<_>>());
+    let mut changed = false;
+    if let (Some(fields), Some(known)) = (value.as_object_mut(), known) {
+        let unknown = fields
+            .keys()
+            .filter(|k| !known.contains(*k))
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in unknown {
+            report.fixable(format!("config.json has the unknown key \"{}\"", key));
+            fields.remove(&key);
+            changed = true;
+        }
+    }
+
+    let config = match serde_json::from_value::<AppConfig>(value) {
+        Ok(config) => config,
+        Err(e) => {
+            report.broken(format!("config.json can't be read: {}", e));
+            return Ok(None);
+        }
+    };
+
+    if let Err(e) = Dates::new(&config) {
+        report.broken(e.to_string());
+    }
+    if let Err(e) = Holidays::load(config.holidays_file.as_deref()) {
+        report.broken(e.to_string());
+    }
+
+    let mut bound = HashMap::new();
+    let json = serde_json::to_value(&config).map_err(io::Error::other)?;
+    for (name, key) in json.as_object().into_iter().flatten() {
+        let key = match (name.ends_with("_char_ctrl"), key.as_str()) {
+            (true, Some(key)) => key.to_string(),
+            _ => continue,
+        };
+        if HIJACKED_CTRL.contains(&key.as_str()) {
+            report.broken(format!(
+                "{} is ctrl-{} which the terminal sends as another key",
+                name, key
+            ));
+        }
+        if let Some(other) = bound.insert(key.clone(), name.clone()) {
+            report.broken(format!("{} and {} are both ctrl-{}", other, name, key));
+        }
+    }
+
+    Ok(if changed { Some(config) } else { None })
+}
+
+/// Returns the repaired sticky notes when something in them can be fixed.
+fn check_db(report: &mut Report) -> io::Result<Option<ListState<Remind>>> {
+    let path = config::db_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut notes = match serde_json::from_str::<ListState<Remind>>(&fs::read_to_string(&path)?) {
+        Ok(notes) => notes,
+        Err(e) => {
+            report.broken(format!("{} can't be read: {}", path.display(), e));
+            return Ok(None);
+        }
+    };
+    let before = report.fixable_count();
+
+    if !notes.items.is_empty() && notes.selected >= notes.items.len() {
+        report.fixable("the selected sticky note doesn't exist".to_string());
+        notes.selected = 0;
+    }
+
+    // smart sticky notes only hold copies and locked ones can't be read
+    let todos = |notes: &ListState<Remind>| {
+        notes
+            .iter()
+            .filter(|n| !n.is_view() && !n.is_locked())
+            .flat_map(|n| n.list.iter().map(|t| t.id))
+            .collect::<Vec<_>>()
+    };
+    let mut next_id = notes.next_todo_id();
+    let mut seen = HashSet::new();
+    for remind in notes
+        .items
+        .iter_mut()
+        .filter(|n| !n.is_view() && !n.is_locked())
+    {
+        for todo in remind.list.items.iter_mut().filter(|t| t.id != 0) {
+            if !seen.insert(todo.id) {
+                report.fixable(format!(
+                    "\"{}\" in \"{}\" has the duplicate id #{}, it becomes #{}",
+                    todo.task, remind.title, todo.id, next_id
+                ));
+                todo.id = next_id;
+                next_id += 1;
+            }
+        }
+    }
+
+    let ids = todos(&notes).into_iter().collect::<HashSet<_>>();
+    // a blocker may be hidden in a locked secret note
+    let hidden = notes.iter().any(Remind::is_locked);
+    let now = Local::now();
+    for remind in notes
+        .items
+        .iter_mut()
+        .filter(|n| !n.is_view() && !n.is_locked())
+    {
+        let title = remind.title.clone();
+        for path in remind.attachments.iter().filter(|p| !attach::exists(p)) {
+            report.broken(format!(
+                "the attachment {} of \"{}\" doesn't exist",
+                path, title
+            ));
+        }
+        let len = remind.list.items.len();
+        if len > 0 && remind.list.selected >= len {
+            report.fixable(format!("the selected todo of \"{}\" doesn't exist", title));
+            remind.list.selected = 0;
+        }
+        for todo in remind.list.items.iter_mut() {
+            match todo.blocked_by {
+                Some(id) if id == todo.id => {
+                    report.fixable(format!(
+                        "\"{}\" in \"{}\" is blocked by itself",
+                        todo.task, title
+                    ));
+                    todo.blocked_by = None;
+                }
+                Some(id) if !hidden && !ids.contains(&id) => {
+                    report.fixable(format!(
+                        "\"{}\" in \"{}\" is blocked by #{} which doesn't exist",
+                        todo.task, title, id
+                    ));
+                    todo.blocked_by = None;
+                }
+                _ => {}
+            }
+            if todo.date > now {
+                report.fixable(format!(
+                    "\"{}\" in \"{}\" was made in the future, {}",
+                    todo.task,
+                    title,
+                    todo.date.format("%Y-%m-%d %H:%M")
+                ));
+                todo.date = now;
+            }
+            match todo.started {
+                Some(started) if started > now.timestamp() => {
+                    report.fixable(format!(
+                        "\"{}\" in \"{}\" was started in the future",
+                        todo.task, title
+                    ));
+                    todo.started = Some(now.timestamp());
+                }
+                Some(_) if todo.status != Status::InProgress => {
+                    report.fixable(format!(
+                        "\"{}\" in \"{}\" is timed but isn't in progress",
+                        todo.task, title
+                    ));
+                    todo.started = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(if report.fixable_count() > before {
+        Some(notes)
+    } else {
+        None
+    })
+}
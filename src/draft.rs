@@ -0,0 +1,65 @@
+//! What's being typed into a form or the notes, saved to `draft.json` next to
+//! the data base every `draft_secs` so a terminal that dies takes nothing with
+//! it. The file is removed once the form is finished or cancelled.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{AddRemind, AddTodo};
+use crate::config;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Draft {
+    /// A todo being added to, or edited at `idx` in, the sticky note at `tab`.
+    Todo {
+        tab: usize,
+        idx: Option<usize>,
+        todo: AddTodo,
+    },
+    /// A sticky note being added, or the one at `tab` being edited.
+    StickyNote {
+        tab: Option<usize>,
+        remind: AddRemind,
+    },
+    /// The notes of the sticky note at `tab` while they are added to.
+    Note {
+        tab: usize,
+        title: String,
+        note: String,
+    },
+}
+
+impl Draft {
+    /// What the restore prompt calls it.
+    pub fn describe(&self) -> String {
+        match self {
+            Draft::Todo { todo, .. } => format!("the todo \"{}\"", todo.task),
+            Draft::StickyNote { remind, .. } => format!("the sticky note \"{}\"", remind.title),
+            Draft::Note { title, .. } => format!("the notes of \"{}\"", title),
+        }
+    }
+}
+
+fn path() -> PathBuf {
+    config::db_path().with_file_name("draft.json")
+}
+
+pub fn save(draft: &Draft) -> io::Result<()> {
+    fs::write(path(), serde_json::to_string(draft)?)
+}
+
+/// A draft left by a `forget` that didn't get to finish it, a broken file is ignored.
+pub fn load() -> Option<Draft> {
+    let text = fs::read_to_string(path()).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+pub fn remove() -> io::Result<()> {
+    match fs::remove_file(path()) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Duration, Local, TimeZone};
+
+use crate::dates::Dates;
+
+/// Parses `14:30` as the next time it is that o'clock, `tomorrow 14:30`,
+/// `2020-03-01 14:30` and `2020-03-01` as the start of that day, the date
+/// and time can be typed in the configured formats too.
+pub fn parse(text: &str, now: DateTime<Local>, dates: &Dates) -> Option<DateTime<Local>> {
+    let text = text.trim().to_lowercase();
+    if text.is_empty() {
+        return None;
+    }
+    let today = now.naive_local().date();
+    let naive = if let Some(time) = text.strip_prefix("tomorrow") {
+        let time = dates.parse_time(time.trim())?;
+        (today + Duration::days(1)).and_time(time)
+    } else if let Some(time) = dates.parse_time(&text) {
+        let at = today.and_time(time);
+        if at < now.naive_local() {
+            at + Duration::days(1)
+        } else {
+            at
+        }
+    } else if let Some(at) = text.match_indices(' ').find_map(|(i, _)| {
+        // formats can have spaces in them so try every split
+        Some(
+            dates
+                .parse_date(&text[..i])?
+                .and_time(dates.parse_time(&text[i + 1..])?),
+        )
+    }) {
+        at
+    } else {
+        dates.parse_date(&text)?.and_hms(0, 0, 0)
+    };
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// Just the time when it's today, otherwise the day too.
+pub fn short(due: i64, now: DateTime<Local>, dates: &Dates) -> String {
+    let due = Local.timestamp(due, 0);
+    if due.naive_local().date() == now.naive_local().date() {
+        dates.time(&due)
+    } else if due.naive_local().date() - now.naive_local().date() < Duration::days(7) {
+        dates.weekday_time(&due)
+    } else {
+        dates.date_time(&due)
+    }
+}
+
+/// How it's typed into the form, so editing a todo keeps its due time.
+pub fn input(due: i64, dates: &Dates) -> String {
+    dates.timestamp(due)
+}
+
+/// Whole days from today until the day of `due`, negative once it's past.
+pub fn days_until(due: i64, now: DateTime<Local>) -> i64 {
+    (Local.timestamp(due, 0).naive_local().date() - now.naive_local().date()).num_days()
+}
+
+/// Like `23 days`, `today` or `2 days ago`.
+pub fn countdown(due: i64, now: DateTime<Local>) -> String {
+    match days_until(due, now) {
+        0 => "today".to_string(),
+        1 => "1 day".to_string(),
+        -1 => "1 day ago".to_string(),
+        days if days < 0 => format!("{} days ago", -days),
+        days => format!("{} days", days),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    fn now() -> DateTime<Local> {
+        Local.ymd(2020, 3, 1).and_hms(12, 0, 0)
+    }
+
+    fn due(text: &str, dates: &Dates) -> Option<DateTime<Local>> {
+        parse(text, now(), dates)
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> Option<DateTime<Local>> {
+        Some(Local.ymd(y, m, d).and_hms(h, min, 0))
+    }
+
+    #[test]
+    fn times_are_the_next_one() {
+        let dates = Dates::default();
+        assert_eq!(due("14:30", &dates), at(2020, 3, 1, 14, 30));
+        assert_eq!(due("9:15", &dates), at(2020, 3, 2, 9, 15));
+    }
+
+    #[test]
+    fn tomorrow() {
+        let dates = Dates::default();
+        assert_eq!(due("tomorrow 9:15", &dates), at(2020, 3, 2, 9, 15));
+        assert_eq!(due("Tomorrow  14:30 ", &dates), at(2020, 3, 2, 14, 30));
+        assert_eq!(due("tomorrow", &dates), None);
+        assert_eq!(due("tomorrow noon", &dates), None);
+    }
+
+    #[test]
+    fn dates_and_times() {
+        let dates = Dates::default();
+        assert_eq!(due("2020-04-02 8:05", &dates), at(2020, 4, 2, 8, 5));
+        assert_eq!(due("2020-04-02", &dates), at(2020, 4, 2, 0, 0));
+        assert_eq!(due("2020-04-02 later", &dates), None);
+        assert_eq!(due("", &dates), None);
+    }
+
+    #[test]
+    fn formats_with_spaces_are_split_right() {
+        let mut cfg = config::CFG.with(|cfg| cfg.clone());
+        cfg.date_format = "%d %b %Y".into();
+        let dates = Dates::new(&cfg).unwrap();
+        assert_eq!(due("2 Apr 2020 8:05", &dates), at(2020, 4, 2, 8, 5));
+        assert_eq!(due("2 Apr 2020", &dates), at(2020, 4, 2, 0, 0));
+        // the configured format doesn't stop the default one from working
+        assert_eq!(due("2020-04-02 8:05", &dates), at(2020, 4, 2, 8, 5));
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// How much work a todo is expected to be, either time or story points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Estimate {
+    Minutes(u64),
+    Points(u64),
+}
+
+impl Estimate {
+    /// Parses `30`, `30m`, `2h`, `1h30m` as minutes and `3p` or `3pt` as points.
+    pub fn parse(text: &str) -> Option<Estimate> {
+        let text = text.trim().to_lowercase();
+        if text.is_empty() {
+            return None;
+        }
+        if let Some(points) = text
+            .strip_suffix("pts")
+            .or_else(|| text.strip_suffix("pt"))
+            .or_else(|| text.strip_suffix('p'))
+        {
+            return points.trim().parse().ok().map(Estimate::Points);
+        }
+        if let Ok(minutes) = text.parse() {
+            return Some(Estimate::Minutes(minutes));
+        }
+
+        let mut minutes = 0;
+        let mut number = String::new();
+        for c in text.chars() {
+            match c {
+                '0'..='9' => number.push(c),
+                'h' | 'm' => {
+                    let n = number.parse::<u64>().ok()?;
+                    minutes += if c == 'h' { n * 60 } else { n };
+                    number.clear();
+                }
+                ' ' => {}
+                _ => return None,
+            }
+        }
+        if number.is_empty() {
+            Some(Estimate::Minutes(minutes))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Estimate::Minutes(minutes) => write!(f, "{}", Duration(*minutes * 60)),
+            Estimate::Points(points) => write!(f, "{}p", points),
+        }
+    }
+}
+
+/// Seconds shown as `45m`, `2h` or `1h30m`.
+pub struct Duration(pub u64);
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let minutes = self.0 / 60;
+        match (minutes / 60, minutes % 60) {
+            (0, m) => write!(f, "{}m", m),
+            (h, 0) => write!(f, "{}h", h),
+            (h, m) => write!(f, "{}h{}m", h, m),
+        }
+    }
+}
+
+/// The estimates of a sticky note's open todos added up.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Remaining {
+    pub minutes: u64,
+    pub points: u64,
+}
+
+impl Remaining {
+    pub fn add(&mut self, estimate: Estimate) {
+        match estimate {
+            Estimate::Minutes(m) => self.minutes += m,
+            Estimate::Points(p) => self.points += p,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.minutes == 0 && self.points == 0
+    }
+}
+
+impl fmt::Display for Remaining {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.minutes, self.points) {
+            (0, p) => write!(f, "{}p remaining", p),
+            (m, 0) => write!(f, "~{} remaining", Duration(m * 60)),
+            (m, p) => write!(f, "~{} + {}p remaining", Duration(m * 60), p),
+        }
+    }
+}
@@ -1,56 +1,174 @@
+use std::cell::Cell;
 use std::io;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvError, TryRecvError, TrySendError};
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
-use termion::event::Key;
-use termion::input::TermRead;
+use termion::event::{self, Key, MouseEvent};
+
+use crate::worker::TaskEvent;
 
 pub enum Event<I> {
     Input(I),
+    Mouse(MouseEvent),
     Tick,
+    /// Time to draw the next frame of an animation, nothing else changes.
+    Frame,
+    /// A background task moved on or finished.
+    Task(TaskEvent),
+}
+
+/// Sends the events of background tasks into the main loop.
+#[derive(Clone, Debug)]
+pub struct TaskSender {
+    send: mpsc::SyncSender<Event<Key>>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl TaskSender {
+    pub fn send(&self, event: TaskEvent) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        // the app may have quit while the task ran
+        if self.send.send(Event::Task(event)).is_err() {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
 }
 
-/// A small event handler that wrap termion input and tick events. Each event
-/// type is handled in its own thread and returned to a common `Receiver`
+/// How many events can wait before the input thread has to wait for the UI.
+const CAPACITY: usize = 256;
+
+/// A small event handler that wrap termion key, mouse and tick events. Each event
+/// type is handled in its own thread and returned to a common `Receiver`. The
+/// channel is bounded, ticks that pile up while the UI is busy count as one and
+/// keys waiting behind a tick are handled first.
 pub struct EventHandle {
     recv: mpsc::Receiver<Event<Key>>,
+    tasks: TaskSender,
+    /// Set while a tick is in the channel, the tick thread skips sending another.
+    tick_queued: Arc<AtomicBool>,
+    /// A tick taken out of the channel but held back for the keys behind it.
+    tick_held: Cell<bool>,
+    /// While set the input thread leaves stdin alone, so another program can use the terminal.
+    paused: Arc<AtomicBool>,
+    /// Events sent but not yet taken by `next`.
+    pending: Arc<AtomicUsize>,
+    /// While set the frame thread sends a `Frame` every `frame_rate`.
+    animating: Arc<AtomicBool>,
+    /// Set while a frame is in the channel, like `tick_queued`.
+    frame_queued: Arc<AtomicBool>,
     input_handle: thread::JoinHandle<()>,
     tick_handle: thread::JoinHandle<()>,
+    frame_handle: thread::JoinHandle<()>,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct Config {
     pub exit_key: Key,
     pub tick_rate: Duration,
+    pub frame_rate: Duration,
 }
 
 impl EventHandle {
     pub fn with_config(cfg: Config) -> Self {
-        let (send, recv) = mpsc::channel();
+        let (send, recv) = mpsc::sync_channel(CAPACITY);
+        let tick_queued = Arc::new(AtomicBool::new(false));
+        let paused = Arc::new(AtomicBool::new(false));
+        let pending = Arc::new(AtomicUsize::new(0));
+        let animating = Arc::new(AtomicBool::new(false));
+        let frame_queued = Arc::new(AtomicBool::new(false));
         let input_handle = {
             let send = send.clone();
+            let paused = paused.clone();
+            let pending = pending.clone();
             thread::spawn(move || {
-                let stdin = io::stdin();
-                for ev in stdin.keys() {
-                    match ev {
-                        Ok(key) => {
-                            if let Err(_e) = send.send(Event::Input(key)) {
+                let mut buf = [0; 64];
+                // bytes of a sequence split across reads wait here for the rest
+                let mut unread = Vec::new();
+                loop {
+                    if paused.load(Ordering::SeqCst) {
+                        unread.clear();
+                        thread::sleep(POLL);
+                        continue;
+                    }
+                    if !stdin_ready() {
+                        // nothing followed, so a lone escape was the escape key
+                        if unread == [b'\x1B'] {
+                            unread.clear();
+                            let esc = event::Event::Key(Key::Esc);
+                            if !send_event(&send, &pending, esc, cfg.exit_key) {
                                 return;
                             }
-                            if key == cfg.exit_key {
+                        }
+                        continue;
+                    }
+                    let n = match read_stdin(&mut buf) {
+                        Ok(0) => return,
+                        Ok(n) => n,
+                        Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                        Err(e) => panic!("{:?}", e),
+                    };
+                    unread.extend_from_slice(&buf[..n]);
+                    let mut start = 0;
+                    while let Some((len, ev)) = split_event(&unread[start..]) {
+                        start += len;
+                        if let Some(ev) = ev {
+                            if !send_event(&send, &pending, ev, cfg.exit_key) {
                                 return;
                             }
                         }
-                        Err(e) => panic!("{:?}", e),
                     }
+                    unread.drain(..start);
+                    // no sequence is this long, it never ends
+                    if unread.len() > MAX_SEQUENCE {
+                        unread.clear();
+                    }
+                }
+            })
+        };
+        let tasks = TaskSender {
+            send: send.clone(),
+            pending: pending.clone(),
+        };
+        let frame_handle = {
+            let send = send.clone();
+            let pending = pending.clone();
+            let animating = animating.clone();
+            let frame_queued = frame_queued.clone();
+            thread::spawn(move || loop {
+                thread::sleep(cfg.frame_rate);
+                if !animating.load(Ordering::SeqCst) || frame_queued.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+                pending.fetch_add(1, Ordering::SeqCst);
+                match send.try_send(Event::Frame) {
+                    Ok(()) => {}
+                    // a frame late is a frame skipped
+                    Err(TrySendError::Full(_)) => {
+                        pending.fetch_sub(1, Ordering::SeqCst);
+                        frame_queued.store(false, Ordering::SeqCst);
+                    }
+                    Err(TrySendError::Disconnected(_)) => return,
                 }
             })
         };
         let tick_handle = {
+            let pending = pending.clone();
+            let tick_queued = tick_queued.clone();
             thread::spawn(move || loop {
-                if let Err(_e) = send.send(Event::Tick) {
-                    return;
+                if !tick_queued.swap(true, Ordering::SeqCst) {
+                    pending.fetch_add(1, Ordering::SeqCst);
+                    match send.try_send(Event::Tick) {
+                        Ok(()) => {}
+                        // the UI is behind, it gets a tick once it catches up
+                        Err(TrySendError::Full(_)) => {
+                            pending.fetch_sub(1, Ordering::SeqCst);
+                            tick_queued.store(false, Ordering::SeqCst);
+                        }
+                        Err(TrySendError::Disconnected(_)) => return,
+                    }
                 }
                 thread::sleep(cfg.tick_rate);
             })
@@ -58,18 +176,271 @@ impl EventHandle {
 
         EventHandle {
             recv,
+            tasks,
+            tick_queued,
+            tick_held: Cell::new(false),
+            paused,
+            pending,
+            animating,
+            frame_queued,
             input_handle,
             tick_handle,
+            frame_handle,
+        }
+    }
+
+    /// The next key or mouse event, a tick only once none are waiting.
+    pub fn next(&self) -> Result<Event<Key>, RecvError> {
+        loop {
+            let event = match self.recv.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Empty) if self.tick_held.get() => {
+                    self.tick_held.set(false);
+                    return Ok(Event::Tick);
+                }
+                Err(TryRecvError::Empty) => self.recv.recv()?,
+                Err(TryRecvError::Disconnected) => return Err(RecvError),
+            };
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            match event {
+                Event::Tick => {
+                    self.tick_queued.store(false, Ordering::SeqCst);
+                    self.tick_held.set(true);
+                }
+                Event::Frame => {
+                    self.frame_queued.store(false, Ordering::SeqCst);
+                    return Ok(Event::Frame);
+                }
+                event => return Ok(event),
+            }
         }
     }
 
-    pub fn next(&self) -> Result<Event<Key>, mpsc::RecvError> {
-        self.recv.recv()
+    /// For background tasks to report back with.
+    pub fn task_sender(&self) -> TaskSender {
+        self.tasks.clone()
+    }
+
+    /// How many events are waiting to be handled.
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Stops reading keys until `resume`, used while `$EDITOR` has the terminal.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Turns the frames between ticks on or off.
+    pub fn animate(&self, on: bool) {
+        self.animating.store(on, Ordering::SeqCst);
     }
 
     #[allow(dead_code)]
     pub fn shutdown(self) {
         let _ = self.input_handle.join();
         let _ = self.tick_handle.join();
+        let _ = self.frame_handle.join();
+    }
+}
+
+/// Sends a key or mouse event on, false once the input thread should stop.
+fn send_event(
+    send: &mpsc::SyncSender<Event<Key>>,
+    pending: &AtomicUsize,
+    ev: event::Event,
+    exit_key: Key,
+) -> bool {
+    let event = match ev {
+        event::Event::Key(key) => Event::Input(key),
+        event::Event::Mouse(mouse) => Event::Mouse(mouse),
+        event::Event::Unsupported(_) => return true,
+    };
+    let exit = matches!(event, Event::Input(key) if key == exit_key);
+    pending.fetch_add(1, Ordering::SeqCst);
+    send.send(event).is_ok() && !exit
+}
+
+/// The most bytes kept waiting for the end of a sequence.
+const MAX_SEQUENCE: usize = 256;
+
+/// Splits the first event off `bytes`, `None` while the rest of it hasn't
+/// been read yet. A sequence termion can't parse is skipped with no event.
+fn split_event(bytes: &[u8]) -> Option<(usize, Option<event::Event>)> {
+    let len = event_len(bytes)?;
+    let seq = &bytes[..len];
+    // termion unwraps inside sequences it expects to be well formed
+    let ev = if well_formed(seq) {
+        event::parse_event(seq[0], &mut seq[1..].iter().map(|b| Ok(*b))).ok()
+    } else {
+        None
+    };
+    Some((len, ev))
+}
+
+/// The length of the event at the start of `bytes` once all of it is there.
+fn event_len(bytes: &[u8]) -> Option<usize> {
+    match *bytes {
+        [] | [b'\x1B'] => None,
+        [b'\x1B', b'O', ..] => bytes.get(2).map(|_| 3),
+        [b'\x1B', b'[', ..] => csi_len(&bytes[2..]).map(|n| n + 2),
+        [b'\x1B', c, ..] => char_len(c, &bytes[1..]).map(|n| n + 1),
+        [c, ..] => char_len(c, bytes),
+    }
+}
+
+/// The length of a CSI sequence after its `ESC [`.
+fn csi_len(rest: &[u8]) -> Option<usize> {
+    match *rest.first()? {
+        // X10 mouse, the button and position bytes follow
+        b'M' => rest.get(3).map(|_| 4),
+        // the linux console's F1 to F5
+        b'[' => rest.get(1).map(|_| 2),
+        _ => {
+            let end = rest.iter().position(|b| !(0x20..=0x3F).contains(b))?;
+            // a byte that can't end it starts the next event
+            Some(end + usize::from((0x40..=0x7E).contains(&rest[end])))
+        }
+    }
+}
+
+/// The length of the UTF-8 character starting with `lead`, a byte that
+/// can't start one is one byte long.
+fn char_len(lead: u8, bytes: &[u8]) -> Option<usize> {
+    let len = match lead {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => 1,
+    };
+    if bytes.len() >= len {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// True when termion can parse `seq` without panicking.
+fn well_formed(seq: &[u8]) -> bool {
+    let csi = match seq {
+        [b'\x1B', b'[', csi @ ..] => csi,
+        _ => return true,
+    };
+    match csi {
+        [b'M', cb, _, _] => (32..128).contains(cb),
+        [b'[', _] => true,
+        // cut short by a byte that can't be in it
+        [.., end] if !(0x40..=0x7E).contains(end) => false,
+        [] => false,
+        [b'<', params @ .., b'M'] | [b'<', params @ .., b'm'] => numbers::<u16>(params, 3),
+        [b'<', ..] => false,
+        [params @ .., b'M'] if params.first().is_some_and(u8::is_ascii_digit) => {
+            numbers::<u16>(params, 3)
+        }
+        [params @ .., b'~'] if params.first().is_some_and(u8::is_ascii_digit) => {
+            numbers::<u8>(params, 1)
+        }
+        // anything else ends up in termion's `return None`
+        _ => true,
+    }
+}
+
+/// True when `params` are at least `min` numbers split by `;`, each fitting in `N`.
+fn numbers<N: std::str::FromStr>(params: &[u8], min: usize) -> bool {
+    let params = match std::str::from_utf8(params) {
+        Ok(params) => params,
+        Err(_) => return false,
+    };
+    params.split(';').all(|n| n.parse::<N>().is_ok()) && params.split(';').count() >= min
+}
+
+/// How long the input thread waits for a key before checking if it was paused.
+const POLL: Duration = Duration::from_millis(50);
+
+/// Waits up to `POLL` for stdin to have something to read.
+fn stdin_ready() -> bool {
+    let mut fds = libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    unsafe { libc::poll(&mut fds, 1, POLL.as_millis() as libc::c_int) > 0 }
+}
+
+/// Reads straight from the file descriptor, `io::stdin` would buffer bytes
+/// `stdin_ready` can't see.
+fn read_stdin(buf: &mut [u8]) -> io::Result<usize> {
+    let n = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr() as *mut _, buf.len()) };
+    if n < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(n as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(bytes: &[u8]) -> (Vec<event::Event>, usize) {
+        let mut found = Vec::new();
+        let mut start = 0;
+        while let Some((len, ev)) = split_event(&bytes[start..]) {
+            start += len;
+            found.extend(ev);
+        }
+        (found, start)
+    }
+
+    #[test]
+    fn sequences_split_across_reads_wait_for_the_rest() {
+        let drag = b"\x1b[<32;10;5M";
+        for cut in 1..drag.len() {
+            assert_eq!(events(&drag[..cut]), (vec![], 0));
+        }
+        assert_eq!(
+            events(drag),
+            (
+                vec![event::Event::Mouse(MouseEvent::Hold(10, 5))],
+                drag.len()
+            )
+        );
+        assert_eq!(events("é".as_bytes()[..1].as_ref()), (vec![], 0));
+    }
+
+    #[test]
+    fn a_trailing_escape_is_not_the_escape_key_yet() {
+        assert_eq!(
+            events(b"a\x1b"),
+            (vec![event::Event::Key(Key::Char('a'))], 1)
+        );
+        assert_eq!(
+            events(b"\x1b[A\x1bOP"),
+            (
+                vec![event::Event::Key(Key::Up), event::Event::Key(Key::F(1))],
+                6
+            )
+        );
+    }
+
+    #[test]
+    fn malformed_sequences_are_skipped() {
+        for bytes in [
+            &b"\x1b[<1;2m"[..],
+            b"\x1b[1;2M",
+            b"\x1b[999~",
+            b"\x1b[<1;;2M",
+        ] {
+            assert_eq!(events(bytes), (vec![], bytes.len()));
+        }
+        // the escape cuts the first one short and starts the next
+        assert_eq!(
+            events(b"\x1b[12\x1b[B"),
+            (vec![event::Event::Key(Key::Down)], 7)
+        );
     }
 }
@@ -0,0 +1,218 @@
+//! Sticky notes that follow an RSS or Atom feed. Every new entry becomes a
+//! todo with its link attached, done means read. The feed is fetched by a
+//! background task like a remote sticky note, but the todos are kept so
+//! they can be marked read.
+
+use std::io;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Edited, Remind, Todo};
+use crate::remote;
+use crate::worker::Progress;
+
+/// A sticky note made of the entries of the feed at `url`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Feed {
+    pub title: String,
+    pub url: String,
+    /// Fetch it again this often, 0 only fetches on start and when asked.
+    #[serde(default = "default_refresh_minutes")]
+    pub refresh_minutes: u64,
+}
+
+fn default_refresh_minutes() -> u64 {
+    60
+}
+
+/// An item of an RSS feed or an entry of an Atom feed.
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub title: String,
+    pub link: Option<String>,
+}
+
+/// The entries of a finished fetch, or why it failed.
+pub struct FeedFetched {
+    pub url: String,
+    pub entries: io::Result<Vec<Entry>>,
+}
+
+/// Downloads and parses the feed at `url`, halfway once it's downloaded.
+pub fn fetch(url: &str, progress: &Progress) -> FeedFetched {
+    let body = remote::get(url);
+    progress.report(50);
+    FeedFetched {
+        url: url.to_string(),
+        entries: body.and_then(|body| parse(&body)),
+    }
+}
+
+/// The text between the first `<tag ...>` and `</tag>` in `xml` and what
+/// comes after it.
+fn element<'a>(xml: &'a str, tag: &str) -> Option<(&'a str, &'a str, &'a str)> {
+    let mut from = 0;
+    loop {
+        let start = from + xml[from..].find(&format!("<{}", tag))?;
+        let rest = &xml[start + tag.len() + 1..];
+        // `<link>` mustn't match `<linkage>`
+        if !rest.starts_with(['>', ' ', '/', '\t', '\n', '\r']) {
+            from = start + 1;
+            continue;
+        }
+        let open_end = rest.find('>')?;
+        let attrs = &rest[..open_end];
+        if let Some(attrs) = attrs.strip_suffix('/') {
+            return Some((attrs, "", &rest[open_end + 1..]));
+        }
+        let body = &rest[open_end + 1..];
+        let close = format!("</{}>", tag);
+        let end = body.find(&close)?;
+        return Some((attrs, &body[..end], &body[end + close.len()..]));
+    }
+}
+
+/// Every `<tag>` in `xml` as its attributes and text.
+fn elements<'a>(mut xml: &'a str, tag: &str) -> Vec<(&'a str, &'a str)> {
+    let mut found = Vec::new();
+    while let Some((attrs, body, rest)) = element(xml, tag) {
+        found.push((attrs, body));
+        xml = rest;
+    }
+    found
+}
+
+/// The value of `name="..."` in the attributes of a tag.
+fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let at = attrs.find(&format!("{}=", name))?;
+    let rest = &attrs[at + name.len() + 1..];
+    let quote = rest.chars().next().filter(|q| *q == '"' || *q == '\'')?;
+    let rest = &rest[1..];
+    Some(&rest[..rest.find(quote)?])
+}
+
+/// `text` without CDATA wrapping, tags and entities.
+fn text(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(data) = raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|r| r.strip_suffix("]]>"))
+    {
+        return data.trim().to_string();
+    }
+    let mut out = String::new();
+    let mut rest = raw;
+    while let Some(at) = rest.find(['&', '<']) {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        if rest.starts_with('<') {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+        let end = match rest.find(';').filter(|end| *end <= 10) {
+            Some(end) => end,
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#')?.parse().ok())
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => out.push(c),
+            None => out.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The entries of an RSS or Atom feed, in the order the feed lists them.
+pub fn parse(body: &str) -> io::Result<Vec<Entry>> {
+    let atom = body.contains("<feed");
+    if !atom && !body.contains("<rss") && !body.contains("<rdf:RDF") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "this isn't an RSS or Atom feed",
+        ));
+    }
+    let tag = if atom { "entry" } else { "item" };
+    Ok(elements(body, tag)
+        .into_iter()
+        .map(|(_, item)| {
+            let title = element(item, "title")
+                .map(|(_, title, _)| text(title))
+                .unwrap_or_default();
+            let links = elements(item, "link");
+            let link = if atom {
+                // the page of the entry, not its comments or enclosures
+                links
+                    .iter()
+                    .find(|(attrs, _)| attr(attrs, "rel").is_none_or(|rel| rel == "alternate"))
+                    .and_then(|(attrs, _)| attr(attrs, "href"))
+                    .map(text)
+            } else {
+                links.first().map(|(_, link)| text(link))
+            };
+            Entry {
+                title,
+                link: link.filter(|l| !l.is_empty()),
+            }
+        })
+        .filter(|e| !e.title.is_empty() || e.link.is_some())
+        .collect())
+}
+
+/// Adds the `entries` `remind` hasn't seen yet as open todos, oldest first,
+/// with ids from `next_id`. An entry is known by its link, or its title
+/// when it has none, so todos marked done stay read and deleted ones stay
+/// gone. Returns how many were added.
+pub fn merge(remind: &mut Remind, entries: Vec<Entry>, mut next_id: u64, device: &str) -> usize {
+    let mut added = 0;
+    // feeds list the newest entry first
+    for entry in entries.into_iter().rev() {
+        let seen = match &entry.link {
+            Some(link) => remind.attachments.contains(link),
+            None => remind.list.iter().any(|t| t.task == entry.title),
+        };
+        if seen {
+            continue;
+        }
+        let task = if entry.title.is_empty() {
+            entry.link.clone().unwrap_or_default()
+        } else {
+            entry.title
+        };
+        remind.list.items.push(Todo {
+            created_on: Some(device.to_string()),
+            edited: Some(Edited {
+                device: device.to_string(),
+                at: Local::now().timestamp(),
+            }),
+            ..Todo::new(next_id, task)
+        });
+        next_id += 1;
+        if let Some(link) = entry.link {
+            remind.attachments.push(link);
+        }
+        added += 1;
+    }
+    if added > 0 {
+        remind.touch();
+    }
+    added
+}
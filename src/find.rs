@@ -0,0 +1,78 @@
+//! Finding and replacing text in the notes of a sticky note.
+
+use std::ops::Range;
+
+/// The find prompt under the notes.
+#[derive(Clone, Debug, Default)]
+pub struct Find {
+    pub query: String,
+    pub replace: String,
+    /// Typing goes to the replacement instead of the query.
+    pub replacing: bool,
+    /// Which of the matches is selected.
+    pub current: usize,
+}
+
+/// Where `query` is in `text`, matching case.
+pub fn matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    text.match_indices(query)
+        .map(|(i, m)| i..i + m.len())
+        .collect()
+}
+
+impl Find {
+    /// The text of the field being typed in.
+    pub fn focused_mut(&mut self) -> &mut String {
+        if self.replacing {
+            &mut self.replace
+        } else {
+            &mut self.query
+        }
+    }
+
+    pub fn selected(&self, text: &str) -> Option<Range<usize>> {
+        let all = matches(text, &self.query);
+        all.get(self.current.min(all.len().saturating_sub(1)))
+            .cloned()
+    }
+
+    /// Selects the next match, going back to the first after the last.
+    pub fn next(&mut self, text: &str) {
+        let count = matches(text, &self.query).len();
+        self.current = if count == 0 {
+            0
+        } else {
+            (self.current + 1) % count
+        };
+    }
+
+    /// Replaces the selected match and selects the one after it, false when
+    /// there was nothing to replace.
+    pub fn replace_one(&mut self, text: &mut String) -> bool {
+        let range = match self.selected(text) {
+            Some(range) => range,
+            None => return false,
+        };
+        text.replace_range(range.clone(), &self.replace);
+        // skip the replacement in case it contains the query too
+        let after = range.start + self.replace.len();
+        self.current = matches(text, &self.query)
+            .iter()
+            .position(|m| m.start >= after)
+            .unwrap_or(0);
+        true
+    }
+
+    /// Replaces every match, returns how many there were.
+    pub fn replace_all(&mut self, text: &mut String) -> usize {
+        let count = matches(text, &self.query).len();
+        if count > 0 {
+            *text = text.replace(&self.query, &self.replace);
+        }
+        self.current = 0;
+        count
+    }
+}
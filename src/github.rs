@@ -0,0 +1,357 @@
+//! `forget import github owner/repo` makes the open issues of a GitHub
+//! repository todos of a sticky note, only built with `--features github`.
+//! Each todo keeps its issue so importing again adds only the new ones and
+//! closes the todos whose issue was closed. With `close_issues` set, a todo
+//! done in `forget` closes its issue and `forget sync github` closes the
+//! issues of todos done meanwhile.
+
+use std::env;
+use std::io;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Edited, ListState, Remind, Status, Todo};
+use crate::config;
+use crate::curl;
+
+const API: &str = "https://api.github.com";
+
+/// The `github` section of the config.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GitHub {
+    /// The environment variable holding the token, needed to close issues
+    /// and for private repositories.
+    #[serde(default = "default_token_env")]
+    pub token_env: String,
+    /// Close the issue of a todo when it's done.
+    #[serde(default)]
+    pub close_issues: bool,
+}
+
+fn default_token_env() -> String {
+    "GITHUB_TOKEN".into()
+}
+
+impl Default for GitHub {
+    fn default() -> Self {
+        Self {
+            token_env: default_token_env(),
+            close_issues: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub state: String,
+    /// Only there for pull requests, which the issues API lists too.
+    #[serde(default)]
+    pub pull_request: Option<serde_json::Value>,
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+/// The curl config for `url`, with the token when `token_env` is set.
+fn request(url: &str, token_env: &str) -> String {
+    let mut config = format!(
+        "url = \"{}\"\nheader = \"Accept: application/vnd.github+json\"\nuser-agent = \"forget\"\n",
+        curl::quote(url)
+    );
+    if let Ok(token) = env::var(token_env) {
+        config.push_str(&format!(
+            "header = \"Authorization: Bearer {}\"\n",
+            curl::quote(&token)
+        ));
+    }
+    config
+}
+
+fn get(url: &str, token_env: &str) -> io::Result<String> {
+    curl::run(&request(url, token_env))
+}
+
+/// Splits `owner/repo#12` into the repository and the issue number.
+pub fn parse_key(key: &str) -> Option<(&str, u64)> {
+    let (repo, number) = key.rsplit_once('#')?;
+    Some((repo, number.parse().ok()?))
+}
+
+/// Closes the issue `key` is, like `owner/repo#12`.
+pub fn close(key: &str, token_env: &str) -> io::Result<()> {
+    let (repo, number) =
+        parse_key(key).ok_or_else(|| invalid(format!("{} isn't an issue", key)))?;
+    if env::var(token_env).is_err() {
+        return Err(invalid(format!(
+            "closing {} needs a token in ${}",
+            key, token_env
+        )));
+    }
+    let url = format!("{}/repos/{}/issues/{}", API, repo, number);
+    let mut config = request(&url, token_env);
+    config.push_str(&format!(
+        "request = \"PATCH\"\ndata = \"{}\"\n",
+        curl::quote(r#"{"state":"closed"}"#)
+    ));
+    curl::run(&config).map(|_| ())
+}
+
+/// The open issues of `repo`, with `label` when there is one.
+fn open_issues(repo: &str, label: Option<&str>, token_env: &str) -> io::Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    for page in 1.. {
+        let mut url = format!(
+            "{}/repos/{}/issues?state=open&per_page=100&page={}",
+            API, repo, page
+        );
+        if let Some(label) = label {
+            url.push_str(&format!("&labels={}", label.replace(' ', "%20")));
+        }
+        let found = serde_json::from_str::<Vec<Issue>>(&get(&url, token_env)?)?;
+        let last = found.len() < 100;
+        issues.extend(found.into_iter().filter(|i| i.pull_request.is_none()));
+        if last {
+            break;
+        }
+    }
+    Ok(issues)
+}
+
+/// The issue numbers an import added and the ones whose todo it closed.
+#[derive(Debug, Default, PartialEq)]
+pub struct Imported {
+    pub added: Vec<u64>,
+    pub closed: Vec<u64>,
+}
+
+/// `owner/repo#12`, what a todo keeps of its issue.
+pub fn key(repo: &str, number: u64) -> String {
+    format!("{}#{}", repo, number)
+}
+
+/// Adds the `open` issues of `repo` missing from the sticky note `title`,
+/// made when there is none, with their pages attached. The todos of issues
+/// of `repo` that aren't open any more are done when `is_closed` says so,
+/// one only missing from a filtered import may still be open.
+pub fn merge<F>(
+    notes: &mut ListState<Remind>,
+    title: &str,
+    repo: &str,
+    open: &[Issue],
+    mut is_closed: F,
+) -> Imported
+where
+    F: FnMut(u64) -> bool,
+{
+    let mut next = notes.next_todo_id();
+    let found = notes.items.iter().position(|n| n.title == title);
+    let idx = match found {
+        Some(idx) => idx,
+        None => {
+            notes.items.push(Remind::new(title.to_string()));
+            notes.items.len() - 1
+        }
+    };
+    let device = config::CFG.with(|cfg| cfg.device_name.clone());
+    let remind = &mut notes.items[idx];
+    let mut imported = Imported::default();
+
+    for issue in open {
+        let key = key(repo, issue.number);
+        if remind.list.iter().any(|t| t.issue.as_ref() == Some(&key)) {
+            continue;
+        }
+        remind.list.items.push(Todo {
+            created_on: Some(device.clone()),
+            edited: Some(Edited {
+                device: device.clone(),
+                at: Local::now().timestamp(),
+            }),
+            issue: Some(key),
+            ..Todo::new(next, issue.title.clone())
+        });
+        next += 1;
+        if !remind.attachments.contains(&issue.html_url) {
+            remind.attachments.push(issue.html_url.clone());
+        }
+        imported.added.push(issue.number);
+    }
+
+    for todo in remind.list.items.iter_mut() {
+        let number = match todo.issue.as_deref().and_then(parse_key) {
+            Some((of, number)) if of == repo => number,
+            _ => continue,
+        };
+        if !todo.status.is_closed() && !open.iter().any(|i| i.number == number) && is_closed(number)
+        {
+            todo.set_status(Status::Done);
+            todo.touch(&device);
+            imported.closed.push(number);
+        }
+    }
+    if imported != Imported::default() {
+        remind.touch();
+    }
+    imported
+}
+
+/// The issues of `repo` still `open` whose todo on the sticky note `title`
+/// is done, what a sync closes.
+pub fn done_open<'a>(
+    notes: &'a ListState<Remind>,
+    title: &str,
+    repo: &str,
+    open: &[Issue],
+) -> Vec<(u64, &'a str)> {
+    notes
+        .iter()
+        .filter(|n| n.title == title)
+        .flat_map(|n| n.list.iter())
+        .filter(|t| t.status == Status::Done)
+        .filter_map(|t| match t.issue.as_deref().and_then(parse_key) {
+            Some((of, number)) if of == repo && open.iter().any(|i| i.number == number) => {
+                Some((number, t.task.as_str()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+struct Args<'a> {
+    repo: &'a str,
+    label: Option<&'a str>,
+    title: String,
+    dry_run: bool,
+}
+
+fn parse_args<'a>(command: &str, args: &'a [String]) -> io::Result<Args<'a>> {
+    let usage = || {
+        invalid(format!(
+            "usage: forget {} github <owner/repo> [--label bug] [--note \"Title\"] [--dry-run]",
+            command
+        ))
+    };
+    if args.first().map(String::as_str) != Some("github") {
+        return Err(usage());
+    }
+    let mut repo = None;
+    let mut label = None;
+    let mut title = None;
+    let mut dry_run = false;
+    let mut words = args[1..].iter();
+    while let Some(word) = words.next() {
+        match word.as_str() {
+            "--label" => label = Some(words.next().ok_or_else(usage)?.as_str()),
+            "--note" => title = Some(words.next().ok_or_else(usage)?),
+            "--dry-run" => dry_run = true,
+            _ => repo = Some(word.as_str()),
+        }
+    }
+    let repo = repo.filter(|r| r.contains('/')).ok_or_else(usage)?;
+    Ok(Args {
+        repo,
+        label,
+        title: title.cloned().unwrap_or_else(|| repo.to_string()),
+        dry_run,
+    })
+}
+
+/// `forget import github owner/repo [--label bug] [--note "Title"] [--dry-run]`
+/// pulls the issues in, `forget sync github ..` closes the issues of done
+/// todos too when `close_issues` is set. `--dry-run` only prints what would
+/// change.
+pub fn run(command: &str, args: &[String]) -> io::Result<()> {
+    let Args {
+        repo,
+        label,
+        title,
+        dry_run,
+    } = parse_args(command, args)?;
+    config::save_cfg_file()?;
+    let github = config::open_cfg_file()?.github.unwrap_or_default();
+    let token_env = github.token_env.as_str();
+
+    let open = open_issues(repo, label, token_env)?;
+    let mut notes = config::open_db()?;
+    let mut failed = None;
+    let imported = merge(&mut notes, &title, repo, &open, |number| {
+        let url = format!("{}/repos/{}/issues/{}", API, repo, number);
+        match get(&url, token_env).and_then(|body| Ok(serde_json::from_str::<Issue>(&body)?)) {
+            Ok(issue) => issue.state == "closed",
+            Err(e) => {
+                failed = Some(e);
+                false
+            }
+        }
+    });
+    let issue = |number: &u64| {
+        open.iter()
+            .find(|i| i.number == *number)
+            .map_or("", |i| i.title.as_str())
+    };
+    let verb = |done: &str, would: &str| if dry_run { would } else { done }.to_string();
+    for number in imported.added.iter() {
+        println!(
+            "{} #{} {}",
+            verb("added", "would add"),
+            number,
+            issue(number)
+        );
+    }
+    for number in imported.closed.iter() {
+        println!(
+            "{} the todo of #{}, it's closed",
+            verb("closed", "would close"),
+            number
+        );
+    }
+
+    let mut closed = 0;
+    let to_close = if command == "sync" {
+        done_open(&notes, &title, repo, &open)
+    } else {
+        Vec::new()
+    };
+    if !to_close.is_empty() && !github.close_issues {
+        println!(
+            "{} done todos have open issues, set \"close_issues\" in the \"github\" config to close them",
+            to_close.len()
+        );
+    } else {
+        for (number, task) in to_close {
+            if dry_run {
+                println!("would close #{} {}", number, task);
+                continue;
+            }
+            match close(&key(repo, number), token_env) {
+                Ok(()) => {
+                    println!("closed #{} {}", number, task);
+                    closed += 1;
+                }
+                Err(e) => failed = Some(e),
+            }
+        }
+    }
+
+    if dry_run {
+        println!("{}: nothing changed, this was a dry run", title);
+        return Ok(());
+    }
+    config::save_db(&notes)?;
+    println!(
+        "{}: {} issues added, {} todos done, {} issues closed",
+        title,
+        imported.added.len(),
+        imported.closed.len(),
+        closed
+    );
+    match failed {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,91 @@
+//! Habit sticky notes, every todo is a habit done at most once a day. The
+//! days it was done are kept on the todo, a new day opens them all again.
+
+use chrono::{Duration, NaiveDate};
+
+use crate::app::{Remind, Status, Todo};
+use crate::dates;
+
+/// Marks a day the habit was done in the grid.
+const DONE: char = '■';
+/// Marks a day it wasn't.
+const MISSED: char = '·';
+
+fn done_on(todo: &Todo, day: NaiveDate) -> bool {
+    todo.habit_days
+        .iter()
+        .any(|d| *d == day.format(dates::DATE).to_string())
+}
+
+/// Shows today as done or not, for a new day or when `forget` starts.
+pub fn reset(remind: &mut Remind, today: NaiveDate) {
+    for todo in remind.list.items.iter_mut() {
+        todo.status = if done_on(todo, today) {
+            Status::Done
+        } else {
+            Status::Open
+        };
+    }
+}
+
+/// Records today for the habits done and takes it off the ones opened again,
+/// true when a todo changed.
+pub fn sync(remind: &mut Remind, today: NaiveDate) -> bool {
+    let day = today.format(dates::DATE).to_string();
+    let mut changed = false;
+    for todo in remind.list.items.iter_mut() {
+        let done = todo.status == Status::Done;
+        if done && !done_on(todo, today) {
+            todo.habit_days.push(day.clone());
+            todo.habit_days.sort();
+            changed = true;
+        } else if !done {
+            let before = todo.habit_days.len();
+            todo.habit_days.retain(|d| *d != day);
+            changed |= todo.habit_days.len() != before;
+        }
+    }
+    changed
+}
+
+/// The last `days` days up to today, oldest first.
+pub fn grid(todo: &Todo, today: NaiveDate, days: usize) -> String {
+    (0..days as i64)
+        .rev()
+        .map(|back| {
+            if done_on(todo, today - Duration::days(back)) {
+                DONE
+            } else {
+                MISSED
+            }
+        })
+        .collect()
+}
+
+/// The current streak, which isn't broken until today is over, and the best one.
+pub fn streaks(todo: &Todo, today: NaiveDate) -> (usize, usize) {
+    let mut days = todo
+        .habit_days
+        .iter()
+        .filter_map(|d| NaiveDate::parse_from_str(d, dates::DATE).ok())
+        .collect::<Vec<_>>();
+    days.sort();
+    days.dedup();
+
+    let mut best = 0;
+    let mut run = 0;
+    let mut last: Option<NaiveDate> = None;
+    for day in days.iter() {
+        run = match last {
+            Some(last) if *day - last == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        best = best.max(run);
+        last = Some(*day);
+    }
+    let current = match last {
+        Some(last) if last == today || last == today - Duration::days(1) => run,
+        _ => 0,
+    };
+    (current, best)
+}
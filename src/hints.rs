@@ -0,0 +1,158 @@
+//! The short `key: what it does` hints drawn in the borders of the todos,
+//! the forms and the notes, made from the config's keys and macros so they
+//! stay right after keys are changed.
+
+use std::mem;
+
+use termion::event::Key;
+
+use crate::action::Action;
+use crate::config::{AppConfig, AppKey};
+
+/// A key the way the README writes them, `ctrl-n`, `alt-u` or `F4`.
+pub fn key_name(key: Key) -> String {
+    match key {
+        Key::Char('\n') => "enter".to_string(),
+        Key::Char('\t') => "tab".to_string(),
+        Key::Char(' ') => "space".to_string(),
+        Key::Char(c) => c.to_string(),
+        Key::Ctrl(c) => format!("ctrl-{}", c),
+        Key::Alt(c) => format!("alt-{}", c),
+        Key::F(n) => format!("F{}", n),
+        Key::Backspace => "backspace".to_string(),
+        Key::Delete => "del".to_string(),
+        Key::Insert => "ins".to_string(),
+        Key::Esc => "esc".to_string(),
+        Key::BackTab => "shift-tab".to_string(),
+        Key::Up => "↑".to_string(),
+        Key::Down => "↓".to_string(),
+        Key::Left => "←".to_string(),
+        Key::Right => "→".to_string(),
+        Key::Home => "home".to_string(),
+        Key::End => "end".to_string(),
+        Key::PageUp => "page up".to_string(),
+        Key::PageDown => "page down".to_string(),
+        _ => "?".to_string(),
+    }
+}
+
+/// The key of the first macro doing only `action`, for actions without a key
+/// of their own in the config.
+pub fn macro_key(config: &AppConfig, action: &Action) -> Option<Key> {
+    config
+        .macros
+        .iter()
+        .filter(|m| m.actions.len() == 1)
+        .find(|m| mem::discriminant(&m.actions[0]) == mem::discriminant(action))
+        .and_then(|m| m.key)
+        .and_then(bound)
+}
+
+/// Each hint as `key: what`, leaving out those whose key is unbound.
+fn hints(hints: &[(Option<Key>, &str)]) -> Vec<String> {
+    hints
+        .iter()
+        .filter_map(|(key, what)| Some(format!("{}: {}", key_name((*key)?), what)))
+        .collect()
+}
+
+fn bound(key: AppKey) -> Option<Key> {
+    match key {
+        AppKey::Null => None,
+        key => Some(Key::from(key)),
+    }
+}
+
+/// Under the todos, `read_only` for smart and remote sticky notes.
+pub fn list(config: &AppConfig, empty: bool, read_only: bool) -> Vec<String> {
+    if empty {
+        return hints(&[(
+            Some(Key::Ctrl(config.new_sticky_note_char_ctrl)),
+            "new sticky note",
+        )]);
+    }
+    if read_only {
+        return hints(&[(Some(Key::Char('\n')), "run")]);
+    }
+    hints(&[
+        (Some(Key::Ctrl(config.new_todo_char_ctrl)), "new"),
+        (Some(Key::Ctrl(config.edit_todo_char_ctrl)), "edit"),
+        (bound(config.mark_done), "done"),
+        (bound(config.remove_todo), "delete"),
+    ])
+}
+
+/// Under a form, `quit_key` drops it or, when that's unbound, the key that
+/// opened it.
+pub fn form(config: &AppConfig, open_key: char) -> Vec<String> {
+    let cancel = bound(config.quit_key).unwrap_or(Key::Ctrl(open_key));
+    hints(&[
+        (Some(Key::Char('\n')), "save"),
+        (Some(Key::Down), "next field"),
+        (Some(cancel), "cancel"),
+    ])
+}
+
+/// Under the notes while they are added to.
+pub fn notes(config: &AppConfig) -> Vec<String> {
+    hints(&[(Some(Key::Ctrl(config.new_note_char_ctrl)), "done")])
+}
+
+/// Under a presented sticky note.
+pub fn present(config: &AppConfig) -> Vec<String> {
+    let stop = bound(config.quit_key).or_else(|| macro_key(config, &Action::Present));
+    hints(&[
+        (Some(Key::Char(' ')), "next"),
+        (Some(Key::Backspace), "back"),
+        (stop, "stop"),
+    ])
+}
+
+/// After a deletion, `None` when no key puts it back.
+pub fn undelete(config: &AppConfig) -> Option<String> {
+    macro_key(config, &Action::Undelete).map(|key| format!("{} puts it back", key_name(key)))
+}
+
+/// Every key of the list and every macro with what it does, for the help
+/// overlay.
+pub fn help(config: &AppConfig) -> Vec<String> {
+    let ctrl = |c: char| Some(Key::Ctrl(c));
+    let mut lines = hints(&[
+        (ctrl(config.new_sticky_note_char_ctrl), "new sticky note"),
+        (
+            ctrl(config.new_smart_note_char_ctrl),
+            "new smart sticky note",
+        ),
+        (ctrl(config.edit_sticky_note_char_ctrl), "edit sticky note"),
+        (
+            ctrl(config.remove_sticky_note_char_ctrl),
+            "delete sticky note",
+        ),
+        (ctrl(config.new_todo_char_ctrl), "new todo"),
+        (ctrl(config.edit_todo_char_ctrl), "edit todo"),
+        (bound(config.mark_done), "done"),
+        (bound(config.remove_todo), "delete todo"),
+        (ctrl(config.status_char_ctrl), "next status"),
+        (ctrl(config.new_note_char_ctrl), "notes"),
+        (ctrl(config.find_char_ctrl), "find and replace"),
+        (ctrl(config.board_char_ctrl), "list or board"),
+        (ctrl(config.editor_char_ctrl), "edit in $EDITOR"),
+        (ctrl(config.goto_due_char_ctrl), "next due"),
+        (ctrl(config.goto_blocker_char_ctrl), "go to blocker"),
+        (ctrl(config.run_all_char_ctrl), "run all commands"),
+        (ctrl(config.runbook_char_ctrl), "runbook"),
+        (ctrl(config.secret_char_ctrl), "secret"),
+        (ctrl(config.activity_char_ctrl), "activity log"),
+        (ctrl(config.record_macro_char_ctrl), "record keys"),
+        (ctrl(config.play_macro_char_ctrl), "play keys"),
+        (ctrl(config.save_state_to_db_char_ctrl), "save"),
+        (ctrl(config.debug_char_ctrl), "debug overlay"),
+        (bound(config.quit_key), "back or quit"),
+        (ctrl(config.exit_key_char_ctrl), "exit"),
+    ]);
+    lines.extend(config.macros.iter().filter_map(|m| {
+        let key = m.key.and_then(bound)?;
+        Some(format!("{}: {}", key_name(key), m.name))
+    }));
+    lines
+}
@@ -0,0 +1,54 @@
+//! Days nobody works, weekends and the dates in `holidays_file`, which has a
+//! `2020-12-25` date on each line and `#` comments.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::paths;
+
+#[derive(Clone, Debug, Default)]
+pub struct Holidays {
+    dates: HashSet<NaiveDate>,
+}
+
+impl Holidays {
+    /// No file means weekends are the only days off.
+    pub fn load(path: Option<&str>) -> io::Result<Holidays> {
+        let path = match path {
+            Some(path) => path,
+            None => return Ok(Holidays::default()),
+        };
+        let text = fs::read_to_string(paths::expand(path))
+            .map_err(|e| io::Error::new(e.kind(), format!("holidays_file {}: {}", path, e)))?;
+        let dates = text
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                NaiveDate::parse_from_str(line, "%Y-%m-%d").map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("holidays_file {} `{}`: {}", path, line, e),
+                    )
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Holidays { dates })
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.dates.contains(&date)
+    }
+
+    /// `date` itself when it's a business day, otherwise the next one.
+    pub fn next_business_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        while !self.is_business_day(date) {
+            date = date.succ();
+        }
+        date
+    }
+}
@@ -0,0 +1,119 @@
+//! `forget hooks install` puts git hooks into the repository of a project's
+//! own sticky notes, see `config::project_db`. Before a commit and after a
+//! merge they print the open todos tagged `#blocking`, with `--fail` the
+//! commit stops while there are any.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process::{self, Command};
+
+use crate::app::{ListState, Remind, Todo};
+use crate::config;
+use crate::query;
+
+/// The hooks installed.
+const HOOKS: [&str; 2] = ["pre-commit", "post-merge"];
+const TAG: &str = "#blocking";
+/// In every hook `install` writes, a hook without it isn't replaced.
+const MARK: &str = "# installed by forget hooks install";
+
+/// The open todos tagged `#blocking` and the sticky notes they're on,
+/// secret sticky notes are skipped.
+pub fn blocking(notes: &ListState<Remind>) -> Vec<(&str, &Todo)> {
+    notes
+        .iter()
+        .filter(|n| !n.is_locked())
+        .flat_map(|n| n.list.iter().map(move |t| (n.title.as_str(), t)))
+        .filter(|(_, t)| !t.status.is_closed() && query::tags(&t.task).any(|tag| tag == TAG))
+        .collect()
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+/// Where git looks for hooks, asked of git so worktrees and `core.hooksPath`
+/// are followed.
+fn hooks_dir() -> io::Result<PathBuf> {
+    let out = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()?;
+    if !out.status.success() {
+        return Err(invalid("this isn't a git repository".to_string()));
+    }
+    let dir = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    Ok(env::current_dir()?.join(dir))
+}
+
+fn install(fail: bool) -> io::Result<()> {
+    let db = config::project_db().ok_or_else(|| {
+        invalid(format!(
+            "there is no {} here, `forget --db {}` makes one",
+            config::PROJECT_DB,
+            config::PROJECT_DB
+        ))
+    })?;
+    let dir = hooks_dir()?;
+    fs::create_dir_all(&dir)?;
+    let forget = env::current_exe()?;
+    for hook in HOOKS.iter() {
+        let path = dir.join(hook);
+        if path.exists() && !fs::read_to_string(&path)?.contains(MARK) {
+            println!("{} is already there, it's left as it is", path.display());
+            continue;
+        }
+        let fail = if fail && *hook == "pre-commit" {
+            " --fail"
+        } else {
+            ""
+        };
+        let script = format!(
+            "#!/bin/sh\n{}\nexec '{}' hooks run {}{}\n",
+            MARK,
+            forget.display().to_string().replace('\'', r"'\''"),
+            hook,
+            fail
+        );
+        fs::write(&path, script)?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        println!("installed {}", path.display());
+    }
+    println!("for the sticky notes in {}", db.display());
+    Ok(())
+}
+
+/// Prints the open `#blocking` todos, exits with 1 when `fail` and there
+/// are any. Nothing happens outside a project.
+fn check(fail: bool) -> io::Result<()> {
+    if config::project_db().is_none() {
+        return Ok(());
+    }
+    let notes = config::open_db()?;
+    let blocking = blocking(&notes);
+    if blocking.is_empty() {
+        return Ok(());
+    }
+    eprintln!("forget: {} open {} todos", blocking.len(), TAG);
+    for (note, todo) in blocking.iter() {
+        eprintln!("  {} #{} {}", note, todo.id, todo.task);
+    }
+    if fail {
+        eprintln!("forget: close them or commit with --no-verify");
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// `forget hooks install [--fail]`, and `forget hooks run <hook> [--fail]`
+/// for the hooks to call.
+pub fn run(args: &[String]) -> io::Result<()> {
+    let fail = args.iter().any(|arg| arg == "--fail");
+    match args.first().map(String::as_str) {
+        Some("install") => install(fail),
+        Some("run") => check(fail),
+        _ => Err(invalid("usage: forget hooks install [--fail]".to_string())),
+    }
+}
@@ -0,0 +1,424 @@
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+/// How long a finished job stays in the jobs panel.
+const JOB_LINGER_SECS: i64 = 30;
+
+/// How a todo's command tells how it went, set in the todo form.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Report {
+    /// A toast when it's done or failed.
+    #[default]
+    Toast,
+    /// Only the jobs panel shows it.
+    Silent,
+    /// A toast only when it fails.
+    Failures,
+    /// A desktop notification with the last line it printed.
+    Notify,
+    /// What it printed to stdout is added to the notes of its sticky note.
+    Note,
+}
+
+impl Report {
+    pub const ALL: [Report; 5] = [
+        Report::Toast,
+        Report::Silent,
+        Report::Failures,
+        Report::Notify,
+        Report::Note,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Report::Toast => "toast",
+            Report::Silent => "silent",
+            Report::Failures => "failures",
+            Report::Notify => "notify",
+            Report::Note => "note",
+        }
+    }
+
+    /// The report named `name`, empty is the default.
+    pub fn parse(name: &str) -> Option<Report> {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            return Some(Report::default());
+        }
+        Report::ALL.iter().copied().find(|r| r.name() == name)
+    }
+
+    /// Whether what the command prints is kept for the report.
+    fn captures(self) -> bool {
+        matches!(self, Report::Notify | Report::Note)
+    }
+
+    /// Whether errors are kept too, the notes only get stdout.
+    fn captures_stderr(self) -> bool {
+        self == Report::Notify
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Exited(i32),
+    Killed,
+    /// Killed after running longer than the timeout.
+    TimedOut,
+    Failed(String),
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobStatus::Queued => write!(f, "queued"),
+            JobStatus::Running => write!(f, "running"),
+            JobStatus::Exited(code) => write!(f, "exit {}", code),
+            JobStatus::Killed => write!(f, "killed"),
+            JobStatus::TimedOut => write!(f, "timed out"),
+            JobStatus::Failed(e) => write!(f, "failed: {}", e),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Job {
+    /// The id of the todo that started this job.
+    pub id: u64,
+    /// The task of the todo that started this job.
+    pub label: String,
+    pub cmd: String,
+    /// The parts of `cmd` between `&&`, each runs once the one before exits with 0.
+    pub steps: Vec<String>,
+    /// How the steps that are done ended.
+    pub results: Vec<JobStatus>,
+    pub status: JobStatus,
+    /// When the first step started.
+    pub started: Option<DateTime<Local>>,
+    pub finished: Option<DateTime<Local>>,
+    pub report: Report,
+    child: Option<Child>,
+    /// Where the steps print to when the report needs it.
+    output: Option<PathBuf>,
+    /// Set once `take_finished` has handed out this job.
+    reported: bool,
+}
+
+/// A job `take_finished` hands out.
+#[derive(Debug)]
+pub struct Finished {
+    pub id: u64,
+    pub label: String,
+    pub status: JobStatus,
+    pub report: Report,
+    /// The last `output_lines` it printed, empty unless the report keeps them.
+    pub output: String,
+}
+
+impl Job {
+    fn finish(&mut self, status: JobStatus) {
+        self.status = status;
+        self.finished = Some(Local::now());
+        self.child = None;
+    }
+
+    /// Starts the next step, or finishes the job when there is none left.
+    fn start_step(&mut self) {
+        let step = match self.steps.get(self.results.len()) {
+            Some(step) => step,
+            None => return self.finish(JobStatus::Exited(0)),
+        };
+        let output = self
+            .output
+            .as_deref()
+            .map(|path| (path, self.report.captures_stderr()));
+        match spawn(step, output) {
+            Ok(child) => {
+                self.child = Some(child);
+                self.status = JobStatus::Running;
+                // the timeout is for the whole job, not each step
+                self.started.get_or_insert_with(Local::now);
+            }
+            Err(e) => self.step_finished(JobStatus::Failed(e.to_string())),
+        }
+    }
+
+    /// Goes on to the next step after a success, the job ends with the
+    /// first step that fails.
+    fn step_finished(&mut self, status: JobStatus) {
+        self.child = None;
+        self.results.push(status.clone());
+        if status == JobStatus::Exited(0) {
+            self.start_step();
+        } else {
+            self.finish(status);
+        }
+    }
+
+    /// The step running or that ended the job, like `2/3`, empty for a
+    /// job of one step.
+    pub fn progress(&self) -> String {
+        if self.steps.len() < 2 {
+            return String::new();
+        }
+        let step = if self.finished.is_some() {
+            self.results.len()
+        } else {
+            self.results.len() + 1
+        };
+        format!("{}/{}", step, self.steps.len())
+    }
+
+    /// The command of the step running or that ended the job.
+    pub fn current_cmd(&self) -> &str {
+        let idx = if self.finished.is_some() {
+            self.results.len().saturating_sub(1)
+        } else {
+            self.results.len()
+        };
+        self.steps
+            .get(idx)
+            .map_or(self.cmd.as_str(), |s| s.as_str())
+    }
+}
+
+/// The parts of `cmd` between `&&`.
+pub fn steps(cmd: &str) -> Vec<String> {
+    cmd.split("&&")
+        .map(|step| step.trim().to_string())
+        .collect()
+}
+
+/// Every command spawned by `forget`, queued jobs are started as soon as
+/// fewer than `max_running` jobs are running (0 means no limit). Jobs
+/// running longer than `timeout_secs` are killed (0 means no timeout).
+#[derive(Debug, Default)]
+pub struct Jobs {
+    pub list: Vec<Job>,
+    pub max_running: usize,
+    pub timeout_secs: u64,
+    /// How many lines of output a report gets, 0 for all of them.
+    pub output_lines: usize,
+    /// How many jobs were pushed, names their output files.
+    pushed: u64,
+}
+
+impl Jobs {
+    pub fn new(max_running: usize, timeout_secs: u64, output_lines: usize) -> Self {
+        Self {
+            list: Vec::new(),
+            max_running,
+            timeout_secs,
+            output_lines,
+            pushed: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn running(&self) -> usize {
+        self.list
+            .iter()
+            .filter(|j| j.status == JobStatus::Running)
+            .count()
+    }
+
+    /// Queues the command of the todo with `id`.
+    pub fn push(&mut self, id: u64, label: String, cmd: String, report: Report) {
+        self.pushed += 1;
+        let output = report.captures().then(|| log_file(self.pushed));
+        let (output, status) = match output.transpose() {
+            Ok(output) => (output, JobStatus::Queued),
+            Err(e) => (
+                None,
+                JobStatus::Failed(format!("can't create its log: {}", e)),
+            ),
+        };
+        let finished = match status {
+            JobStatus::Queued => None,
+            _ => Some(Local::now()),
+        };
+        self.list.push(Job {
+            id,
+            label,
+            steps: steps(&cmd),
+            results: Vec::new(),
+            cmd,
+            status,
+            started: None,
+            finished,
+            report,
+            child: None,
+            output,
+            reported: false,
+        });
+        self.poll();
+    }
+
+    /// Reaps finished processes, kills the ones past the timeout, starts
+    /// queued jobs and drops jobs that finished a while ago.
+    pub fn poll(&mut self) {
+        let deadline = Local::now() - Duration::seconds(self.timeout_secs as i64);
+        for job in self.list.iter_mut() {
+            if let Some(child) = job.child.as_mut() {
+                match child.try_wait() {
+                    Ok(Some(status)) => job.step_finished(match status.code() {
+                        Some(code) => JobStatus::Exited(code),
+                        None => JobStatus::Killed,
+                    }),
+                    Ok(None)
+                        if self.timeout_secs != 0
+                            && job.started.is_some_and(|started| started < deadline) =>
+                    {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        job.step_finished(JobStatus::TimedOut);
+                    }
+                    Ok(None) => {}
+                    Err(e) => job.step_finished(JobStatus::Failed(e.to_string())),
+                }
+            }
+        }
+
+        let mut running = self.running();
+        for job in self.list.iter_mut() {
+            if self.max_running != 0 && running >= self.max_running {
+                break;
+            }
+            if job.status == JobStatus::Queued {
+                job.start_step();
+                if job.status == JobStatus::Running {
+                    running += 1;
+                }
+            }
+        }
+
+        let linger = Local::now() - Duration::seconds(JOB_LINGER_SECS);
+        self.list
+            .retain(|j| j.finished.map(|done| done > linger).unwrap_or(true));
+    }
+
+    /// The jobs that finished since the last call.
+    pub fn take_finished(&mut self) -> Vec<Finished> {
+        let lines = self.output_lines;
+        self.list
+            .iter_mut()
+            .filter(|j| j.finished.is_some() && !j.reported)
+            .map(|j| {
+                j.reported = true;
+                Finished {
+                    id: j.id,
+                    label: j.label.clone(),
+                    status: j.status.clone(),
+                    report: j.report,
+                    output: j
+                        .output
+                        .take()
+                        .map(|path| last_lines(&path, lines))
+                        .unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn kill_all(&mut self) {
+        for job in self.list.iter_mut() {
+            if let Some(child) = job.child.as_mut() {
+                let _ = child.kill();
+                let _ = child.wait();
+                job.finish(JobStatus::Killed);
+            } else if job.status == JobStatus::Queued {
+                job.finish(JobStatus::Killed);
+            }
+            if let Some(path) = job.output.take() {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// The last `count` lines in the file at `path`, which is removed, every
+/// line when `count` is 0.
+fn last_lines(path: &Path, count: usize) -> String {
+    let text = fs::read_to_string(path).unwrap_or_default();
+    let _ = fs::remove_file(path);
+    let lines = text.lines().collect::<Vec<_>>();
+    let skip = match count {
+        0 => 0,
+        count => lines.len().saturating_sub(count),
+    };
+    lines[skip..].join("\n")
+}
+
+/// Whether `cmd` may run without asking. Every command may when `allowlist`
+/// is empty, otherwise every step has to match an entry, see `matches`.
+pub fn is_allowed(allowlist: &[String], cmd: &str) -> bool {
+    allowlist.is_empty()
+        || steps(cmd)
+            .iter()
+            .all(|step| allowlist.iter().any(|entry| matches(entry, step)))
+}
+
+/// Whether `cmd` runs in the terminal pane instead of in the background.
+pub fn is_interactive(pty_cmds: &[String], cmd: &str) -> bool {
+    pty_cmds.iter().any(|entry| matches(entry, cmd.trim()))
+}
+
+/// An entry names the program of a command or, ending in `*`, is the start of
+/// it like `git pull*`.
+fn matches(entry: &str, cmd: &str) -> bool {
+    match entry.strip_suffix('*') {
+        Some(prefix) => cmd.starts_with(prefix),
+        None => cmd.split_whitespace().next() == Some(entry),
+    }
+}
+
+/// A new, empty file only the user can read for the output of the `n`th job,
+/// in the `jobs` directory next to the data base.
+fn log_file(n: u64) -> io::Result<PathBuf> {
+    let path = paths::private_dir("jobs")?.join(format!("{}_{}.log", std::process::id(), n));
+    // left over from an earlier `forget` that had the same pid
+    let _ = fs::remove_file(&path);
+    paths::create_private(&path)?;
+    Ok(path)
+}
+
+/// Starts `cmd`, printing to the end of the file at `output` if there is
+/// one, errors too when it says so.
+fn spawn(cmd: &str, output: Option<(&Path, bool)>) -> io::Result<Child> {
+    let cmd_args = cmd.split_whitespace().collect::<Vec<_>>();
+    if cmd_args.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty command"));
+    }
+    let (stdout, stderr) = match output {
+        Some((path, stderr)) => {
+            let file = OpenOptions::new().append(true).open(path)?;
+            let errors = if stderr {
+                Stdio::from(file.try_clone()?)
+            } else {
+                Stdio::null()
+            };
+            (Stdio::from(file), errors)
+        }
+        None => (Stdio::null(), Stdio::null()),
+    };
+    Command::new(cmd_args[0])
+        .args(&cmd_args[1..])
+        .stdin(Stdio::null())
+        .stdout(stdout)
+        .stderr(stderr)
+        .spawn()
+}
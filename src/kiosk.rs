@@ -0,0 +1,48 @@
+//! `forget --kiosk <seconds>` shows one sticky note full screen on a terminal
+//! nobody types into, a Pi on the wall. It reads the data base again every
+//! `<seconds>`, ignores every key but the exit key and runs no commands.
+
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+pub struct Kiosk {
+    /// The sticky note shown, the current one when `None`.
+    pub title: Option<String>,
+    pub every: Duration,
+    pub reloaded: Instant,
+}
+
+impl Kiosk {
+    pub fn new(every: Duration, title: Option<String>) -> Kiosk {
+        Kiosk {
+            title,
+            every,
+            reloaded: Instant::now(),
+        }
+    }
+
+    /// True once it's time to read the data base again.
+    pub fn is_due(&self) -> bool {
+        self.reloaded.elapsed() >= self.every
+    }
+
+    /// Whole seconds until the next reload, counted down in the corner.
+    pub fn left(&self) -> u64 {
+        let left = self.every.saturating_sub(self.reloaded.elapsed());
+        left.as_secs() + u64::from(left.subsec_nanos() > 0)
+    }
+
+    /// Reads `args` for `--kiosk <seconds>` and `--note <title>`.
+    pub fn from_args(args: &[String]) -> Option<Kiosk> {
+        let value = |name: &str| {
+            args.iter()
+                .position(|arg| arg == name)
+                .and_then(|i| args.get(i + 1))
+        };
+        let secs = value("--kiosk")?.parse::<u64>().ok()?;
+        Some(Kiosk::new(
+            Duration::from_secs(secs.max(1)),
+            value("--note").cloned(),
+        ))
+    }
+}
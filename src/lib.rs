@@ -0,0 +1,71 @@
+//! Everything `forget` is made of, shared by the `forget` and `forget-tray`
+//! binaries and the benchmarks.
+
+pub mod action;
+pub mod activity;
+pub mod agenda;
+pub mod anim;
+pub mod ansi;
+pub mod app;
+pub mod attach;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod calc;
+#[cfg(feature = "chat")]
+pub mod chat;
+pub mod checklist;
+pub mod clipboard;
+pub mod colors;
+pub mod config;
+pub mod crash;
+pub mod curl;
+pub mod dates;
+pub mod debug;
+pub mod deleted;
+pub mod doctor;
+pub mod draft;
+pub mod due;
+pub mod effort;
+pub mod event;
+pub mod feed;
+pub mod find;
+#[cfg(feature = "github")]
+pub mod github;
+pub mod habit;
+pub mod hints;
+pub mod holidays;
+pub mod hooks;
+pub mod jobs;
+pub mod kiosk;
+#[cfg(feature = "links")]
+pub mod links;
+pub mod logger;
+pub mod markdown;
+pub mod modal;
+pub mod notify;
+pub mod paths;
+pub mod pattern;
+pub mod pty;
+pub mod publish;
+pub mod query;
+pub mod quiet;
+pub mod recurring;
+pub mod remote;
+pub mod rules;
+pub mod secret;
+pub mod session;
+pub mod share;
+pub mod shell;
+pub mod spell;
+#[cfg(feature = "tray")]
+pub mod status;
+pub mod symbols;
+#[cfg(feature = "sync")]
+pub mod sync;
+pub mod theme;
+pub mod title;
+pub mod toast;
+pub mod ux;
+pub mod web_colors;
+pub mod widget;
+pub mod worker;
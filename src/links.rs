@@ -0,0 +1,64 @@
+//! Turns a todo that is only a link into a read later item titled with the
+//! page's `<title>`, fetched with `curl` by a background task. Only built
+//! with `--features links`.
+
+use std::io;
+
+use crate::curl;
+
+/// The title of the page a todo links to, or why it couldn't be fetched.
+pub struct Titled {
+    pub id: u64,
+    pub url: String,
+    pub title: io::Result<String>,
+}
+
+/// The link when the todo's text is only that.
+pub fn link(task: &str) -> Option<&str> {
+    let url = task.trim();
+    let is_link = (url.starts_with("http://") || url.starts_with("https://"))
+        && !url.contains(char::is_whitespace);
+    if is_link {
+        Some(url)
+    } else {
+        None
+    }
+}
+
+pub fn get_title(url: &str) -> io::Result<String> {
+    let config = format!(
+        "url = \"{}\"\nlocation\nmax-filesize = 5000000\n",
+        curl::quote(url)
+    );
+    title(&curl::run(&config)?)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "the page has no title"))
+}
+
+/// The text of the first `<title>` with runs of whitespace made one space.
+fn title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let text = html
+        .get(start..end)?
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = unescape(&text);
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
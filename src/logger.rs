@@ -0,0 +1,120 @@
+//! Logs to `~/.forget/forget.log`, the terminal is in raw mode so nothing can
+//! be printed while the app runs.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use chrono::Local;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::FormatTime;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// `FORGET_LOG` overrides the `log_level` from the config.
+pub const LEVEL_ENV: &str = "FORGET_LOG";
+
+/// How many warnings and errors the debug overlay keeps.
+const RECENT: usize = 5;
+
+static RECENT_ERRORS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Local time to the millisecond at the start of each line.
+struct LocalTime;
+
+impl FormatTime for LocalTime {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        write!(w, "{}", Local::now().format("%Y-%m-%d %H:%M:%S%.3f"))
+    }
+}
+
+/// Keeps the warnings and errors for the debug overlay.
+struct RecentErrors;
+
+impl<S: Subscriber> Layer<S> for RecentErrors {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let level = *event.metadata().level();
+        if level > Level::WARN {
+            return;
+        }
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        if let Ok(mut recent) = RECENT_ERRORS.lock() {
+            if recent.len() == RECENT {
+                recent.pop_front();
+            }
+            recent.push_back(format!("{} {}", level, message.0));
+        }
+    }
+}
+
+/// The `message` field of an event, what the macros were given to format.
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+/// Starts logging at `level`, or the level in `FORGET_LOG` when it is set.
+pub fn init(level: &str) -> io::Result<()> {
+    let level = std::env::var(LEVEL_ENV).unwrap_or_else(|_| level.to_string());
+    let level = LevelFilter::from_str(&level).unwrap_or(LevelFilter::WARN);
+
+    let mut path = dirs::home_dir().expect("home dir not found");
+    path.push(".forget");
+    fs::create_dir_all(&path)?;
+    path.push("forget.log");
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+
+    let lines = tracing_subscriber::fmt::layer()
+        .with_writer(Mutex::new(file))
+        .with_timer(LocalTime)
+        .with_ansi(false);
+    tracing_subscriber::registry()
+        .with(lines.with_filter(level))
+        .with(RecentErrors.with_filter(level))
+        .try_init()
+        .map_err(io::Error::other)
+}
+
+/// The last few warnings and errors, oldest first.
+pub fn recent_errors() -> Vec<String> {
+    RECENT_ERRORS
+        .lock()
+        .map(|recent| recent.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_keeps_only_the_last_warnings() {
+        let subscriber = tracing_subscriber::registry().with(RecentErrors);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("not kept");
+            for n in 0..=RECENT {
+                tracing::warn!("warning {}", n);
+            }
+            tracing::error!("broke");
+        });
+        let recent = recent_errors();
+        assert_eq!(recent.len(), RECENT);
+        assert_eq!(recent[0], "WARN warning 2");
+        assert_eq!(recent[RECENT - 1], "ERROR broke");
+    }
+}
@@ -1,67 +1,175 @@
 use std::io;
-use std::time::Duration;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
 
-use termion::event::Key;
 use termion::input::MouseTerminal;
-use termion::raw::IntoRawMode;
+use termion::raw::{IntoRawMode, RawTerminal};
+use termion::screen::AlternateScreen;
 use tui::backend::TermionBackend;
 use tui::Terminal;
 
-mod app;
-mod config;
-mod event;
-mod ux;
-mod widget;
+use forget::app::App;
+#[cfg(feature = "bench")]
+use forget::bench;
+use forget::event::{Config, Event, EventHandle};
+#[cfg(feature = "github")]
+use forget::github;
+use forget::kiosk::Kiosk;
+#[cfg(feature = "tray")]
+use forget::status;
+use forget::{ansi, config, crash, doctor, hooks, logger, paths, share, theme, ux};
 
-use app::App;
-use event::{Config, Event, EventHandle};
+/// How often ticks repaint in the low bandwidth mode, keys always do.
+const LOW_BANDWIDTH_REPAINT: Duration = Duration::from_secs(1);
 
 fn main() -> Result<(), failure::Error> {
-    let mut args = std::env::args();
-    let tick_rate = if let Some(tick) = args.find(|arg| arg.parse::<u64>().is_ok()) {
-        tick.parse()?
-    } else {
-        250
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    config::choose_db(&mut args)?;
+    if args.first().map(String::as_str) == Some("doctor") {
+        let fix = args.iter().any(|arg| arg == "--fix");
+        return Ok(doctor::run(fix)?);
+    }
+    match args.first().map(String::as_str) {
+        #[cfg(feature = "bench")]
+        Some("gen") => return Ok(bench::gen(&args[1..])?),
+        #[cfg(feature = "bench")]
+        Some("bench") => return Ok(bench::run(&args[1..])?),
+        Some("theme") => return Ok(theme::run(&args[1..])?),
+        Some("share") => return Ok(share::run(&args[1..])?),
+        Some("snapshot") => return Ok(ansi::run(&args[1..])?),
+        Some("hooks") => return Ok(hooks::run(&args[1..])?),
+        #[cfg(feature = "github")]
+        Some(command @ ("import" | "sync")) => return Ok(github::run(command, &args[1..])?),
+        _ => {}
+    }
+    #[cfg(feature = "tray")]
+    if args.first().map(String::as_str) == Some("status") {
+        let watch = args.iter().any(|arg| arg == "--watch");
+        return Ok(status::run(watch)?);
+    }
+
+    // the number after `--kiosk` is its seconds and one after `--note` a title
+    let tick_rate = match args.iter().enumerate().find(|(i, arg)| {
+        arg.parse::<u64>().is_ok()
+            && (*i == 0 || !["--kiosk", "--note"].contains(&args[i - 1].as_str()))
+    }) {
+        Some((_, tick)) => tick.parse()?,
+        None => 250,
     };
 
     let mut app = App::new().expect("error from `forget`");
+    if std::env::args().any(|arg| arg == "--low-bandwidth") {
+        app.low_bandwidth = true;
+    }
+    if args.iter().any(|arg| arg == "--kiosk") {
+        let kiosk = Kiosk::from_args(&args).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "usage: forget --kiosk <seconds> [--note <title>]",
+            )
+        })?;
+        app.start_kiosk(kiosk)?;
+    }
+    logger::init(&app.config.log_level)?;
+    tracing::info!("started with a {}ms tick", tick_rate);
 
     let events = EventHandle::with_config(Config {
         tick_rate: Duration::from_millis(tick_rate),
+        frame_rate: Duration::from_millis(app.config.frame_ms.max(1)),
         exit_key: termion::event::Key::Ctrl(app.config.exit_key_char_ctrl),
     });
+    app.workers.connect(events.task_sender());
 
-    let stdout = io::stdout().into_raw_mode()?;
-    let stdout = MouseTerminal::from(stdout);
-    let backend = TermionBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    crash::install();
+    let mut terminal = open_terminal()?;
 
     terminal.clear()?;
 
+    let mut ticked = false;
+    let mut painted = Instant::now();
     loop {
-        ux::draw(&mut terminal, &mut app)?;
+        if let Some(path) = app.external_edit.clone() {
+            // the editor gets the terminal back in its normal mode
+            events.pause();
+            drop(terminal);
+            let edited = run_editor(&app.config.editor, &path);
+            terminal = open_terminal()?;
+            terminal.clear()?;
+            events.resume();
+            app.finish_external_edit(edited);
+        }
+        if app.smart_stale {
+            app.refresh_smart_notes();
+        }
+        // a clock or countdown changing every tick is most of what a slow
+        // connection would carry
+        if !(ticked && app.low_bandwidth && painted.elapsed() < LOW_BANDWIDTH_REPAINT) {
+            painted = Instant::now();
+            ux::draw(&mut terminal, &mut app)?;
+            app.debug.record_frame(painted.elapsed());
+        }
+        app.debug.queue_depth = events.pending();
+        ticked = false;
         match events.next()? {
-            Event::Input(key) => match key {
-                Key::Char(c) => app.on_key(c),
-                Key::Up => app.on_up(),
-                Key::Down => app.on_down(),
-                Key::Left => app.on_left(),
-                Key::Right => app.on_right(),
-                Key::Esc => app.on_ctrl_key('q'),
-                Key::Backspace => app.on_backspace(),
-                Key::Delete => app.on_delete(),
-                Key::Ctrl(c) => app.on_ctrl_key(c),
-                _ => {}
-            },
+            Event::Input(key) if app.locked => app.on_lock_key(key),
+            Event::Input(key) => {
+                app.on_input();
+                app.on_key_press(key);
+            }
+            Event::Mouse(_) if app.locked || app.modal.is_some() || app.pty_focus => {}
+            Event::Mouse(mouse) => {
+                app.on_input();
+                app.on_mouse(mouse);
+            }
             Event::Tick => {
                 app.on_tick();
+                ticked = true;
             }
+            // a frame only moves the animations along, the model is as it was
+            Event::Frame => {}
+            Event::Task(task) => app.on_task(task),
         }
-        if app.should_quit {
-            terminal.clear()?;
+        events.animate(app.animating());
+        // a save still writing would be cut off half way
+        if app.should_quit && !app.saving() {
             break;
         }
     }
 
     Ok(())
 }
+
+type Term = Terminal<TermionBackend<AlternateScreen<MouseTerminal<RawTerminal<io::Stdout>>>>>;
+
+fn open_terminal() -> io::Result<Term> {
+    let stdout = io::stdout().into_raw_mode()?;
+    let stdout = MouseTerminal::from(stdout);
+    // leaving the alternate screen on drop gives back the user's scrollback
+    let stdout = AlternateScreen::from(stdout);
+    let backend = TermionBackend::new(stdout);
+    Terminal::new(backend)
+}
+
+/// Opens `path` in the `editor` from the config, or `$VISUAL` or `$EDITOR`,
+/// falling back to `vi`.
+fn run_editor(editor: &str, path: &Path) -> io::Result<()> {
+    let editor = match editor.trim() {
+        "" => std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".into()),
+        editor => paths::interpolate(editor),
+    };
+    let mut words = editor.split_whitespace();
+    let program = paths::tilde(words.next().unwrap_or("vi"));
+    let status = Command::new(&program).args(words).arg(path).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} exited with {}",
+            program.display(),
+            status
+        )))
+    }
+}
@@ -0,0 +1,122 @@
+//! Just enough Markdown for the notes: `**bold**`, `*italics*`, `` `code` ``,
+//! `# headings` and `- bullet` lists.
+
+/// A run of text with the same styling.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub code: bool,
+}
+
+const BULLET: &str = "• ";
+
+pub fn parse(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            push(&mut spans, "\n", Span::default());
+        }
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some(heading) = trimmed
+            .strip_prefix('#')
+            .map(|h| h.trim_start_matches('#'))
+            .and_then(|h| h.strip_prefix(' '))
+        {
+            let bold = Span {
+                bold: true,
+                ..Span::default()
+            };
+            parse_inline(&mut spans, heading, bold);
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| trimmed.strip_prefix(bullet))
+        {
+            push(&mut spans, indent, Span::default());
+            push(&mut spans, BULLET, Span::default());
+            parse_inline(&mut spans, item, Span::default());
+        } else {
+            parse_inline(&mut spans, line, Span::default());
+        }
+    }
+    spans
+}
+
+/// Splits `line` on the inline markers, a marker without a closing one is
+/// left as it is.
+fn parse_inline(spans: &mut Vec<Span>, line: &str, base: Span) {
+    let mut style = base;
+    let mut i = 0;
+    let mut plain = String::new();
+    while i < line.len() {
+        let rest = &line[i..];
+        // nothing but the closing backtick counts inside code
+        let markers: &[&str] = if style.code {
+            &["`"]
+        } else {
+            &["`", "**", "__", "*", "_"]
+        };
+        let marker = markers.iter().copied().find(|m| rest.starts_with(m));
+        let toggled = marker
+            .map(|m| toggles(line, i, m, is_open(&style, m)))
+            .unwrap_or(false);
+        if let (true, Some(m)) = (toggled, marker) {
+            push(spans, &plain, style.clone());
+            plain.clear();
+            match m {
+                "`" => style.code = !style.code,
+                "**" | "__" => style.bold = !style.bold,
+                _ => style.italic = !style.italic,
+            }
+            i += m.len();
+        } else {
+            let c = rest.chars().next().unwrap_or(' ');
+            plain.push(c);
+            i += c.len_utf8();
+        }
+    }
+    push(spans, &plain, style);
+}
+
+fn is_open(style: &Span, marker: &str) -> bool {
+    match marker {
+        "`" => style.code,
+        "**" | "__" => style.bold,
+        _ => style.italic,
+    }
+}
+
+/// A marker has to touch the text it wraps and an opening one needs a
+/// closing one later in the line, `_` only counts at the edge of a word so
+/// `snake_case` stays as it is.
+fn toggles(line: &str, i: usize, marker: &str, closing: bool) -> bool {
+    if marker == "`" {
+        return closing || line[i + 1..].contains('`');
+    }
+    let before = line[..i].chars().last();
+    let after = line[i + marker.len()..].chars().next();
+    let (inside, outside) = if closing {
+        (before, after)
+    } else {
+        (after, before)
+    };
+    if inside.map(char::is_whitespace).unwrap_or(true) {
+        return false;
+    }
+    if marker.starts_with('_') && outside.map(char::is_alphanumeric).unwrap_or(false) {
+        return false;
+    }
+    closing || line[i + marker.len()..].contains(marker)
+}
+
+fn push(spans: &mut Vec<Span>, text: &str, style: Span) {
+    if text.is_empty() {
+        return;
+    }
+    spans.push(Span {
+        text: text.to_string(),
+        ..style
+    });
+}
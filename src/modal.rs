@@ -0,0 +1,122 @@
+use termion::event::Key;
+
+/// What `App` does with the answer once a modal closes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModalAction {
+    /// Remove the sticky note at this index.
+    RemoveStickyNote(usize),
+    /// Use the picked symbol as the highlight symbol.
+    SetHighlight,
+    /// Give the sticky note at this index the picked icon, the first choice is none.
+    SetIcon(usize),
+    /// Run the first of the commands waiting on the allowlist.
+    RunCommand,
+    /// Attach the path typed to the sticky note at this index.
+    Attach(usize),
+    /// Open the picked attachment of the sticky note at this index.
+    OpenAttachment(usize),
+    /// Remove the picked attachment of the sticky note at this index.
+    RemoveAttachment(usize),
+    /// Pick up the draft left over from last time.
+    RestoreDraft,
+    /// Use the colors of the picked theme, the first choice is the default.
+    SetTheme,
+    /// Quit once it's confirmed.
+    Quit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModalResult {
+    /// The index of the choice picked.
+    Chosen(usize),
+    Cancelled,
+}
+
+/// A centered dialog that takes every key until it's answered.
+#[derive(Clone, Debug)]
+pub struct Modal {
+    pub title: String,
+    pub message: String,
+    pub choices: Vec<String>,
+    pub selected: usize,
+    /// What has been typed, only for a modal asking for text.
+    pub input: Option<String>,
+    pub action: ModalAction,
+}
+
+impl Modal {
+    /// A yes or no question, `Chosen(0)` means yes.
+    pub fn confirm<S: Into<String>>(title: S, message: S, action: ModalAction) -> Modal {
+        Modal {
+            title: title.into(),
+            message: message.into(),
+            choices: vec!["Yes".into(), "No".into()],
+            // saying no is the safe default
+            selected: 1,
+            input: None,
+            action,
+        }
+    }
+
+    /// Pick one of `choices`.
+    pub fn pick<S: Into<String>>(title: S, choices: Vec<String>, action: ModalAction) -> Modal {
+        Modal {
+            title: title.into(),
+            message: String::new(),
+            choices,
+            selected: 0,
+            input: None,
+            action,
+        }
+    }
+
+    /// Asks for a line of text, `Chosen(0)` once enter is pressed.
+    pub fn input<S: Into<String>>(title: S, message: S, action: ModalAction) -> Modal {
+        Modal {
+            title: title.into(),
+            message: message.into(),
+            choices: Vec::new(),
+            selected: 0,
+            input: Some(String::new()),
+            action,
+        }
+    }
+
+    fn is_confirm(&self) -> bool {
+        self.choices == ["Yes", "No"]
+    }
+
+    /// Handles a key, returns the result once the modal is done.
+    pub fn on_key(&mut self, key: Key) -> Option<ModalResult> {
+        if let Some(input) = self.input.as_mut() {
+            match key {
+                Key::Char('\n') => return Some(ModalResult::Chosen(0)),
+                Key::Esc => return Some(ModalResult::Cancelled),
+                Key::Char(c) => input.push(c),
+                Key::Backspace => {
+                    input.pop();
+                }
+                _ => {}
+            }
+            return None;
+        }
+        match key {
+            Key::Up | Key::Left | Key::BackTab => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            Key::Down | Key::Right | Key::Char('\t') if self.selected + 1 < self.choices.len() => {
+                self.selected += 1;
+            }
+            Key::Char('\n') => return Some(ModalResult::Chosen(self.selected)),
+            Key::Char('y') | Key::Char('Y') if self.is_confirm() => {
+                return Some(ModalResult::Chosen(0))
+            }
+            Key::Char('n') | Key::Char('N') if self.is_confirm() => {
+                return Some(ModalResult::Chosen(1))
+            }
+            Key::Esc => return Some(ModalResult::Cancelled),
+            _ => {}
+        }
+        None
+    }
+}
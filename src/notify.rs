@@ -0,0 +1,29 @@
+use std::io;
+use std::process::{Command, Stdio};
+
+/// Shows a desktop notification with `notify-send`, or `osascript` on macOS.
+pub fn desktop(summary: &str, body: &str) -> io::Result<()> {
+    let script = format!("display notification {:?} with title {:?}", body, summary);
+    let cmds: [(&str, Vec<&str>); 2] = [
+        ("notify-send", vec!["--app-name=forget", summary, body]),
+        ("osascript", vec!["-e", &script]),
+    ];
+    for (cmd, args) in cmds.iter() {
+        let status = Command::new(cmd)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        return match status {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => Err(e),
+            Ok(status) if status.success() => Ok(()),
+            Ok(_) => Err(io::Error::other(format!("{} failed", cmd))),
+        };
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no notification command found",
+    ))
+}
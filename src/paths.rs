@@ -0,0 +1,88 @@
+//! Paths and commands from the config can start with `~` and hold
+//! environment variables, `$HOME/notes` or `${XDG_DATA_HOME}/forget`.
+
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+/// `text` with `$VAR` and `${VAR}` replaced by their values, unset ones by
+/// nothing. `$$` is a `$`.
+pub fn interpolate(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(at) = rest.find('$') {
+        out.push_str(&rest[..at]);
+        let after = &rest[at + 1..];
+        let (name, next) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                // no closing brace, it's not a variable
+                None => {
+                    out.push('$');
+                    rest = after;
+                    continue;
+                }
+            }
+        } else if let Some(next) = after.strip_prefix('$') {
+            out.push('$');
+            rest = next;
+            continue;
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        };
+        if name.is_empty() {
+            out.push('$');
+        } else {
+            out.push_str(&env::var(name).unwrap_or_default());
+        }
+        rest = next;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `path` with its variables filled in and a leading `~` made the home dir.
+pub fn expand(path: &str) -> PathBuf {
+    tilde(&interpolate(path))
+}
+
+/// `path` with a leading `~` made the home dir.
+pub fn tilde(path: &str) -> PathBuf {
+    match (path, dirs::home_dir()) {
+        ("~", Some(home)) => home,
+        (path, Some(mut home)) if path.starts_with("~/") => {
+            home.push(&path[2..]);
+            home
+        }
+        (path, _) => PathBuf::from(path),
+    }
+}
+
+/// The directory `name` next to the data base, only the user can look in it.
+pub fn private_dir(name: &str) -> io::Result<PathBuf> {
+    let dir = config::db_path().with_file_name(name);
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&dir)?;
+    // it may have been there already with looser permissions
+    fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))?;
+    Ok(dir)
+}
+
+/// Creates `path` readable by the user alone, failing when anything, a link
+/// included, is already there.
+pub fn create_private(path: &Path) -> io::Result<File> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+}
@@ -0,0 +1,417 @@
+//! A small regular expression matcher for user supplied patterns.
+//!
+//! Supports literals, `.`, character classes (`[abc]`, `[a-z]`, `[^0-9]`),
+//! `\d`, `\w`, `\s` and escaped metacharacters, the quantifiers `*`, `+`
+//! and `?`, the anchors `^` and `$`, top level alternation with `|` and a
+//! leading `(?i)` for case insensitive matching. Groups are not supported.
+//!
+//! Case insensitive matching compares one char of the text at a time with
+//! its case folded, the text itself is never lowercased as a whole since
+//! that can change how many chars it has (`İ` lowercases to two) and so move
+//! the match away from where it is in the text.
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Atom {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Start,
+    End,
+}
+
+impl Atom {
+    fn matches(&self, c: char, ignore_case: bool) -> bool {
+        match self {
+            Atom::Char(a) if ignore_case => fold(*a) == fold(c),
+            Atom::Char(a) => *a == c,
+            Atom::Any => true,
+            Atom::Class { ranges, negated } => {
+                let within = |c: char| ranges.iter().any(|(lo, hi)| *lo <= c && c <= *hi);
+                let found = if ignore_case {
+                    within(c) || within(fold(c)) || within(upper(c))
+                } else {
+                    within(c)
+                };
+                found != *negated
+            }
+            Atom::Start | Atom::End => false,
+        }
+    }
+}
+
+/// `c` in lower case, the first char when that takes more than one.
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// `c` in upper case, or `c` when that takes more than one char.
+fn upper(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Piece {
+    atom: Atom,
+    min: usize,
+    max: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatternError(String);
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid pattern: {}", self.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    source: String,
+    alternatives: Vec<Vec<Piece>>,
+    ignore_case: bool,
+}
+
+impl Pattern {
+    pub fn new(source: &str) -> Result<Pattern, PatternError> {
+        let (ignore_case, rest) = match source.strip_prefix("(?i)") {
+            Some(rest) => (true, rest),
+            None => (false, source),
+        };
+
+        let alternatives = split_alternatives(rest)
+            .iter()
+            .map(|alt| parse(alt))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Pattern {
+            source: source.to_string(),
+            alternatives,
+            ignore_case,
+        })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// The char range of the first (leftmost) match in `text`.
+    pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+        self.find_from(text, 0)
+    }
+
+    /// Like `find` but the match starts at char index `start` or later.
+    pub fn find_from(&self, text: &str, start: usize) -> Option<(usize, usize)> {
+        let chars = text.chars().collect::<Vec<_>>();
+        // where a match already failed doesn't depend on where it began
+        let mut failed = self
+            .alternatives
+            .iter()
+            .map(|alt| vec![false; alt.len() * (chars.len() + 1)])
+            .collect::<Vec<_>>();
+        (start..=chars.len()).find_map(|begin| {
+            self.alternatives
+                .iter()
+                .zip(failed.iter_mut())
+                .filter_map(|(alt, failed)| {
+                    match_here(alt, 0, &chars, begin, failed, self.ignore_case)
+                        .map(|end| (begin, end))
+                })
+                .max_by_key(|(_, end)| *end)
+        })
+    }
+}
+
+/// Splits on `|` that are not escaped or inside a class.
+fn split_alternatives(src: &str) -> Vec<String> {
+    let mut alts = vec![String::new()];
+    let mut in_class = false;
+    let mut chars = src.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let last = alts.last_mut().unwrap();
+                last.push(c);
+                if let Some(next) = chars.next() {
+                    last.push(next);
+                }
+            }
+            '[' => {
+                in_class = true;
+                alts.last_mut().unwrap().push(c);
+            }
+            ']' => {
+                in_class = false;
+                alts.last_mut().unwrap().push(c);
+            }
+            '|' if !in_class => alts.push(String::new()),
+            _ => alts.last_mut().unwrap().push(c),
+        }
+    }
+    alts
+}
+
+fn escape_class(c: char) -> Option<Atom> {
+    let ranges = match c {
+        'd' => vec![('0', '9')],
+        'w' => vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+        's' => vec![(' ', ' '), ('\t', '\t'), ('\n', '\n'), ('\r', '\r')],
+        _ => return None,
+    };
+    Some(Atom::Class {
+        ranges,
+        negated: false,
+    })
+}
+
+fn parse(src: &str) -> Result<Vec<Piece>, PatternError> {
+    let chars = src.chars().collect::<Vec<_>>();
+    let mut pieces: Vec<Piece> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let atom = match chars[i] {
+            '.' => Atom::Any,
+            '^' => Atom::Start,
+            '$' => Atom::End,
+            '\\' => {
+                i += 1;
+                let c = *chars
+                    .get(i)
+                    .ok_or_else(|| PatternError("trailing `\\`".into()))?;
+                escape_class(c).unwrap_or(Atom::Char(c))
+            }
+            '[' => {
+                i += 1;
+                let negated = chars.get(i) == Some(&'^');
+                if negated {
+                    i += 1;
+                }
+                let mut ranges = Vec::new();
+                loop {
+                    let c = *chars
+                        .get(i)
+                        .ok_or_else(|| PatternError("unclosed `[`".into()))?;
+                    if c == ']' && !ranges.is_empty() {
+                        break;
+                    }
+                    let lo = if c == '\\' {
+                        i += 1;
+                        let c = *chars
+                            .get(i)
+                            .ok_or_else(|| PatternError("trailing `\\`".into()))?;
+                        if let Some(Atom::Class { ranges: class, .. }) = escape_class(c) {
+                            ranges.extend(class);
+                            i += 1;
+                            continue;
+                        }
+                        c
+                    } else {
+                        c
+                    };
+                    if chars.get(i + 1) == Some(&'-') && chars.get(i + 2).is_some_and(|c| *c != ']')
+                    {
+                        ranges.push((lo, chars[i + 2]));
+                        i += 3;
+                    } else {
+                        ranges.push((lo, lo));
+                        i += 1;
+                    }
+                }
+                Atom::Class { ranges, negated }
+            }
+            '*' | '+' | '?' => {
+                return Err(PatternError(format!("nothing to repeat at {}", i)));
+            }
+            '(' | ')' => return Err(PatternError("groups are not supported".into())),
+            c => Atom::Char(c),
+        };
+        i += 1;
+
+        let (min, max) = match chars.get(i) {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            _ => (1, Some(1)),
+        };
+        if (min, max) != (1, Some(1)) {
+            if let Atom::Start | Atom::End = atom {
+                return Err(PatternError("anchors can't be repeated".into()));
+            }
+            i += 1;
+        }
+        pieces.push(Piece { atom, min, max });
+    }
+    Ok(pieces)
+}
+
+/// Greedy backtracking match of `pieces[at..]` at `pos`, returns where the match ends.
+/// `failed` marks the pieces and positions already tried without a match so
+/// none is tried twice, patterns like `a*a*a*b` would otherwise take exponential time.
+fn match_here(
+    pieces: &[Piece],
+    at: usize,
+    text: &[char],
+    pos: usize,
+    failed: &mut [bool],
+    ignore_case: bool,
+) -> Option<usize> {
+    let piece = match pieces.get(at) {
+        Some(piece) => piece,
+        None => return Some(pos),
+    };
+    let state = at * (text.len() + 1) + pos;
+    if failed[state] {
+        return None;
+    }
+    let end = match piece.atom {
+        Atom::Start if pos == 0 => match_here(pieces, at + 1, text, pos, failed, ignore_case),
+        Atom::End if pos == text.len() => {
+            match_here(pieces, at + 1, text, pos, failed, ignore_case)
+        }
+        Atom::Start | Atom::End => None,
+        _ => match_repeated(piece, pieces, at, text, pos, failed, ignore_case),
+    };
+    if end.is_none() {
+        failed[state] = true;
+    }
+    end
+}
+
+/// Matches as many of `piece` as it can at `pos`, giving them back one at a
+/// time until the pieces after it match.
+fn match_repeated(
+    piece: &Piece,
+    pieces: &[Piece],
+    at: usize,
+    text: &[char],
+    pos: usize,
+    failed: &mut [bool],
+    ignore_case: bool,
+) -> Option<usize> {
+    let mut count = 0;
+    while piece.max.is_none_or(|max| count < max)
+        && pos + count < text.len()
+        && piece.atom.matches(text[pos + count], ignore_case)
+    {
+        count += 1;
+    }
+    while count >= piece.min {
+        if let Some(end) = match_here(pieces, at + 1, text, pos + count, failed, ignore_case) {
+            return Some(end);
+        }
+        if count == 0 {
+            break;
+        }
+        count -= 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(pattern: &str, text: &str) -> Option<(usize, usize)> {
+        Pattern::new(pattern).unwrap().find(text)
+    }
+
+    #[test]
+    fn anchors() {
+        assert_eq!(find("^call", "call mom"), Some((0, 4)));
+        assert_eq!(find("^call", "recall"), None);
+        assert_eq!(find("mom$", "call mom"), Some((5, 8)));
+        assert_eq!(find("mom$", "mom calls"), None);
+        assert_eq!(find("^$", ""), Some((0, 0)));
+        assert_eq!(find("^a$", "ab"), None);
+    }
+
+    #[test]
+    fn classes() {
+        assert_eq!(find("[abc]", "xxbx"), Some((2, 3)));
+        assert_eq!(find("[a-c]+", "zzcab!"), Some((2, 5)));
+        assert_eq!(find("[^0-9]", "12a3"), Some((2, 3)));
+        assert_eq!(find(r"\d+", "room 101"), Some((5, 8)));
+        assert_eq!(find(r"\w+", "  snake_case9 "), Some((2, 13)));
+        assert_eq!(find(r"a\sb", "a\tb"), Some((0, 3)));
+        assert_eq!(find(r"[\d.]+", "v1.2.3"), Some((1, 6)));
+        // a leading `]` and a trailing `-` are literals
+        assert_eq!(find("[]]", "a]"), Some((1, 2)));
+        assert_eq!(find("[a-]", "x-"), Some((1, 2)));
+        assert_eq!(find(".", "é"), Some((0, 1)));
+    }
+
+    #[test]
+    fn repetition() {
+        assert_eq!(find("ab*c", "ac"), Some((0, 2)));
+        assert_eq!(find("ab*c", "abbbc"), Some((0, 5)));
+        assert_eq!(find("ab+c", "ac"), None);
+        assert_eq!(find("ab+c", "abbc"), Some((0, 4)));
+        assert_eq!(find("colou?r", "color colour"), Some((0, 5)));
+        // greedy but gives back what the rest needs
+        assert_eq!(find("a.*b", "axbxb!"), Some((0, 5)));
+        assert_eq!(find("a+a", "aaa"), Some((0, 3)));
+    }
+
+    #[test]
+    fn alternation() {
+        let pattern = Pattern::new("bug|fix").unwrap();
+        assert!(pattern.is_match("a fix"));
+        assert!(pattern.is_match("bug report"));
+        assert!(!pattern.is_match("feature"));
+        // the leftmost match wins, then the longest at that spot
+        assert_eq!(find("b|ab", "xab"), Some((1, 3)));
+        assert_eq!(find("[|]", "a|b"), Some((1, 2)));
+        assert_eq!(find(r"a\|b", "a|b"), Some((0, 3)));
+        assert_eq!(find("^x|y$", "ay"), Some((1, 2)));
+    }
+
+    #[test]
+    fn escapes_and_case() {
+        assert_eq!(find(r"1\.5", "105 1.5"), Some((4, 7)));
+        assert_eq!(find(r"\*", "a*b"), Some((1, 2)));
+        assert_eq!(find(r"\$\d", "costs $5"), Some((6, 8)));
+        assert_eq!(find(r"\\", r"a\b"), Some((1, 2)));
+        assert_eq!(find("(?i)urgent", "URGENT fix"), Some((0, 6)));
+        assert_eq!(find("urgent", "URGENT fix"), None);
+        assert_eq!(find("(?i)[a-z]+", "123 ABC"), Some((4, 7)));
+        assert_eq!(find("(?i)[^A-Z]", "aB1"), Some((2, 3)));
+        let pattern = Pattern::new("b").unwrap();
+        assert_eq!(pattern.find_from("abab", 2), Some((3, 4)));
+    }
+
+    #[test]
+    fn ignoring_case_keeps_char_positions() {
+        // `İ` is one char but lowercases to two
+        assert_eq!(find("(?i)stanbul", "İstanbul"), Some((1, 8)));
+        assert_eq!(find("(?i)urgent", "İ URGENT"), Some((2, 8)));
+        assert_eq!(find("(?i)istanbul", "İSTANBUL"), Some((0, 8)));
+        assert_eq!(find("(?i)İ", "xi"), Some((1, 2)));
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        for bad in ["*a", "a|+b", "[abc", r"a\", r"[a\", "(a)", "a)", "^*", "$?"] {
+            assert!(Pattern::new(bad).is_err(), "{} should be invalid", bad);
+        }
+        let err = Pattern::new("[ab").unwrap_err();
+        assert_eq!(err.to_string(), "invalid pattern: unclosed `[`");
+    }
+
+    #[test]
+    fn backtracking_is_bounded() {
+        let text = "a".repeat(200);
+        let started = std::time::Instant::now();
+        assert_eq!(find("a*a*a*a*a*a*a*a*a*a*b", &text), None);
+        assert_eq!(find("(?i)a*a*a*a*a*a*a*a*a*a*$", &text), Some((0, 200)));
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+}
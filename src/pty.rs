@@ -0,0 +1,287 @@
+//! Runs an interactive command like `ssh host` in a pseudo terminal shown in
+//! a pane, keys typed while the pane has focus go to the command.
+//!
+//! The pane is a dumb terminal: carriage returns, backspaces and tabs move
+//! the cursor, other escape sequences are dropped, so full screen programs
+//! don't draw properly but prompts and shells work.
+//!
+//! This calls `openpty` through `libc` rather than using `portable-pty`:
+//! `forget` only runs on Unix because of termion, `libc` is already a
+//! dependency and the pane needs nothing past opening, resizing and reading
+//! one pty, which isn't worth portable-pty's Windows support and extra crates.
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use termion::event::Key;
+
+/// Output lines kept, older ones are dropped.
+const MAX_LINES: usize = 1000;
+
+#[derive(Debug)]
+pub struct Pty {
+    pub cmd: String,
+    pub screen: Screen,
+    pub exited: Option<ExitStatus>,
+    /// Columns and rows the command was last told about.
+    pub size: (u16, u16),
+    master: File,
+    child: Child,
+    output: Receiver<Vec<u8>>,
+}
+
+impl Pty {
+    /// Starts `cmd`, split on whitespace like other commands, in a new
+    /// pseudo terminal of `cols` by `rows`.
+    pub fn spawn(cmd: &str, cols: u16, rows: u16) -> io::Result<Pty> {
+        let words = cmd.split_whitespace().collect::<Vec<_>>();
+        if words.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty command"));
+        }
+
+        let (mut master, mut slave) = (0, 0);
+        let size = winsize(cols, rows);
+        let res = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                &size,
+            )
+        };
+        if res != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let master = unsafe { File::from_raw_fd(master) };
+        let slave = unsafe { File::from_raw_fd(slave) };
+        // openpty can't open them close on exec, without it every command
+        // started later, in a pane or not, keeps this terminal open
+        close_on_exec(&master)?;
+        close_on_exec(&slave)?;
+
+        let mut command = Command::new(words[0]);
+        command
+            .args(&words[1..])
+            .env("TERM", "dumb")
+            .stdin(Stdio::from(slave.try_clone()?))
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave));
+        // the command gets its own session with the pty as its terminal, so
+        // ctrl-c and job control reach it and not `forget`
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let child = command.spawn()?;
+
+        let mut reader = master.try_clone()?;
+        let (tx, output) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buf = [0; 4096];
+            // reading fails once the command exits and the pty closes
+            while let Ok(n) = reader.read(&mut buf) {
+                if n == 0 || tx.send(buf[..n].to_vec()).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Pty {
+            cmd: cmd.to_string(),
+            screen: Screen::default(),
+            exited: None,
+            size: (cols, rows),
+            master,
+            child,
+            output,
+        })
+    }
+
+    /// Takes the output so far and notices when the command exits.
+    pub fn poll(&mut self) {
+        while let Ok(bytes) = self.output.try_recv() {
+            self.screen.feed(&bytes);
+        }
+        if self.exited.is_none() {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                self.exited = Some(status);
+            }
+        }
+    }
+
+    /// Sends a key to the command the way a terminal would.
+    pub fn send_key(&mut self, key: Key) -> io::Result<()> {
+        if self.exited.is_some() {
+            return Ok(());
+        }
+        let bytes = match key {
+            Key::Char('\n') => b"\r".to_vec(),
+            Key::Char(c) => c.to_string().into_bytes(),
+            Key::Alt(c) => format!("\x1b{}", c).into_bytes(),
+            Key::Ctrl(c) => vec![(c as u8) & 0x1f],
+            Key::Backspace => vec![0x7f],
+            Key::Esc => vec![0x1b],
+            Key::Up => b"\x1b[A".to_vec(),
+            Key::Down => b"\x1b[B".to_vec(),
+            Key::Right => b"\x1b[C".to_vec(),
+            Key::Left => b"\x1b[D".to_vec(),
+            Key::Home => b"\x1b[H".to_vec(),
+            Key::End => b"\x1b[F".to_vec(),
+            Key::Delete => b"\x1b[3~".to_vec(),
+            Key::PageUp => b"\x1b[5~".to_vec(),
+            Key::PageDown => b"\x1b[6~".to_vec(),
+            Key::BackTab => b"\x1b[Z".to_vec(),
+            _ => return Ok(()),
+        };
+        self.master.write_all(&bytes)
+    }
+
+    /// Tells the command the pane changed size.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        if self.size == (cols, rows) {
+            return;
+        }
+        self.size = (cols, rows);
+        let size = winsize(cols, rows);
+        unsafe {
+            use std::os::unix::io::AsRawFd;
+            libc::ioctl(self.master.as_raw_fd(), libc::TIOCSWINSZ, &size);
+        }
+    }
+
+    pub fn kill(&mut self) {
+        if self.exited.is_none() {
+            let _ = self.child.kill();
+            self.exited = self.child.wait().ok();
+        }
+    }
+}
+
+impl Drop for Pty {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+fn winsize(cols: u16, rows: u16) -> libc::winsize {
+    libc::winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
+
+fn close_on_exec(file: &File) -> io::Result<()> {
+    let fd = file.as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    if flags == -1 || unsafe { libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// The lines a dumb terminal would show.
+#[derive(Clone, Debug)]
+pub struct Screen {
+    pub lines: Vec<String>,
+    /// Column of the cursor in the last line, in chars.
+    col: usize,
+    /// A UTF-8 char cut off at the end of the last read.
+    partial: Vec<u8>,
+}
+
+impl Default for Screen {
+    fn default() -> Self {
+        Screen {
+            lines: vec![String::new()],
+            col: 0,
+            partial: Vec::new(),
+        }
+    }
+}
+
+impl Screen {
+    pub fn feed(&mut self, bytes: &[u8]) {
+        let mut data = std::mem::take(&mut self.partial);
+        data.extend_from_slice(bytes);
+        let text = match std::str::from_utf8(&data) {
+            Ok(text) => text.to_string(),
+            Err(e) if e.error_len().is_none() => {
+                // keep the start of a char split between reads for next time
+                let (text, rest) = data.split_at(e.valid_up_to());
+                self.partial = rest.to_vec();
+                String::from_utf8_lossy(text).into_owned()
+            }
+            Err(_) => String::from_utf8_lossy(&data).into_owned(),
+        };
+
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' => match chars.next() {
+                    // CSI, up to a final byte in @..~
+                    Some('[') => {
+                        for c in chars.by_ref() {
+                            if ('@'..='~').contains(&c) {
+                                break;
+                            }
+                        }
+                    }
+                    // OSC, up to BEL or ESC \
+                    Some(']') => {
+                        while let Some(c) = chars.next() {
+                            if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                                break;
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                '\r' => self.col = 0,
+                '\n' => self.newline(),
+                '\x08' => self.col = self.col.saturating_sub(1),
+                '\t' => self.col = (self.col / 8 + 1) * 8,
+                c if c.is_control() => {}
+                c => self.put(c),
+            }
+        }
+    }
+
+    fn newline(&mut self) {
+        self.lines.push(String::new());
+        self.col = 0;
+        if self.lines.len() > MAX_LINES {
+            self.lines.remove(0);
+        }
+    }
+
+    /// Writes over the char under the cursor, or adds one at the end.
+    fn put(&mut self, c: char) {
+        let line = self.lines.last_mut().expect("a screen always has a line");
+        let len = line.chars().count();
+        if self.col < len {
+            let start = line
+                .char_indices()
+                .nth(self.col)
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            let end = start + line[start..].chars().next().map_or(0, char::len_utf8);
+            line.replace_range(start..end, c.encode_utf8(&mut [0; 4]));
+        } else {
+            line.extend(std::iter::repeat_n(' ', self.col - len));
+            line.push(c);
+        }
+        self.col += 1;
+    }
+}
@@ -0,0 +1,96 @@
+//! Uploads a sticky note as Markdown so it can be shared with a link.
+
+use std::env;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::app::Remind;
+use crate::checklist;
+use crate::curl;
+use crate::dates::Dates;
+
+const GIST_API: &str = "https://api.github.com/gists";
+
+/// Where `publish` uploads to.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Publish {
+    /// A GitHub gist made with the token in the environment variable `token_env`.
+    Gist {
+        token_env: String,
+        #[serde(default)]
+        public: bool,
+    },
+    /// Posts the Markdown as the body, the response is the link, like `https://paste.rs`.
+    Post { url: String },
+}
+
+/// The sticky note as it's published, without the ids `checklist::dump` keeps.
+pub fn markdown(remind: &Remind, dates: &Dates) -> String {
+    let mut text = format!("# {}\n\n", remind.title);
+    if let Some(updated) = remind.updated_at {
+        text.push_str(&format!("_Updated {}_\n\n", dates.timestamp(updated)));
+    }
+    if !remind.note.trim().is_empty() {
+        text.push_str(remind.note.trim());
+        text.push_str("\n\n");
+    }
+    for todo in remind.list.iter() {
+        text.push_str(&format!(
+            "- [{}] {}\n",
+            checklist::marker(todo.status),
+            todo.task
+        ));
+    }
+    text
+}
+
+/// Uploads the Markdown `text` of the sticky note titled `title`, returns its link.
+pub fn upload(to: &Publish, title: &str, text: &str) -> io::Result<String> {
+    match to {
+        Publish::Gist { token_env, public } => {
+            let token = env::var(token_env).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("${} is not set", token_env),
+                )
+            })?;
+            let body = json!({
+                "description": title,
+                "public": public,
+                "files": { format!("{}.md", title): { "content": text } },
+            });
+            let config = format!(
+                "url = \"{}\"\nheader = \"Authorization: token {}\"\nheader = \"Accept: application/vnd.github+json\"\ndata-binary = \"{}\"\n",
+                GIST_API,
+                curl::quote(&token),
+                curl::quote(&body.to_string())
+            );
+            let response = curl::run(&config)?;
+            let response = serde_json::from_str::<serde_json::Value>(&response)?;
+            response["html_url"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "no link in the response")
+                })
+        }
+        Publish::Post { url } => {
+            let config = format!(
+                "url = \"{}\"\ndata-binary = \"{}\"\n",
+                curl::quote(url),
+                curl::quote(text)
+            );
+            let response = curl::run(&config)?;
+            response
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "no link in the response")
+                })
+        }
+    }
+}
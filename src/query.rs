@@ -0,0 +1,192 @@
+use crate::app::{Remind, Status, Todo};
+use crate::pattern::Pattern;
+
+/// One whitespace separated part of a smart note query.
+#[derive(Clone, Debug)]
+enum Term {
+    /// `#work` matches todos tagged `#work`.
+    Tag(String),
+    /// `is:open` matches todos that aren't done or cancelled.
+    Open,
+    /// `is:done`, `is:doing`, `is:waiting` or `is:cancelled`.
+    Status(Status),
+    /// `note:groceries` matches todos of sticky notes whose title contains it.
+    Note(String),
+    /// `has:cmd` matches todos that run a command.
+    HasCmd,
+    /// `/^call/` matches the task against a pattern.
+    Pattern(Pattern),
+    /// Anything else is looked for in the task, ignoring case.
+    Text(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct Query {
+    /// Every term has to match, negated terms must not match.
+    terms: Vec<(bool, Term)>,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Query {
+        let terms = query
+            .split_whitespace()
+            .map(|word| match word.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (true, Term::parse(rest)),
+                _ => (false, Term::parse(word)),
+            })
+            .collect();
+        Query { terms }
+    }
+
+    pub fn matches(&self, remind: &Remind, todo: &Todo) -> bool {
+        self.terms
+            .iter()
+            .all(|(negated, term)| term.matches(remind, todo) != *negated)
+    }
+}
+
+impl Term {
+    fn parse(word: &str) -> Term {
+        let lower = word.to_lowercase();
+        if word.len() > 1 && word.starts_with('#') {
+            Term::Tag(lower)
+        } else if lower == "is:open" {
+            Term::Open
+        } else if let Some(status) = lower.strip_prefix("is:").and_then(parse_status) {
+            Term::Status(status)
+        } else if lower == "has:cmd" {
+            Term::HasCmd
+        } else if let Some(note) = lower.strip_prefix("note:") {
+            Term::Note(note.to_string())
+        } else if word.len() > 2 && word.starts_with('/') && word.ends_with('/') {
+            match Pattern::new(&word[1..word.len() - 1]) {
+                Ok(pat) => Term::Pattern(pat),
+                Err(_) => Term::Text(lower),
+            }
+        } else {
+            Term::Text(lower)
+        }
+    }
+
+    fn matches(&self, remind: &Remind, todo: &Todo) -> bool {
+        match self {
+            Term::Tag(tag) => tags(&todo.task).any(|t| &t == tag),
+            Term::Open => !todo.status.is_closed(),
+            Term::Status(status) => todo.status == *status,
+            Term::Note(title) => remind.title.to_lowercase().contains(title),
+            Term::HasCmd => remind.command_for(todo).is_some(),
+            Term::Pattern(pat) => pat.is_match(&todo.task),
+            Term::Text(text) => todo.task.to_lowercase().contains(text),
+        }
+    }
+}
+
+fn parse_status(name: &str) -> Option<Status> {
+    match name {
+        "doing" | "inprogress" | "in-progress" => Some(Status::InProgress),
+        "waiting" => Some(Status::Waiting),
+        "done" => Some(Status::Done),
+        "cancelled" | "canceled" => Some(Status::Cancelled),
+        _ => None,
+    }
+}
+
+/// The `#tags` of a task, lower cased.
+pub fn tags(task: &str) -> impl Iterator<Item = String> + '_ {
+    task.split_whitespace()
+        .filter(|w| w.len() > 1 && w.starts_with('#'))
+        .map(|w| {
+            w.trim_end_matches(|c: char| c.is_ascii_punctuation())
+                .to_lowercase()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remind(title: &str) -> Remind {
+        Remind {
+            title: title.to_string(),
+            ..Remind::default()
+        }
+    }
+
+    fn todo(task: &str, status: Status) -> Todo {
+        Todo {
+            status,
+            ..Todo::new(1, task.to_string())
+        }
+    }
+
+    fn matches(query: &str, task: &str) -> bool {
+        Query::parse(query).matches(&remind("Work"), &todo(task, Status::Open))
+    }
+
+    #[test]
+    fn every_term_has_to_match() {
+        assert!(matches("call MOM", "Call mom about Sunday"));
+        assert!(!matches("call dad", "Call mom about Sunday"));
+        assert!(matches("#work", "finish the report #Work."));
+        assert!(!matches("#work", "finish the #workshop report"));
+        assert!(matches("", "anything"));
+    }
+
+    #[test]
+    fn negated_terms_must_not_match() {
+        assert!(matches("milk -oat", "buy milk"));
+        assert!(!matches("milk -oat", "buy oat milk"));
+        assert!(!matches("-#work", "report #work"));
+        assert!(matches("-is:done", "report"));
+        // a lone `-` is looked for as text
+        assert!(matches("-", "pros - cons"));
+        assert!(!matches("-", "pros and cons"));
+    }
+
+    #[test]
+    fn is_terms() {
+        let work = remind("Work");
+        let is = |query: &str, status| Query::parse(query).matches(&work, &todo("report", status));
+        assert!(is("is:open", Status::Open));
+        assert!(is("is:open", Status::Waiting));
+        assert!(!is("is:open", Status::Done));
+        assert!(!is("is:open", Status::Cancelled));
+        assert!(is("is:doing", Status::InProgress));
+        assert!(is("IS:In-Progress", Status::InProgress));
+        assert!(is("is:canceled", Status::Cancelled));
+        assert!(!is("is:done", Status::Waiting));
+        // an unknown status is just text
+        assert!(!is("is:later", Status::Open));
+        assert!(Query::parse("is:later").matches(&work, &todo("ask is:later", Status::Open)));
+    }
+
+    #[test]
+    fn note_terms() {
+        let query = Query::parse("note:groc milk");
+        assert!(query.matches(&remind("Groceries"), &todo("milk", Status::Open)));
+        assert!(!query.matches(&remind("Work"), &todo("milk", Status::Open)));
+        assert!(Query::parse("-note:work").matches(&remind("Home"), &todo("milk", Status::Open)));
+    }
+
+    #[test]
+    fn has_cmd() {
+        let query = Query::parse("has:cmd");
+        let mut deploy = todo("deploy", Status::Open);
+        assert!(!query.matches(&remind("Work"), &deploy));
+        deploy.cmd = "make deploy".into();
+        assert!(query.matches(&remind("Work"), &deploy));
+    }
+
+    #[test]
+    fn pattern_terms() {
+        assert!(matches("/^call/", "call mom"));
+        assert!(!matches("/^call/", "recall the order"));
+        assert!(matches("-/^call/", "recall the order"));
+        assert!(matches("/mom$/", "call mom"));
+        // a pattern that doesn't compile is looked for as text
+        assert!(matches("/(a)/", "see /(a)/ above"));
+        assert!(!matches("/(a)/", "a"));
+        // too short to be a pattern
+        assert!(matches("//", "http://example.com"));
+    }
+}
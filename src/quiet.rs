@@ -0,0 +1,71 @@
+use std::io;
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+use crate::toast::Level;
+
+/// When background notifications are held back, `start` and `end` are
+/// `HH:MM` and may wrap past midnight, like `22:00` to `08:00`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+/// The parsed `quiet_hours` and the notifications held back during them.
+#[derive(Clone, Debug, Default)]
+pub struct Quiet {
+    hours: Option<(NaiveTime, NaiveTime)>,
+    held: Vec<Level>,
+}
+
+impl Quiet {
+    pub fn new(hours: Option<&QuietHours>) -> io::Result<Self> {
+        let parse = |time: &str| {
+            NaiveTime::parse_from_str(time, "%H:%M").map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("quiet hours `{}`: {}", time, e),
+                )
+            })
+        };
+        let hours = match hours {
+            Some(hours) => Some((parse(&hours.start)?, parse(&hours.end)?)),
+            None => None,
+        };
+        Ok(Self {
+            hours,
+            held: Vec::new(),
+        })
+    }
+
+    pub fn is_quiet(&self, now: NaiveTime) -> bool {
+        match self.hours {
+            Some((start, end)) if start <= end => start <= now && now < end,
+            Some((start, end)) => now >= start || now < end,
+            None => false,
+        }
+    }
+
+    pub fn hold(&mut self, level: Level) {
+        self.held.push(level);
+    }
+
+    /// Once quiet hours are over, what happened during them in one line.
+    pub fn summary(&mut self, now: NaiveTime) -> Option<String> {
+        if self.held.is_empty() || self.is_quiet(now) {
+            return None;
+        }
+        let failed = self.held.iter().filter(|l| **l == Level::Error).count();
+        let finished = self.held.len() - failed;
+        self.held.clear();
+        Some(match failed {
+            0 => format!("While it was quiet {} commands finished", finished),
+            _ => format!(
+                "While it was quiet {} commands finished and {} failed",
+                finished, failed
+            ),
+        })
+    }
+}
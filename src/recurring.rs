@@ -0,0 +1,132 @@
+use std::io;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{ListState, Remind, Todo};
+use crate::holidays::Holidays;
+
+/// A sticky note made fresh from a template on a schedule, like a "Daily"
+/// note every weekday at 9:00.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Recurring {
+    /// Ties the made sticky notes to this template.
+    pub name: String,
+    /// `{date}` is replaced with the day the note is made for.
+    pub title: String,
+    #[serde(default)]
+    pub note: String,
+    #[serde(default)]
+    pub todos: Vec<String>,
+    /// `Mon` to `Sun`, empty means every day.
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// `HH:MM`
+    pub at: String,
+    /// What happens when the schedule falls on a weekend or holiday.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days_off: Option<DaysOff>,
+    /// What happens to the unfinished todos of the note made before.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub carry_over: Option<CarryOver>,
+    /// Move the previous note to the archive when a new one is made.
+    #[serde(default = "default_archive")]
+    pub archive: bool,
+}
+
+/// How unfinished todos get to the new note.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CarryOver {
+    /// Take them out of the old note.
+    Move,
+    /// Leave them in the old note too.
+    Copy,
+}
+
+/// Work notes that shouldn't be made on a Saturday.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DaysOff {
+    /// Make no note that day.
+    Skip,
+    /// Make the note on the next business day instead.
+    NextBusinessDay,
+}
+
+fn default_archive() -> bool {
+    true
+}
+
+/// Which template a sticky note was made from and when.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Instance {
+    pub name: String,
+    /// Unix time of the schedule the note was made for.
+    pub due: i64,
+}
+
+impl Recurring {
+    /// Checks the time and days parse, so mistakes show up on start.
+    pub fn validate(&self) -> io::Result<()> {
+        self.time()?;
+        self.weekdays()?;
+        Ok(())
+    }
+
+    fn time(&self) -> io::Result<NaiveTime> {
+        NaiveTime::parse_from_str(&self.at, "%H:%M").map_err(|e| self.error(&self.at, e))
+    }
+
+    fn weekdays(&self) -> io::Result<Vec<Weekday>> {
+        self.days
+            .iter()
+            .map(|day| Weekday::from_str(day).map_err(|_| self.error(day, "not a day")))
+            .collect()
+    }
+
+    fn error<E: std::fmt::Display>(&self, value: &str, e: E) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("recurring note `{}` `{}`: {}", self.name, value, e),
+        )
+    }
+
+    /// The latest time on the schedule that isn't after `now`.
+    pub fn last_due(&self, now: DateTime<Local>, holidays: &Holidays) -> Option<DateTime<Local>> {
+        let time = self.time().ok()?;
+        let days = self.weekdays().ok()?;
+        // far enough back to get past a week of holidays
+        (0..22)
+            .map(|back| now.naive_local().date() - Duration::days(back))
+            .filter(|date| days.is_empty() || days.contains(&date.weekday()))
+            .filter_map(|date| match self.days_off {
+                Some(DaysOff::Skip) if !holidays.is_business_day(date) => None,
+                Some(DaysOff::NextBusinessDay) => Some(holidays.next_business_day(date)),
+                _ => Some(date),
+            })
+            .filter_map(|date| Local.from_local_datetime(&date.and_time(time)).earliest())
+            .filter(|due| *due <= now)
+            .max()
+    }
+
+    /// A new sticky note for the schedule at `due`, the todos get ids once added.
+    pub fn make(&self, due: DateTime<Local>) -> Remind {
+        let todos = self
+            .todos
+            .iter()
+            .map(|task| Todo::new(0, task.clone()))
+            .collect();
+        Remind {
+            note: self.note.clone(),
+            list: ListState::new(todos),
+            instance: Some(Instance {
+                name: self.name.clone(),
+                due: due.timestamp(),
+            }),
+            ..Remind::new(
+                self.title
+                    .replace("{date}", &due.format("%Y-%m-%d").to_string()),
+            )
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Read only sticky notes subscribed to from a URL, fetched with `curl` by a
+//! background task so a slow server never holds up drawing.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Remind, Todo};
+use crate::checklist;
+use crate::curl;
+use crate::worker::Progress;
+
+/// How long `curl` gets before the fetch counts as failed.
+const FETCH_TIMEOUT_SECS: &str = "20";
+
+/// A sticky note published somewhere else, as the JSON of a `forget` sticky
+/// note or a Markdown checklist.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RemoteNote {
+    pub title: String,
+    pub url: String,
+    /// Fetch it again this often, 0 only fetches on start and when asked.
+    #[serde(default)]
+    pub refresh_minutes: u64,
+}
+
+/// The note and todos of a finished fetch, or why it failed.
+pub struct Fetched {
+    pub url: String,
+    pub parsed: io::Result<(String, Vec<Todo>)>,
+}
+
+/// Which URLs are being fetched and when they last were.
+#[derive(Debug, Default)]
+pub struct Fetcher {
+    pending: HashSet<String>,
+    /// When each URL was last asked for.
+    last: HashMap<String, Instant>,
+}
+
+impl Fetcher {
+    /// Notes `url` is being fetched, false if it already is.
+    pub fn start(&mut self, url: &str) -> bool {
+        if !self.pending.insert(url.to_string()) {
+            return false;
+        }
+        self.last.insert(url.to_string(), Instant::now());
+        true
+    }
+
+    /// True when `url` hasn't been asked for in the last `minutes`.
+    pub fn is_stale(&self, url: &str, minutes: u64) -> bool {
+        self.last
+            .get(url)
+            .is_none_or(|last| last.elapsed() >= Duration::from_secs(minutes * 60))
+    }
+
+    pub fn finished(&mut self, url: &str) {
+        self.pending.remove(url);
+    }
+}
+
+/// Downloads and parses `url`, halfway once it's downloaded.
+pub fn fetch(url: &str, progress: &Progress) -> Fetched {
+    let body = get(url);
+    progress.report(50);
+    Fetched {
+        url: url.to_string(),
+        parsed: body.and_then(|body| parse(&body)),
+    }
+}
+
+/// The body at `url`, following redirects.
+pub fn get(url: &str) -> io::Result<String> {
+    curl::run(&format!(
+        "url = \"{}\"\nlocation\nmax-time = {}\n",
+        curl::quote(url),
+        FETCH_TIMEOUT_SECS
+    ))
+}
+
+/// The note and todos of a fetched body, JSON when it looks like JSON and a
+/// Markdown checklist otherwise.
+pub fn parse(body: &str) -> io::Result<(String, Vec<Todo>)> {
+    if body.trim_start().starts_with('{') {
+        let remind = serde_json::from_str::<Remind>(body)?;
+        if remind.secret.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "secret sticky notes can't be shared",
+            ));
+        }
+        return Ok((remind.note, remind.list.items));
+    }
+    // only the list items, headings and other text aren't todos
+    let items = body
+        .lines()
+        .map(str::trim)
+        .filter_map(|l| l.strip_prefix("- ").or_else(|| l.strip_prefix("* ")))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let todos = checklist::parse(&items)
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let mut todo = Todo::new(line.id.unwrap_or(i as u64 + 1), line.task);
+            todo.set_status(line.status);
+            todo
+        })
+        .collect();
+    Ok((String::new(), todos))
+}
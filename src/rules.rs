@@ -0,0 +1,42 @@
+use tui::style::Style;
+
+use crate::app::Todo;
+use crate::config::ColorRule;
+use crate::pattern::Pattern;
+
+/// The compiled `color_rules` from the config, the first rule whose pattern
+/// matches a todo decides its style.
+#[derive(Clone, Debug, Default)]
+pub struct ColorRules {
+    rules: Vec<(Pattern, Option<u64>, Style)>,
+}
+
+impl ColorRules {
+    /// Compiles `rules`, a rule with an invalid pattern is skipped and
+    /// described in the messages returned with them.
+    pub fn new(rules: &[ColorRule]) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match Pattern::new(&rule.pattern) {
+                Ok(pat) => Some((pat, rule.max_runs, rule.style.clone().into())),
+                Err(e) => {
+                    errors.push(format!("Color rule `{}` skipped, {}", rule.pattern, e));
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        (Self { rules }, errors)
+    }
+
+    /// `has_cmd` is whether the todo has a command, its own or the sticky note's default.
+    pub fn style_for(&self, todo: &Todo, has_cmd: bool) -> Option<Style> {
+        self.rules
+            .iter()
+            .find(|(pat, max_runs, _)| {
+                let runs = max_runs.is_none_or(|max| has_cmd && todo.runs <= max);
+                runs && pat.is_match(&todo.task)
+            })
+            .map(|(_, _, style)| *style)
+    }
+}
@@ -0,0 +1,77 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Passed to `openssl` through the environment so it never shows up in `ps`.
+const PASS_VAR: &str = "FORGET_PASS";
+const CIPHER_ARGS: &[&str] = &[
+    "enc",
+    "-aes-256-cbc",
+    "-pbkdf2",
+    "-iter",
+    "100000",
+    "-a",
+    "-A",
+    "-pass",
+    "env:FORGET_PASS",
+];
+
+/// Encrypts `plain` with `pass`, the result is base64 text safe to store in the DB.
+pub fn encrypt(plain: &str, pass: &str) -> io::Result<String> {
+    openssl(&["-salt"], plain, pass)
+}
+
+/// Decrypts text produced by `encrypt`, a wrong passphrase is an error.
+pub fn decrypt(cipher: &str, pass: &str) -> io::Result<String> {
+    openssl(&["-d"], cipher, pass)
+}
+
+/// Checks `pass` against a SHA-512 crypt hash as made by `openssl passwd -6`.
+pub fn verify_passphrase(pass: &str, hash: &str) -> io::Result<bool> {
+    let salt = hash
+        .split('$')
+        .nth(2)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "malformed passphrase hash"))?;
+    let out = run(&["passwd", "-6", "-salt", salt, "-stdin"], pass, None)?;
+    Ok(out.trim() == hash.trim())
+}
+
+fn openssl(extra: &[&str], input: &str, pass: &str) -> io::Result<String> {
+    let args = CIPHER_ARGS
+        .iter()
+        .chain(extra.iter())
+        .copied()
+        .collect::<Vec<_>>();
+    run(&args, input, Some(pass))
+}
+
+fn run(args: &[&str], input: &str, pass: Option<&str>) -> io::Result<String> {
+    let mut cmd = Command::new("openssl");
+    if let Some(pass) = pass {
+        cmd.env(PASS_VAR, pass);
+    }
+    let mut child = cmd
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // write from another thread so a full stdout pipe can't deadlock us
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child.wait_with_output()?;
+    writer
+        .join()
+        .map_err(|_| io::Error::other("openssl writer panicked"))??;
+
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "wrong passphrase or corrupt secret",
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,80 @@
+//! `session.json` remembers how far each sticky note has been seen, so a tab
+//! that changed while it wasn't shown, from a recurring note, a remote
+//! refresh or another `forget` on the same data base, is marked until it's
+//! visited.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::app::Remind;
+use crate::config;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Session {
+    /// The `updated_at` of each sticky note, by title, when it was last shown.
+    #[serde(default)]
+    seen: BTreeMap<String, i64>,
+    /// When this `forget` started, sticky notes never shown count as seen then.
+    #[serde(skip)]
+    opened: i64,
+    /// Where it's saved, `None` for one that isn't.
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+/// Kept next to the data base, each one remembers its own sticky notes.
+fn path() -> PathBuf {
+    config::db_path().with_file_name("session.json")
+}
+
+impl Session {
+    /// A session that's never saved, for `forget bench` and the tests.
+    pub fn new() -> Session {
+        Session {
+            opened: Local::now().timestamp(),
+            ..Session::default()
+        }
+    }
+
+    /// The last session, a missing or broken file starts a new one.
+    pub fn load() -> Session {
+        let path = path();
+        let mut session = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Session>(&text).ok())
+            .unwrap_or_default();
+        session.opened = Local::now().timestamp();
+        session.path = Some(path);
+        session
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        match &self.path {
+            Some(path) => fs::write(path, serde_json::to_string(self)?),
+            None => Ok(()),
+        }
+    }
+
+    /// Marks `remind` seen as it is now.
+    pub fn see(&mut self, remind: &Remind) {
+        if let Some(updated) = remind.updated_at {
+            self.seen.insert(remind.title.clone(), updated);
+        }
+    }
+
+    /// True when `remind` changed since it was last shown.
+    pub fn is_unread(&self, remind: &Remind) -> bool {
+        let seen = self.seen.get(&remind.title).unwrap_or(&self.opened);
+        remind.updated_at.is_some_and(|updated| updated > *seen)
+    }
+
+    /// Forgets the sticky notes no longer there, so the file doesn't grow.
+    pub fn retain(&mut self, titles: &[String]) {
+        self.seen.retain(|title, _| titles.contains(title));
+    }
+}
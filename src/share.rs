@@ -0,0 +1,175 @@
+//! `forget share export` writes some sticky notes to a bundle another
+//! `forget` can `share import`, to hand a checklist to a friend. Bundles
+//! leave out what only makes sense on this machine, and commands unless
+//! asked for.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{ListState, Remind, Shared};
+use crate::config;
+
+/// What `share export` writes.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Bundle {
+    /// Who shared it, `$USER` or the `device_name`.
+    pub from: String,
+    /// Unix time it was exported.
+    pub exported: i64,
+    pub notes: Vec<Remind>,
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+/// `notes` by title as they are shared, without run counts, attachments,
+/// recurring templates or, unless `include_cmds`, commands. Secret sticky
+/// notes are never shared.
+pub fn bundle(
+    notes: &ListState<Remind>,
+    titles: &[&str],
+    include_cmds: bool,
+) -> io::Result<Bundle> {
+    let mut shared = Vec::new();
+    for title in titles {
+        let remind = notes
+            .iter()
+            .find(|n| n.title == *title)
+            .ok_or_else(|| invalid(format!("there is no sticky note called {:?}", title)))?;
+        if remind.secret.is_some() {
+            return Err(invalid(format!("{:?} is secret, it isn't shared", title)));
+        }
+        let mut remind = remind.sealed()?;
+        remind.attachments.clear();
+        remind.instance = None;
+        remind.shared = None;
+        remind.note_scroll = 0;
+        if !include_cmds {
+            remind.default_cmd.clear();
+        }
+        for todo in remind.list.items.iter_mut() {
+            todo.tracked = 0;
+            todo.started = None;
+            todo.runs = 0;
+            todo.last_run = None;
+            todo.edited = None;
+            if !include_cmds {
+                todo.cmd.clear();
+            }
+        }
+        shared.push(remind);
+    }
+    // a todo waiting on one that isn't shared isn't waiting for the friend
+    let ids = shared
+        .iter()
+        .flat_map(|n| n.list.iter().map(|t| t.id))
+        .collect::<Vec<_>>();
+    for todo in shared.iter_mut().flat_map(|n| n.list.items.iter_mut()) {
+        todo.blocked_by = todo.blocked_by.filter(|id| ids.contains(id));
+    }
+    let from = std::env::var("USER")
+        .ok()
+        .filter(|user| !user.is_empty())
+        .unwrap_or_else(|| config::CFG.with(|cfg| cfg.device_name.clone()));
+    Ok(Bundle {
+        from,
+        exported: Local::now().timestamp(),
+        notes: shared,
+    })
+}
+
+/// Adds the notes of `bundle` to `notes` as new sticky notes, a title
+/// already taken gets ` (from <who>)` after it. Returns their titles.
+pub fn add(notes: &mut ListState<Remind>, bundle: Bundle) -> Vec<String> {
+    let mut next = notes.next_todo_id();
+    let mut ids = HashMap::new();
+    let mut added = Vec::new();
+    for mut remind in bundle.notes {
+        let mut title = remind.title.clone();
+        while notes.iter().any(|n| n.title == title) {
+            title = format!("{} (from {})", title, bundle.from);
+        }
+        remind.title = title.clone();
+        remind.shared = Some(Shared {
+            from: bundle.from.clone(),
+            at: bundle.exported,
+        });
+        remind.touch();
+        for todo in remind.list.items.iter_mut() {
+            ids.insert(todo.id, next);
+            todo.id = next;
+            next += 1;
+        }
+        notes.items.push(remind);
+        added.push(title);
+    }
+    let start = notes.len() - added.len();
+    for todo in notes.items[start..]
+        .iter_mut()
+        .flat_map(|n| n.list.items.iter_mut())
+    {
+        todo.blocked_by = todo.blocked_by.and_then(|id| ids.get(&id).copied());
+    }
+    added
+}
+
+/// `forget share export --notes "Trip,Groceries" [--include-cmds] bundle.json`
+/// and `forget share import bundle.json`.
+pub fn run(args: &[String]) -> io::Result<()> {
+    let usage = || {
+        invalid(
+            "usage: forget share export --notes \"Trip,Groceries\" [--include-cmds] <file> \
+             or forget share import <file>"
+                .to_string(),
+        )
+    };
+    let rest = args.get(1..).unwrap_or_default();
+    let mut titles = None;
+    let mut file = None;
+    let mut include_cmds = false;
+    let mut words = rest.iter();
+    while let Some(word) = words.next() {
+        match word.as_str() {
+            "--notes" => titles = Some(words.next().ok_or_else(usage)?),
+            "--include-cmds" => include_cmds = true,
+            _ => file = Some(word),
+        }
+    }
+    let file = file.ok_or_else(usage)?;
+
+    match args.first().map(String::as_str) {
+        Some("export") => {
+            let titles = titles
+                .ok_or_else(usage)?
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .collect::<Vec<_>>();
+            if Path::new(file).exists() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} is already there", file),
+                ));
+            }
+            let bundle = bundle(&config::open_db()?, &titles, include_cmds)?;
+            fs::write(file, serde_json::to_string_pretty(&bundle)?)?;
+            println!("shared {} sticky notes in {}", bundle.notes.len(), file);
+        }
+        Some("import") => {
+            let bundle = serde_json::from_str::<Bundle>(&fs::read_to_string(file)?)?;
+            let from = bundle.from.clone();
+            let mut notes = config::open_db()?;
+            let added = add(&mut notes, bundle);
+            config::save_db(&notes)?;
+            println!("added {} from {}", added.join(", "), from);
+        }
+        _ => return Err(usage()),
+    }
+    Ok(())
+}
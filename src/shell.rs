@@ -0,0 +1,77 @@
+//! Splits a command into the pieces it is highlighted by, it only has to look
+//! right, not parse every shell.
+
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// The program run, the first word and every word after an operator.
+    Program,
+    Flag,
+    /// Quoted text, an unclosed quote runs to the end.
+    Str,
+    /// Pipes, redirects, `&&`, `||`, `;` and `&`.
+    Operator,
+    /// `$VAR`, `${VAR}` and `{task}`.
+    Variable,
+    Word,
+    Space,
+}
+
+const OPERATORS: [&str; 9] = ["&&", "||", ">>", "|", ";", "&", ">", "<", "2>"];
+
+pub fn tokens(cmd: &str) -> Vec<(Range<usize>, Kind)> {
+    let mut tokens = Vec::new();
+    let mut expect_program = true;
+    let mut i = 0;
+    while i < cmd.len() {
+        let rest = &cmd[i..];
+        let c = rest.chars().next().unwrap_or(' ');
+        let (len, kind) = if c.is_whitespace() {
+            let len = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            (len, Kind::Space)
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+            (op.len(), Kind::Operator)
+        } else if c == '"' || c == '\'' {
+            let len = rest[1..].find(c).map(|end| end + 2).unwrap_or(rest.len());
+            (len, Kind::Str)
+        } else if c == '$' {
+            let len = if rest.starts_with("${") {
+                rest.find('}').map(|end| end + 1).unwrap_or(rest.len())
+            } else {
+                1 + rest[1..]
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len() - 1)
+            };
+            (len, Kind::Variable)
+        } else if rest.starts_with("{task}") {
+            ("{task}".len(), Kind::Variable)
+        } else {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || "\"'$|;&<>".contains(c))
+                .unwrap_or(rest.len());
+            let placeholder = rest.find("{task}").unwrap_or(rest.len());
+            let len = end.min(placeholder).max(c.len_utf8());
+            let kind = if expect_program {
+                Kind::Program
+            } else if c == '-' {
+                Kind::Flag
+            } else {
+                Kind::Word
+            };
+            (len, kind)
+        };
+
+        match kind {
+            // a redirect is followed by a file, not a program
+            Kind::Operator => expect_program = !cmd[i..i + len].contains(['<', '>']),
+            Kind::Space => {}
+            _ => expect_program = false,
+        }
+        tokens.push((i..i + len, kind));
+        i += len;
+    }
+    tokens
+}
@@ -0,0 +1,133 @@
+//! Spell checking against hunspell style word lists, the affix rules aren't
+//! applied so a few common endings are stripped instead.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// Endings tried when a word isn't in the list as it is.
+const SUFFIXES: [&str; 8] = ["'s", "s", "es", "ed", "d", "ing", "ly", "er"];
+
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl fmt::Debug for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dictionary")
+            .field("words", &self.words.len())
+            .finish()
+    }
+}
+
+impl Dictionary {
+    /// Loads the word list for `lang`, like `en_US`, from `~/.forget/dict`
+    /// or the system's hunspell dictionaries.
+    pub fn load(lang: &str) -> Option<Dictionary> {
+        let text = Self::paths(lang)
+            .into_iter()
+            .find_map(|path| fs::read_to_string(path).ok())?;
+        let words = text
+            .lines()
+            .enumerate()
+            // a .dic file starts with the number of words
+            .filter(|(i, line)| !(*i == 0 && line.trim().parse::<usize>().is_ok()))
+            .filter_map(|(_, line)| line.split('/').next())
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        Some(Dictionary { words })
+    }
+
+    fn paths(lang: &str) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(mut home) = dirs::home_dir() {
+            home.push(".forget");
+            home.push("dict");
+            home.push(format!("{}.dic", lang));
+            paths.push(home);
+        }
+        for dir in &[
+            "/usr/share/hunspell",
+            "/usr/share/myspell",
+            "/usr/share/myspell/dicts",
+            "/Library/Spelling",
+        ] {
+            paths.push(PathBuf::from(format!("{}/{}.dic", dir, lang)));
+        }
+        if lang.starts_with("en") {
+            paths.push(PathBuf::from("/usr/share/dict/words"));
+        }
+        paths
+    }
+
+    pub fn is_correct(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        if self.words.contains(&word) {
+            return true;
+        }
+        SUFFIXES.iter().any(|suffix| {
+            word.strip_suffix(suffix)
+                .map(|stem| !stem.is_empty() && self.words.contains(stem))
+                .unwrap_or(false)
+        })
+    }
+
+    /// The byte ranges of the misspelled words in `text`, tags, mentions and
+    /// links are skipped.
+    pub fn misspelled(&self, text: &str) -> Vec<Range<usize>> {
+        let mut found = Vec::new();
+        let mut start = None;
+        let mut skip = false;
+        for (i, c) in text
+            .char_indices()
+            .chain(std::iter::once((text.len(), ' ')))
+        {
+            let in_word =
+                c.is_alphabetic() || (c == '\'' && start.is_some() && next_is_alphabetic(text, i));
+            match (in_word, start) {
+                (true, None) => {
+                    skip |= i > 0 && is_skipped_token(text, i);
+                    start = Some(i);
+                }
+                (false, Some(s)) => {
+                    let link = text[i..].starts_with("://");
+                    skip |= link || c.is_ascii_digit();
+                    if !skip && !self.is_correct(&text[s..i]) {
+                        found.push(s..i);
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+            if c.is_whitespace() {
+                skip = false;
+            }
+        }
+        found
+    }
+}
+
+fn next_is_alphabetic(text: &str, i: usize) -> bool {
+    text[i + 1..]
+        .chars()
+        .next()
+        .map(char::is_alphabetic)
+        .unwrap_or(false)
+}
+
+/// True when the whitespace separated token the word starts in is a tag,
+/// mention or path.
+fn is_skipped_token(text: &str, i: usize) -> bool {
+    let token_start = text[..i]
+        .rfind(char::is_whitespace)
+        .map(|ws| ws + 1)
+        .unwrap_or(0);
+    let prefix = &text[token_start..i];
+    prefix.starts_with('#')
+        || prefix.starts_with('@')
+        || prefix.contains('/')
+        || prefix.chars().any(|c| c.is_ascii_digit())
+}
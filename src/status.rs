@@ -0,0 +1,80 @@
+//! `forget status`, the todos due today as one line of JSON for a status bar
+//! or tray like waybar, polybar or i3blocks, only built with `--features tray`.
+
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{Local, TimeZone};
+use serde_json::json;
+
+use crate::app::Todo;
+use crate::config;
+use crate::dates::Dates;
+use crate::due;
+
+/// How often `--watch` reads the data base again.
+const WATCH_SECS: u64 = 30;
+
+/// Prints the status once, or with `watch` every time it changes.
+pub fn run(watch: bool) -> io::Result<()> {
+    let mut last = String::new();
+    loop {
+        let line = status()?;
+        if line != last {
+            let mut out = io::stdout();
+            writeln!(out, "{}", line)?;
+            out.flush()?;
+            last = line;
+        }
+        if !watch {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(WATCH_SECS));
+    }
+}
+
+fn status() -> io::Result<String> {
+    let now = Local::now();
+    let end_of_day = Local
+        .from_local_datetime(&(now.naive_local().date().succ().and_hms(0, 0, 0)))
+        .earliest()
+        .map(|d| d.timestamp())
+        .unwrap_or(i64::MAX);
+
+    let notes = config::open_db()?;
+    let dates = config::open_cfg_file()
+        .and_then(|config| Dates::new(&config))
+        .unwrap_or_default();
+    // smart and remote lists aren't saved and locked secrets can't be read
+    let mut todos = notes
+        .iter()
+        .filter(|n| !n.is_view() && !n.is_locked())
+        .flat_map(|n| n.list.iter())
+        .filter(|t| !t.status.is_closed())
+        .filter(|t| t.due.is_some_and(|d| d < end_of_day))
+        .collect::<Vec<&Todo>>();
+    todos.sort_by_key(|t| t.due);
+
+    let overdue = todos
+        .iter()
+        .filter(|t| t.due.is_some_and(|d| d < now.timestamp()))
+        .count();
+    let tooltip = todos
+        .iter()
+        .map(|t| {
+            format!(
+                "{} {}",
+                due::short(t.due.unwrap_or_default(), now, &dates),
+                t.as_str()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let (text, class) = match (todos.len(), overdue) {
+        (0, _) => (String::new(), "none"),
+        (n, 0) => (format!("⏰ {}", n), "due"),
+        (n, _) => (format!("⏰ {}", n), "overdue"),
+    };
+    Ok(json!({ "text": text, "tooltip": tooltip, "class": class }).to_string())
+}
@@ -0,0 +1,32 @@
+//! The symbols offered by the pickers for the highlight symbol and the
+//! sticky note icons.
+
+/// Symbols with a name to tell the ones that look alike apart.
+pub const SYMBOLS: &[(&str, &str)] = &[
+    ("✔️", "check"),
+    ("✅", "check box"),
+    ("☑️", "ballot box"),
+    ("👉", "pointing"),
+    ("▶", "play"),
+    ("➜", "arrow"),
+    ("★", "star"),
+    ("⭐", "yellow star"),
+    ("📌", "pin"),
+    ("📝", "memo"),
+    ("💡", "idea"),
+    ("🔥", "fire"),
+    ("🚀", "rocket"),
+    ("🎯", "target"),
+    ("🐛", "bug"),
+    ("🏠", "home"),
+    ("💼", "work"),
+    ("🛒", "shopping"),
+];
+
+/// How a symbol is listed in a picker.
+pub fn choices() -> Vec<String> {
+    SYMBOLS
+        .iter()
+        .map(|(symbol, name)| format!("{}  {}", symbol, name))
+        .collect()
+}
@@ -0,0 +1,349 @@
+//! Shares sticky notes with other people through a small sync server, only
+//! built with `--features sync`. Each change to a shared sticky note is an
+//! op, a todo put or removed or the note text replaced. The ops are found by
+//! comparing the note with how it was last synced, pushed to the server and
+//! the ops of everyone else pulled back and merged, the last one the server
+//! got winning. The server keeps the ops of each sticky note in order:
+//!
+//! - `POST <url>/notes/<title>/ops` with `{"ops": [..]}` adds ops.
+//! - `GET <url>/notes/<title>/ops?since=<seq>` answers `{"ops": [..]}`, every
+//!   op after `seq` with the `seq` the server gave it.
+//!
+//! Both send the token as `Authorization: Bearer <token>`.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::app::{Remind, Todo};
+use crate::curl;
+
+/// The server and which sticky notes are shared through it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SyncServer {
+    pub url: String,
+    /// The environment variable holding the token.
+    pub token_env: String,
+    /// Titles of the sticky notes shared, everyone sharing one uses the same title.
+    pub notes: Vec<String>,
+    /// Seconds between syncs.
+    #[serde(default = "default_every_secs")]
+    pub every_secs: u64,
+}
+
+fn default_every_secs() -> u64 {
+    5
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Change {
+    /// A todo added or changed, `key` is the same for it everywhere.
+    Put {
+        key: String,
+        todo: Box<Todo>,
+    },
+    Remove {
+        key: String,
+    },
+    Note {
+        text: String,
+    },
+}
+
+impl Change {
+    /// What it changes, the last op for the same thing wins.
+    fn target(&self) -> &str {
+        match self {
+            Change::Put { key, .. } | Change::Remove { key } => key,
+            Change::Note { .. } => "",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Op {
+    /// Given by the server, 0 until it has the op.
+    #[serde(default)]
+    pub seq: u64,
+    /// The `device_name` it was made on.
+    pub device: String,
+    /// Unix time.
+    pub at: i64,
+    pub change: Change,
+}
+
+/// How one shared sticky note was last synced.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Channel {
+    /// The last op pulled.
+    since: u64,
+    /// The key of each local todo id.
+    keys: BTreeMap<u64, String>,
+    /// The todos and note text as the server has them.
+    base: BTreeMap<String, Todo>,
+    note: String,
+}
+
+/// A todo as it's synced, ids and blockers only mean something locally.
+fn wire(todo: &Todo) -> Todo {
+    Todo {
+        id: 0,
+        blocked_by: None,
+        ..todo.clone()
+    }
+}
+
+fn same(a: &Todo, b: &Todo) -> bool {
+    serde_json::to_string(a).ok() == serde_json::to_string(b).ok()
+}
+
+impl Channel {
+    /// The ops that make the server's copy look like `remind`.
+    pub fn diff(&mut self, remind: &Remind, device: &str) -> Vec<Op> {
+        let at = Local::now().timestamp();
+        let op = |change| Op {
+            seq: 0,
+            device: device.to_string(),
+            at,
+            change,
+        };
+        let mut ops = Vec::new();
+        let mut present = Vec::new();
+        for todo in remind.list.iter() {
+            let key = self
+                .keys
+                .entry(todo.id)
+                .or_insert_with(|| format!("{}-{}-{}", device, at, todo.id))
+                .clone();
+            let todo = wire(todo);
+            if !self.base.get(&key).is_some_and(|old| same(old, &todo)) {
+                ops.push(op(Change::Put {
+                    key: key.clone(),
+                    todo: Box::new(todo),
+                }));
+            }
+            present.push(key);
+        }
+        for key in self.base.keys().filter(|key| !present.contains(key)) {
+            ops.push(op(Change::Remove { key: key.clone() }));
+        }
+        if remind.note != self.note {
+            ops.push(op(Change::Note {
+                text: remind.note.clone(),
+            }));
+        }
+        ops
+    }
+
+    /// The server has `ops` now.
+    pub fn pushed(&mut self, ops: &[Op]) {
+        for op in ops {
+            self.settle(&op.change);
+        }
+    }
+
+    fn settle(&mut self, change: &Change) {
+        match change {
+            Change::Put { key, todo } => {
+                self.base.insert(key.clone(), (**todo).clone());
+            }
+            Change::Remove { key } => {
+                self.base.remove(key);
+            }
+            Change::Note { text } => self.note = text.clone(),
+        }
+    }
+
+    /// Merges the `ops` pulled into `remind`, the ones from `device` are
+    /// already there. New todos get ids from `next_id` on. Returns who made
+    /// the changes merged.
+    pub fn merge(
+        &mut self,
+        remind: &mut Remind,
+        mut ops: Vec<Op>,
+        device: &str,
+        mut next_id: u64,
+    ) -> Vec<String> {
+        ops.sort_by_key(|op| op.seq);
+        self.since = ops.last().map_or(self.since, |op| op.seq.max(self.since));
+        // only the last op for each todo counts, when it's ours there's nothing to do
+        let mut last = BTreeMap::new();
+        for (idx, op) in ops.iter().enumerate() {
+            last.insert(op.change.target().to_string(), idx);
+        }
+        let mut from = Vec::new();
+        for idx in last.into_values() {
+            let op = &ops[idx];
+            self.settle(&op.change);
+            if op.device == device {
+                continue;
+            }
+            let id = |keys: &BTreeMap<u64, String>, key: &str| {
+                keys.iter().find(|(_, k)| *k == key).map(|(id, _)| *id)
+            };
+            match &op.change {
+                Change::Put { key, todo } => match id(&self.keys, key)
+                    .and_then(|id| remind.list.items.iter_mut().find(|t| t.id == id))
+                {
+                    Some(local) => {
+                        *local = Todo {
+                            id: local.id,
+                            blocked_by: local.blocked_by,
+                            ..(**todo).clone()
+                        }
+                    }
+                    None => {
+                        self.keys.insert(next_id, key.clone());
+                        remind.list.items.push(Todo {
+                            id: next_id,
+                            ..(**todo).clone()
+                        });
+                        next_id += 1;
+                    }
+                },
+                Change::Remove { key } => {
+                    if let Some(id) = id(&self.keys, key) {
+                        remind.list.items.retain(|t| t.id != id);
+                        self.keys.remove(&id);
+                    }
+                }
+                Change::Note { text } => remind.note = text.clone(),
+            }
+            if !from.contains(&op.device) {
+                from.push(op.device.clone());
+            }
+        }
+        let len = remind.list.len();
+        remind.list.selected = remind.list.selected.min(len.saturating_sub(1));
+        from
+    }
+
+    pub fn since(&self) -> u64 {
+        self.since
+    }
+}
+
+/// Every shared sticky note's `Channel`, kept in `sync.json`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SyncState {
+    pub channels: BTreeMap<String, Channel>,
+}
+
+fn path() -> PathBuf {
+    let mut home = dirs::home_dir().expect("home dir not found");
+    home.push(".forget");
+    home.push("sync.json");
+    home
+}
+
+impl SyncState {
+    /// A missing or broken file syncs everything again from the start.
+    pub fn load() -> SyncState {
+        fs::read_to_string(path())
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(path(), serde_json::to_string(self)?)
+    }
+}
+
+/// What a sync of one sticky note got back.
+pub struct Synced {
+    pub title: String,
+    /// The ops pushed, empty when pushing failed.
+    pub pushed: Vec<Op>,
+    pub pulled: io::Result<Vec<Op>>,
+}
+
+/// Escapes a title for the path of a URL.
+fn escape(title: &str) -> String {
+    title
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn request(
+    server: &SyncServer,
+    token: &str,
+    url: &str,
+    body: Option<String>,
+) -> io::Result<String> {
+    let mut config = format!(
+        "url = \"{}\"\nheader = \"Authorization: Bearer {}\"\nheader = \"Content-Type: application/json\"\n",
+        curl::quote(url),
+        curl::quote(token)
+    );
+    if let Some(body) = body {
+        config.push_str(&format!("data-binary = \"{}\"\n", curl::quote(&body)));
+    }
+    tracing::debug!("sync {}", server.url);
+    curl::run(&config)
+}
+
+/// Pushes `ops` for the sticky note `title` and pulls every op after `since`.
+pub fn sync(server: &SyncServer, title: String, ops: Vec<Op>, since: u64) -> Synced {
+    let token = match env::var(&server.token_env) {
+        Ok(token) => token,
+        Err(_) => {
+            return Synced {
+                title,
+                pushed: Vec::new(),
+                pulled: Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("${} is not set", server.token_env),
+                )),
+            }
+        }
+    };
+    let url = format!(
+        "{}/notes/{}/ops",
+        server.url.trim_end_matches('/'),
+        escape(&title)
+    );
+    let pushed = if ops.is_empty() {
+        Ok(())
+    } else {
+        request(
+            server,
+            &token,
+            &url,
+            Some(json!({ "ops": ops }).to_string()),
+        )
+        .map(|_| ())
+    };
+    let pushed = match pushed {
+        Ok(()) => ops,
+        Err(e) => {
+            return Synced {
+                title,
+                pushed: Vec::new(),
+                pulled: Err(e),
+            }
+        }
+    };
+    let pulled =
+        request(server, &token, &format!("{}?since={}", url, since), None).and_then(|body| {
+            let body = serde_json::from_str::<serde_json::Value>(&body)?;
+            Ok(serde_json::from_value::<Vec<Op>>(body["ops"].clone())?)
+        });
+    Synced {
+        title,
+        pushed,
+        pulled,
+    }
+}
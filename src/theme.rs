@@ -0,0 +1,192 @@
+//! Themes are `app_colors` kept in `~/.forget/themes`, made from base16
+//! scheme files by `forget theme import` and picked in the app with alt-t.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, AppColor, AppMod, AppStyle, ColorCfg};
+use crate::web_colors;
+
+/// What the theme picker calls the colors `forget` comes with.
+pub const DEFAULT: &str = "Default";
+
+/// A base16 scheme, `base00` to `base0F`.
+#[derive(Clone, Debug)]
+pub struct Base16 {
+    pub name: String,
+    pub base: [(u8, u8, u8); 16],
+}
+
+impl Base16 {
+    /// Reads the `scheme:` (or `name:`) and `baseXX:` lines of a base16 YAML
+    /// file, the rest of YAML isn't needed for them.
+    pub fn parse(text: &str) -> io::Result<Base16> {
+        let mut name = None;
+        let mut base = [None; 16];
+        for line in text.lines() {
+            let line = line.split(" #").next().unwrap_or_default().trim();
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), unquote(value.trim())),
+                None => continue,
+            };
+            if key == "scheme" || key == "name" {
+                name = Some(value.to_string());
+                continue;
+            }
+            let idx = match key
+                .strip_prefix("base0")
+                .and_then(|digit| u8::from_str_radix(digit, 16).ok())
+            {
+                Some(idx) if key.len() == 6 => idx as usize,
+                _ => continue,
+            };
+            let hex = format!("#{}", value.trim_start_matches('#'));
+            base[idx] = Some(
+                web_colors::rgb(&hex)
+                    .ok_or_else(|| invalid(format!("{} is {:?}, not a hex color", key, value)))?,
+            );
+        }
+        let mut colors = [(0, 0, 0); 16];
+        for (idx, color) in base.iter().enumerate() {
+            colors[idx] = color.ok_or_else(|| invalid(format!("base{:02X} is missing", idx)))?;
+        }
+        Ok(Base16 {
+            name: name.unwrap_or_else(|| "Imported".to_string()),
+            base: colors,
+        })
+    }
+
+    fn color(&self, idx: usize) -> AppColor {
+        let (r, g, b) = self.base[idx];
+        AppColor::Rgb(r, g, b)
+    }
+
+    /// The styles by the base16 guidelines: `base05` for text, `base03` for
+    /// what is out of the way and `base08` to `base0E` for the accents. The
+    /// terminal's background is kept.
+    pub fn colors(&self) -> ColorCfg {
+        let style = |idx: usize, modifier: AppMod| AppStyle {
+            fg: self.color(idx),
+            bg: AppColor::Reset,
+            modifier,
+        };
+        ColorCfg {
+            normal: style(0x05, AppMod::empty()),
+            highlight: style(0x0A, AppMod::BOLD),
+            tabs: style(0x0D, AppMod::BOLD),
+            titles: style(0x08, AppMod::BOLD),
+            text: style(0x0B, AppMod::ITALIC),
+            warning: style(0x08, AppMod::BOLD),
+            completed: style(0x03, AppMod::CROSSED_OUT),
+            pending: AppStyle {
+                fg: AppColor::Reset,
+                bg: AppColor::Reset,
+                modifier: AppMod::ITALIC,
+            },
+            command: style(0x0C, AppMod::empty()),
+            countdown: style(0x0E, AppMod::BOLD),
+        }
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches(|c| c == '"' || c == '\'')
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn dir() -> PathBuf {
+    let mut home = dirs::home_dir().expect("home dir not found");
+    home.push(".forget");
+    home.push("themes");
+    home
+}
+
+/// The file name of a theme, `Gruvbox dark, hard` is `gruvbox-dark-hard`.
+fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The names of the saved themes, sorted.
+pub fn list() -> Vec<String> {
+    let mut names = fs::read_dir(dir())
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|p| Some(p.file_stem()?.to_string_lossy().into_owned()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// The colors of the theme called `name`, `DEFAULT` is the built in one.
+pub fn load(name: &str) -> io::Result<ColorCfg> {
+    if name == DEFAULT {
+        return Ok(config::CFG.with(|cfg| cfg.app_colors.clone()));
+    }
+    let text = fs::read_to_string(dir().join(format!("{}.json", name)))?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+fn save(name: &str, colors: &ColorCfg) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir())?;
+    let path = dir().join(format!("{}.json", slug(name)));
+    fs::write(&path, serde_json::to_string_pretty(colors)?)?;
+    Ok(path)
+}
+
+/// Saves the base16 scheme at `path` as a theme and switches to it.
+pub fn import(path: &Path) -> io::Result<String> {
+    let scheme = Base16::parse(&fs::read_to_string(path)?)?;
+    let colors = scheme.colors();
+    let saved = save(&scheme.name, &colors)?;
+    // writes the default config first when there is none
+    config::save_cfg_file()?;
+    let mut cfg = config::open_cfg_file()?;
+    cfg.app_colors = colors;
+    config::save_config(&cfg)?;
+    Ok(format!(
+        "imported \"{}\" to {} and switched to it",
+        scheme.name,
+        saved.display()
+    ))
+}
+
+/// `forget theme import <file>` and `forget theme list`.
+pub fn run(args: &[String]) -> io::Result<()> {
+    match args.first().map(String::as_str) {
+        Some("import") => {
+            let path = args.get(1).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "usage: forget theme import <file>",
+                )
+            })?;
+            println!("{}", import(Path::new(path))?);
+        }
+        Some("list") | None => {
+            println!("{}", DEFAULT);
+            for name in list() {
+                println!("{}", name);
+            }
+        }
+        Some(other) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown theme command {:?}, try import or list", other),
+            ))
+        }
+    }
+    Ok(())
+}
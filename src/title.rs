@@ -0,0 +1,17 @@
+//! Date patterns in sticky note titles, so `Standup @today` becomes
+//! `Standup 2020-03-01` for daily notes.
+
+use chrono::{DateTime, Duration, Local};
+
+use crate::dates::Dates;
+
+/// Replaces `@today`, `@yesterday`, `@tomorrow`, `@now` and `@week` in `title`.
+pub fn expand(title: &str, now: DateTime<Local>, dates: &Dates) -> String {
+    let date = |days: i64| dates.date(&(now + Duration::days(days)));
+    title
+        .replace("@today", &date(0))
+        .replace("@yesterday", &date(-1))
+        .replace("@tomorrow", &date(1))
+        .replace("@now", &dates.date_time(&now))
+        .replace("@week", &dates.week(&now))
+}
@@ -0,0 +1,71 @@
+/// How a toast is colored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct Toast {
+    pub text: String,
+    pub level: Level,
+    /// Ticks until the toast disappears.
+    ticks_left: u64,
+    /// Set for the tick after it expired, while it folds away.
+    pub leaving: bool,
+}
+
+/// Short messages stacked in the bottom right corner, each one goes away
+/// after `ticks` ticks.
+#[derive(Clone, Debug, Default)]
+pub struct Toasts {
+    pub list: Vec<Toast>,
+    ticks: u64,
+}
+
+impl Toasts {
+    pub fn new(ticks: u64) -> Self {
+        Self {
+            list: Vec::new(),
+            ticks,
+        }
+    }
+
+    pub fn info<S: Into<String>>(&mut self, text: S) {
+        self.push(text.into(), Level::Info);
+    }
+
+    /// Errors are logged too, toasts only last a moment.
+    pub fn error<S: Into<String>>(&mut self, text: S) {
+        let text = text.into();
+        tracing::error!("{}", text);
+        self.push(text, Level::Error);
+    }
+
+    fn push(&mut self, text: String, level: Level) {
+        if self.ticks == 0 {
+            return;
+        }
+        self.list.push(Toast {
+            text,
+            level,
+            ticks_left: self.ticks,
+            leaving: false,
+        });
+    }
+
+    /// True when a toast expired, it's kept one more tick for
+    /// `Anim::ToastsLeaving` to fold it away.
+    pub fn tick(&mut self) -> bool {
+        self.list.retain(|t| !t.leaving);
+        let mut expired = false;
+        for toast in self.list.iter_mut() {
+            toast.ticks_left = toast.ticks_left.saturating_sub(1);
+            if toast.ticks_left == 0 {
+                toast.leaving = true;
+                expired = true;
+            }
+        }
+        expired
+    }
+}
@@ -1,57 +1,695 @@
+use std::collections::HashSet;
 use std::io;
 
+use chrono::{Datelike, Local, TimeZone};
 use tui::backend::Backend;
-use tui::layout::{Constraint, Direction, Layout, Rect};
-use tui::style::Style;
-use tui::widgets::{Block, Borders, Paragraph, Tabs, Text, Widget};
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::style::{Modifier, Style};
+use tui::widgets::{Block, Borders, Gauge, List, Paragraph, SelectableList, Tabs, Text, Widget};
 use tui::{Frame, Terminal};
+use unicode_width::UnicodeWidthStr;
 
-use super::app::{App, Remind};
-use super::widget::TodoList;
+use super::anim::Anim;
+use super::app::{App, Areas, DropTarget, ListState, Remind, Status, Todo};
+use super::attach;
+use super::checklist;
+use super::colors::{self, Mode};
+use super::config::{AppColor, AppStyle};
+use super::dates::Dates;
+use super::due;
+use super::effort::Duration;
+use super::find::{self, Find};
+use super::hints;
+use super::logger;
+use super::markdown;
+use super::shell::{self, Kind};
+use super::toast::Level;
+use super::widget::{AsciiBorders, Clear, Dialog, TodoList};
+
+const MAX_JOBS_SHOWN: usize = 5;
+const MAX_ATTACHMENTS_SHOWN: usize = 4;
+
+const ACTIVITY_HINT: &str = "type to filter";
 
 const ADD_REMIND: &str = "Title of Sticky Note";
 const ADD_TODO: &str = "What do you want Todo";
 const ADD_CMD: &str = "Command to run";
+const ADD_BLOCKED_BY: &str = "Blocked by (id of another todo, empty for none)";
+const ADD_ESTIMATE: &str = "Estimate (30m, 2h or 3p for points, empty for none)";
+const FIND: &str = "Find (enter next, tab replace, ctrl-a replace all)";
+const ADD_DUE: &str = "Due (14:30, tomorrow 9:00 or 2020-03-01 14:30, empty for none)";
+const ADD_REPORT: &str = "Report (toast, silent, failures, notify or note, empty for toast)";
+/// In front of the next due todo next to the tabs.
+const DUE: &str = "⏰";
+const LOCKED: &str = "🔒";
+const UNLOCKED: &str = "🔓";
+const LOCKED_HINT: &str = "This sticky note is secret, press ctrl-l to unlock it.";
+const SMART: &str = "🔎";
+const REMOTE: &str = "🌐";
+const FEED: &str = "📰";
+/// After the title of a tab that changed since it was last shown.
+const UNREAD: &str = "•";
+/// Before the seconds until a kiosk reads the data base again.
+const RELOAD: &str = "↻";
+const ADD_QUERY: &str = "Query (words, #tags, is:open, is:done, note:title, has:cmd, /pattern/)";
+const PASSPHRASE: &str = "Passphrase";
+const CONFIRM_PASSPHRASE: &str = "Passphrase Again";
+const RUNBOOK_HINT: &str = "Enter/y: done   r: run command   s/n: skip";
+const EDIT_REMIND: &str = "Edit Title of Sticky Note";
+const ADD_DEFAULT_CMD: &str = "Default command ({task} is replaced by the todo)";
+const ADD_WIP_LIMIT: &str = "Most todos in progress at once (empty for no limit)";
+const WIP_WARNING: &str = "⚠";
+/// Next to the title while a command runs in the hidden terminal pane.
+const PTY: &str = "terminal";
+const HELP: &str = "Keys (any key closes)";
+const COLOR_HINT: &str = "tab: next   enter: save   esc: cancel";
+/// How many cells wide an RGB slider is.
+const SLIDER: usize = 24;
+/// Below this the layout has no room to draw, a message asks for more.
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 10;
 
 pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<(), io::Error> {
+    let titles = app
+        .tabs
+        .titles
+        .iter()
+        .zip(app.sticky_note.iter())
+        .enumerate()
+        .map(|(idx, (title, remind))| {
+            let title = match (&remind.secret, remind.is_locked()) {
+                (Some(_), true) => format!("{} {}", LOCKED, title),
+                (Some(_), false) => format!("{} {}", UNLOCKED, title),
+                _ if remind.is_smart() => format!("{} {}", SMART, title),
+                _ if remind.is_remote() => format!("{} {}", REMOTE, title),
+                _ if remind.feed.is_some() => format!("{} {}", FEED, title),
+                _ => match &remind.icon {
+                    Some(icon) => format!("{} {}", icon, title),
+                    None => title.clone(),
+                },
+            };
+            if idx != app.tabs.index && app.session.is_unread(remind) {
+                format!("{} {}", title, UNREAD)
+            } else {
+                title
+            }
+        })
+        .collect::<Vec<_>>();
+
     terminal.draw(|mut f| {
-        let chunks = Layout::default()
-            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
-            .split(f.size());
+        draw_frame(&mut f, app, &titles);
+        if app.low_bandwidth {
+            let size = f.size();
+            AsciiBorders.render(&mut f, size);
+        }
+    })
+}
 
-        Tabs::default()
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(&app.title)
-                    .title_style(
-                        Style::default()
-                            .fg(app.config.app_colors.titles.fg.into())
-                            .modifier(app.config.app_colors.titles.modifier.into()),
-                    ),
-            )
-            .titles(&app.tabs.titles)
-            .style(Style::default().fg(app.config.app_colors.normal.fg.into()))
-            .highlight_style(
-                Style::default()
-                    .fg(app.config.app_colors.tabs.fg.into())
-                    .modifier(app.config.app_colors.tabs.modifier.into()),
-            )
-            .select(app.tabs.index)
-            .render(&mut f, chunks[0]);
+fn draw_frame<B: Backend>(f: &mut Frame<B>, app: &mut App, titles: &[String]) {
+    let size = f.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        draw_too_small(f, app);
+        return;
+    }
+    if app.locked {
+        draw_lock_screen(f, app);
+        return;
+    }
+    if app.present.is_some() {
+        draw_present(f, app);
+        return;
+    }
 
-        draw_app(&mut f, app, chunks[1])
-    })
+    let chunks = Layout::default()
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(f.size());
+
+    // the same positions `Tabs` draws the titles at
+    app.areas.tabs_row = chunks[0].y + 1;
+    let mut x = chunks[0].x + 1;
+    app.areas.tabs = titles
+        .iter()
+        .map(|title| {
+            let width = title.width() as u16;
+            let range = (x + 1, width);
+            x += width + 3;
+            range
+        })
+        .collect();
+
+    let name = match &app.db_label {
+        Some(db) => format!("{} ─ {}", app.title, db),
+        None => app.title.clone(),
+    };
+    let title = match app.drag.and_then(|d| d.over) {
+        Some(DropTarget::Tab(tab)) => format!("Move to {}", app.tabs.titles[tab]),
+        _ if app.recording.is_some() => format!("{} ● REC", name),
+        _ if app.dry_run => format!("{} ─ DRY RUN", name),
+        _ if app.pty.is_some() && !app.pty_focus => format!("{} ─ F10: {}", name, PTY),
+        _ => name,
+    };
+
+    Tabs::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(&title)
+                .title_style(
+                    Style::default()
+                        .fg(app.config.app_colors.titles.fg.into())
+                        .modifier(app.config.app_colors.titles.modifier.into()),
+                ),
+        )
+        .titles(titles)
+        .style(Style::default().fg(app.config.app_colors.normal.fg.into()))
+        .highlight_style(
+            Style::default()
+                .fg(app.config.app_colors.tabs.fg.into())
+                .modifier(app.config.app_colors.tabs.modifier.into()),
+        )
+        .select(app.tabs.index)
+        .render(f, chunks[0]);
+    draw_due(f, app, chunks[0]);
+    draw_tasks(f, app, chunks[0]);
+
+    if app.activity_view {
+        draw_activity(f, app, chunks[1])
+    } else if app.agenda.is_some() {
+        draw_agenda(f, app, chunks[1])
+    } else {
+        draw_app(f, app, chunks[1])
+    }
+    if app.debug_overlay {
+        draw_debug(f, app);
+    }
+    draw_pty(f, app);
+    draw_modal(f, app);
+    draw_help(f, app);
+    draw_colors(f, app);
+    draw_toasts(f, app);
+}
+
+/// The next due todo across every sticky note, right aligned inside the tab bar.
+fn draw_due<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    app.areas.due = (0, 0);
+    let todo = match app.next_due() {
+        Some((tab, idx)) => &app.sticky_note[tab].list.items[idx],
+        None => return,
+    };
+    let due = match todo.due {
+        Some(due) => due::short(due, Local::now(), &app.dates),
+        None => return,
+    };
+    let tabs_end = app.areas.tabs.last().map(|(x, w)| x + w).unwrap_or(area.x);
+    // keep clear of the tab titles and the right border
+    let room = (area.x + area.width).saturating_sub(tabs_end + 3) as usize;
+    let prefix = format!(" {} {} ", DUE, due);
+    if room < prefix.width() + 4 {
+        return;
+    }
+    let mut task = String::new();
+    for c in todo.as_str().chars() {
+        if prefix.width() + task.width() + 3 > room {
+            task.pop();
+            task.push('…');
+            break;
+        }
+        task.push(c);
+    }
+    let text = format!("{}{} ", prefix, task);
+    let width = text.width() as u16;
+    let rect = Rect::new(area.x + area.width - 1 - width, area.y + 1, width, 1);
+    app.areas.due = (rect.x, width);
+    let style = Style::default()
+        .fg(app.config.app_colors.titles.fg.into())
+        .modifier(app.config.app_colors.titles.modifier.into());
+    Paragraph::new([Text::styled(text, style)].iter()).render(f, rect);
+}
+
+/// The spinner of the background tasks, right aligned on the tab bar's top border.
+fn draw_tasks<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let frame = app.animations.frame(Anim::Spinner).unwrap_or_default();
+    let text = match app.workers.spinner(frame) {
+        Some(spinner) => format!(" {} ", spinner),
+        None => return,
+    };
+    // keep clear of the title and the corners
+    let room = area.width.saturating_sub(app.title.width() as u16 + 4);
+    let width = (text.width() as u16).min(room);
+    if width < 4 {
+        return;
+    }
+    let rect = Rect::new(area.x + area.width - 1 - width, area.y, width, 1);
+    let style = Style::default()
+        .fg(app.config.app_colors.titles.fg.into())
+        .modifier(app.config.app_colors.titles.modifier.into());
+    Paragraph::new([Text::styled(text, style)].iter()).render(f, rect);
 }
 
 fn draw_app<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
 where
     B: Backend,
 {
+    if app.jobs.is_empty() {
+        draw_main_page(f, app, area);
+        return;
+    }
+
+    let jobs_height = app.jobs.list.len().min(MAX_JOBS_SHOWN) as u16 + 2;
     let chunks = Layout::default()
-        .constraints([Constraint::Percentage(100), Constraint::Percentage(25)].as_ref())
+        .constraints([Constraint::Min(0), Constraint::Length(jobs_height)].as_ref())
         .split(area);
     draw_main_page(f, app, chunks[0]);
+    draw_jobs(f, app, chunks[1]);
+}
+
+/// Frame times, the event queue and the last errors in the top right corner.
+fn draw_debug<B>(f: &mut Frame<B>, app: &App)
+where
+    B: Backend,
+{
+    let mut lines = vec![
+        format!(
+            "frame {:.1?} avg {:.1?} max {:.1?}",
+            app.debug.last_frame(),
+            app.debug.average_frame(),
+            app.debug.slowest_frame()
+        ),
+        format!("events queued {}", app.debug.queue_depth),
+    ];
+    let errors = logger::recent_errors();
+    if errors.is_empty() {
+        lines.push("no errors".to_string());
+    }
+    lines.extend(errors);
+
+    let size = f.size();
+    let width = (size.width / 2).max(30).min(size.width);
+    let height = (lines.len() as u16 + 2).min(size.height);
+    let area = Rect::new(size.width - width, 0, width, height);
+    let style: Style = app.config.app_colors.normal.clone().into();
+    let text = lines
+        .iter()
+        .map(|line| Text::styled(format!("{}\n", line), style))
+        .collect::<Vec<_>>();
+    Clear.render(f, area);
+    Paragraph::new(text.iter())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Debug")
+                .title_style(app.config.app_colors.titles.clone().into()),
+        )
+        .render(f, area);
+}
+
+fn draw_colors<B>(f: &mut Frame<B>, app: &App)
+where
+    B: Backend,
+{
+    let picker = match &app.colors {
+        Some(picker) => picker,
+        None => return,
+    };
+    let colors = &app.config.app_colors;
+    let color = picker.color(colors);
+    let mut lines = vec![
+        format!(
+            "{} {}",
+            colors::PARTS[picker.part],
+            if picker.bg {
+                "background"
+            } else {
+                "foreground"
+            }
+        ),
+        colors::name(color),
+    ];
+    match color {
+        AppColor::Rgb(r, g, b) => {
+            for (i, (channel, value)) in [('R', r), ('G', g), ('B', b)].iter().enumerate() {
+                let filled = *value as usize * SLIDER / 255;
+                lines.push(format!(
+                    "{} {} {}{} {}",
+                    if i == picker.channel { '>' } else { ' ' },
+                    channel,
+                    "█".repeat(filled),
+                    "░".repeat(SLIDER - filled),
+                    value
+                ));
+            }
+        }
+        _ => lines.push(String::new()),
+    }
+    lines.push(match colors::mode(color) {
+        Mode::Named => "←/→ color   m: indexed".to_string(),
+        Mode::Indexed => "←/→ color   m: RGB".to_string(),
+        Mode::Rgb => "↑/↓ channel   ←/→ value   m: named".to_string(),
+    });
+    lines.push(COLOR_HINT.to_string());
+
+    let normal: Style = colors.normal.clone().into();
+    let mut text = lines
+        .iter()
+        .map(|line| Text::styled(format!("{}\n", line), normal))
+        .collect::<Vec<_>>();
+    // what the style being changed looks like
+    text.insert(
+        2,
+        Text::styled(
+            " The quick brown fox \n",
+            colors::style(colors, picker.part).clone().into(),
+        ),
+    );
+    let area = centered_rect(60, text.len() as u16 + 2, f.size());
+    Clear.render(f, area);
+    Paragraph::new(text.iter())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Colors")
+                .title_style(colors.titles.clone().into()),
+        )
+        .render(f, area);
+}
+
+/// The terminal pane over most of the screen while it has focus.
+fn draw_pty<B>(f: &mut Frame<B>, app: &mut App)
+where
+    B: Backend,
+{
+    let pty = match &app.pty {
+        Some(pty) if app.pty_focus => pty,
+        _ => return,
+    };
+    let size = f.size();
+    let area = centered_rect(90, size.height.saturating_sub(4), size);
+    app.areas.pty = area;
+    let title = match pty.exited {
+        Some(status) => format!("{} ─ {} ─ F10: close", pty.cmd, status),
+        None => format!("{} ─ F10: back to forget", pty.cmd),
+    };
+    let rows = area.height.saturating_sub(2) as usize;
+    let lines = &pty.screen.lines;
+    let style: Style = app.config.app_colors.normal.clone().into();
+    let text = lines[lines.len().saturating_sub(rows)..]
+        .iter()
+        .map(|line| Text::styled(format!("{}\n", line), style))
+        .collect::<Vec<_>>();
+    Clear.render(f, area);
+    Paragraph::new(text.iter())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(&title)
+                .title_style(app.config.app_colors.titles.clone().into()),
+        )
+        .render(f, area);
+}
+
+fn draw_modal<B>(f: &mut Frame<B>, app: &App)
+where
+    B: Backend,
+{
+    let modal = match &app.modal {
+        Some(modal) => modal,
+        None => return,
+    };
+    let message = if modal.message.is_empty() { 0 } else { 2 };
+    let rows = modal.choices.len().max(modal.input.is_some() as usize);
+    let height = (rows as u16 + message + 2).min(f.size().height);
+    let area = centered_rect(60, height, f.size());
+    Dialog::new(modal)
+        .style(app.config.app_colors.normal.clone().into())
+        .highlight_style(app.config.app_colors.highlight.clone().into())
+        .render(f, area);
+}
+
+/// Every key and what it does in the middle of the screen, in as many
+/// columns as fit.
+fn draw_help<B>(f: &mut Frame<B>, app: &App)
+where
+    B: Backend,
+{
+    if !app.help {
+        return;
+    }
+    let lines = hints::help(&app.config);
+    let size = f.size();
+    let width = size.width * 90 / 100;
+    let column = lines.iter().map(|l| l.width()).max().unwrap_or(0) + 2;
+    let columns = (width.saturating_sub(2) as usize / column).max(1);
+    let rows = lines.len().div_ceil(columns);
+    let mut text = String::new();
+    for row in 0..rows {
+        for line in lines.iter().skip(row).step_by(rows) {
+            text.push_str(&format!(" {:<1$}", line, column - 1));
+        }
+        text.push('\n');
+    }
+    let area = centered_rect(90, rows as u16 + 2, size);
+    let style: Style = app.config.app_colors.normal.clone().into();
+    Clear.render(f, area);
+    Paragraph::new([Text::styled(text, style)].iter())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(HELP)
+                .title_style(app.config.app_colors.titles.clone().into()),
+        )
+        .render(f, area);
+}
+
+/// A rect `percent_x` of `area`'s width and `height` rows high in its middle.
+fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Stacks the toasts in the bottom right corner, newest at the bottom.
+fn draw_toasts<B>(f: &mut Frame<B>, app: &App)
+where
+    B: Backend,
+{
+    let size = f.size();
+    let mut bottom = size.height;
+    for toast in app.toasts.list.iter().rev() {
+        let mut width = (toast.text.width() as u16 + 4).min(size.width);
+        if toast.leaving {
+            // folds into the right edge, gone once the animation is done
+            match app.animations.progress(Anim::ToastsLeaving) {
+                Some(done) => width = (f64::from(width) * (1.0 - done)) as u16,
+                None => continue,
+            }
+        }
+        if bottom < 3 || width < 4 {
+            break;
+        }
+        let area = Rect::new(size.width - width, bottom - 3, width, 3);
+        bottom -= 3;
+
+        let style: Style = match toast.level {
+            Level::Info => app.config.app_colors.highlight.clone().into(),
+            Level::Error => app.config.app_colors.warning.clone().into(),
+        };
+        Clear.render(f, area);
+        Paragraph::new([Text::styled(format!(" {}", toast.text), style)].iter())
+            .block(Block::default().borders(Borders::ALL).border_style(style))
+            .render(f, area);
+    }
+}
+
+fn draw_activity<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let items = app
+        .activity
+        .filtered()
+        .iter()
+        .map(|a| {
+            format!(
+                "{}  {:<18} {}",
+                app.dates.date_time(&a.date),
+                a.action,
+                a.detail
+            )
+        })
+        .collect::<Vec<_>>();
+    let title = if app.activity.filter.is_empty() {
+        format!("Activity ({})", ACTIVITY_HINT)
+    } else {
+        format!("Activity matching \"{}\"", app.activity.filter)
+    };
+
+    SelectableList::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(&title)
+                .title_style(
+                    Style::default()
+                        .bg(app.config.app_colors.titles.bg.into())
+                        .fg(app.config.app_colors.titles.fg.into())
+                        .modifier(app.config.app_colors.titles.modifier.into()),
+                ),
+        )
+        .items(&items)
+        .select(Some(app.activity.selected))
+        .style(Style::default().fg(app.config.app_colors.text.fg.into()))
+        .highlight_style(app.config.app_colors.highlight.clone().into())
+        .render(f, area);
+}
+
+fn draw_agenda<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let agenda = match &app.agenda {
+        Some(agenda) => agenda,
+        None => return,
+    };
+    let titles = Style::from(app.config.app_colors.titles.clone());
+    let text = Style::default().fg(app.config.app_colors.text.fg.into());
+    let highlight = Style::from(app.config.app_colors.highlight.clone());
+
+    let now = Local::now();
+    let countdowns = agenda.countdowns(&app.sticky_note, app.config.countdown_days);
+    let week = agenda.week(&app.sticky_note, &app.dates);
+    let mut groups = Vec::new();
+    if !countdowns.is_empty() {
+        groups.push(("Coming up".to_string(), &countdowns, true));
+    }
+    for (day, entries) in week.iter() {
+        let heading = format!(
+            "{} {} {}",
+            app.dates.day_name(day.weekday()),
+            day.day(),
+            app.dates.month_name(*day)
+        );
+        groups.push((heading, entries, false));
+    }
+
+    let mut lines = Vec::new();
+    let mut selected_line = 0;
+    let mut entry = 0;
+    for (heading, entries, countdown) in groups {
+        lines.push(Text::styled(heading, titles));
+        for e in entries {
+            let line = if countdown {
+                format!("  {} — {} ({})", e.task, due::countdown(e.due, now), e.note)
+            } else {
+                format!(
+                    "  {}  {} ({})",
+                    app.dates.time(&Local.timestamp(e.due, 0)),
+                    e.task,
+                    e.note
+                )
+            };
+            let style = if entry == agenda.selected {
+                selected_line = lines.len();
+                highlight
+            } else if e.done {
+                app.config.app_colors.completed.clone().into()
+            } else if countdown {
+                app.config.app_colors.countdown.clone().into()
+            } else {
+                text
+            };
+            lines.push(Text::styled(line, style));
+            entry += 1;
+        }
+    }
+    // keep the selected todo in view
+    let height = area.height.saturating_sub(2) as usize;
+    let offset = (selected_line + 1).saturating_sub(height);
+
+    let start = week.first().map(|(day, _)| *day).unwrap_or(agenda.day);
+    let title = format!(
+        "Week of {} (←→ week, page up/down month, enter goes to the todo)",
+        app.dates.day(start)
+    );
+    List::new(lines.into_iter().skip(offset))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(&title)
+                .title_style(titles),
+        )
+        .render(f, area);
+}
+
+fn draw_jobs<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let text_style = Style::default().fg(app.config.app_colors.text.fg.into());
+    let items = app.jobs.list.iter().rev().take(MAX_JOBS_SHOWN).map(|job| {
+        let progress = job.progress();
+        let status = if progress.is_empty() {
+            job.status.to_string()
+        } else {
+            format!("{} {}", job.status, progress)
+        };
+        Text::styled(
+            format!("[{}] {} -> {}", status, job.label, job.current_cmd()),
+            text_style,
+        )
+    });
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(&format!("Jobs ({} running)", app.jobs.running()))
+                .title_style(
+                    Style::default()
+                        .bg(app.config.app_colors.titles.bg.into())
+                        .fg(app.config.app_colors.titles.fg.into())
+                        .modifier(app.config.app_colors.titles.modifier.into()),
+                ),
+        )
+        .render(f, area);
+}
+
+/// How often and when the todo's command ran, `None` when it has no command.
+fn run_summary(remind: &Remind, todo: &Todo, dates: &Dates) -> Option<String> {
+    remind.command_for(todo)?;
+    Some(match todo.last_run {
+        Some(last) if todo.runs > 0 => format!("ran {}× last {}", todo.runs, dates.timestamp(last)),
+        _ => "never run".to_string(),
+    })
+}
+
+/// Where the todo was last changed, only when that was on another machine.
+fn provenance(app: &App, todo: &Todo) -> Option<String> {
+    let edited = todo.edited.as_ref()?;
+    if edited.device == app.config.device_name {
+        return None;
+    }
+    Some(format!("edited on {} {}", edited.device, ago(edited.at)))
+}
+
+/// How long ago the unix time `at` was, like `2h ago`.
+fn ago(at: i64) -> String {
+    let secs = (Local::now().timestamp() - at).max(0) as u64;
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=86_399 => format!("{} ago", Duration(secs)),
+        _ => format!("{}d ago", secs / 86_400),
+    }
 }
 
 fn draw_main_page<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
@@ -63,22 +701,230 @@ where
         .direction(Direction::Horizontal)
         .split(area);
 
+    app.areas.main = chunks[0];
+    let split = app.split();
     let chunks = Layout::default()
-        .constraints([Constraint::Percentage(65), Constraint::Percentage(45)].as_ref())
+        .constraints(
+            [
+                Constraint::Percentage(split),
+                Constraint::Percentage(100 - split),
+            ]
+            .as_ref(),
+        )
         .direction(Direction::Horizontal)
         .split(chunks[0]);
 
-    let (todo, selected) = if let Some(todo) = app.sticky_note.items.get(app.tabs.index) {
-        (todo.clone(), todo.list.selected)
+    let board = app
+        .sticky_note
+        .items
+        .get(app.tabs.index)
+        .is_some_and(|n| n.board);
+    let goal = app
+        .sticky_note
+        .items
+        .get(app.tabs.index)
+        .is_some_and(|n| n.goal);
+    // a goal's progress goes above its todos
+    let (gauge, list) = if goal {
+        let chunks = Layout::default()
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .direction(Direction::Vertical)
+            .split(chunks[0]);
+        (Some(chunks[0]), chunks[1])
+    } else {
+        (None, chunks[0])
+    };
+    // the last deletion shows under the todos for a moment
+    let (list, peek) = if app.deleted.peek().is_some() {
+        let chunks = Layout::default()
+            .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .direction(Direction::Vertical)
+            .split(list);
+        (chunks[0], Some(chunks[1]))
     } else {
-        (Remind::default(), 0)
+        (list, None)
+    };
+    app.areas.list = if board { Rect::default() } else { list };
+    app.areas.notes = chunks[1];
+
+    // borrowed, cloning the sticky note every frame is slow for long notes
+    let app: &App = app;
+    let empty = Remind::default();
+    let todo = app.sticky_note.items.get(app.tabs.index).unwrap_or(&empty);
+    let selected = todo.list.selected;
+
+    let mut title = match todo.list.get_selected() {
+        Some(selected) => {
+            let mut title = match run_summary(todo, selected, &app.dates) {
+                Some(runs) => format!("{} #{} · {}", todo.title, selected.id, runs),
+                None => format!("{} #{}", todo.title, selected.id),
+            };
+            if let Some(edited) = provenance(app, selected) {
+                title = format!("{} · {}", title, edited);
+            }
+            title
+        }
+        None => todo.title.clone(),
+    };
+    let remaining = todo.remaining();
+    if !remaining.is_empty() {
+        title = format!("{} · {}", title, remaining);
+    }
+    let open_ids = app.open_ids();
+
+    if let Some(area) = gauge {
+        draw_goal(f, app, todo, area);
+    }
+    if let Some(area) = peek {
+        draw_deleted(f, app, area);
+    }
+    if board {
+        draw_board(f, app, todo, &title, &open_ids, list);
+    } else {
+        let wip = todo.wip_exceeded();
+        if let Some((doing, limit)) = wip {
+            title = format!("{} {} {}/{} in progress", title, WIP_WARNING, doing, limit);
+        }
+        let drop_target = match app.drag.and_then(|d| d.over) {
+            Some(DropTarget::Row(row)) => Some(row),
+            _ => None,
+        };
+        todo_list(app, todo, &title, Some(selected), &open_ids, wip.is_some())
+            .drop_target(drop_target)
+            .render(f, list);
+        if !app.editing() {
+            let hint = hints::list(
+                &app.config,
+                app.sticky_note.is_empty(),
+                app.current_read_only(),
+            );
+            draw_hint(f, app, &hint, list, 0);
+        }
+    }
+
+    draw_util_block(f, app, chunks[1])
+}
+
+/// What was deleted last and how to get it back.
+fn draw_deleted<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let (deleted, more) = match app.deleted.peek() {
+        Some(peek) => peek,
+        None => return,
     };
+    let mut line = match hints::undelete(&app.config) {
+        Some(hint) => format!("Deleted the {}, {}", deleted.describe(), hint),
+        None => format!("Deleted the {}", deleted.describe()),
+    };
+    if more > 0 {
+        line = format!("{} ({} more)", line, more);
+    }
+    let text = [Text::styled(
+        line,
+        Style::default().fg(app.config.app_colors.text.fg.into()),
+    )];
+    Paragraph::new(text.iter()).render(f, area);
+}
 
-    TodoList::new(&todo)
+/// How far along a goal sticky note is, weighted by its todos' weights.
+fn draw_goal<B>(f: &mut Frame<B>, app: &App, remind: &Remind, area: Rect)
+where
+    B: Backend,
+{
+    let (done, total) = remind.goal_progress();
+    let ratio = if total == 0 {
+        0.0
+    } else {
+        done as f64 / total as f64
+    };
+    let label = format!("{:.0}% ({} of {})", ratio * 100.0, done, total);
+    Gauge::default()
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(&todo.title)
+                .title("Goal")
+                .title_style(app.config.app_colors.titles.clone().into()),
+        )
+        .style(app.config.app_colors.highlight.clone().into())
+        .ratio(ratio)
+        .label(&label)
+        .render(f, area);
+}
+
+/// A column for every status of a sticky note's todos, the selected todo's
+/// column gets the sticky note's title.
+fn draw_board<B>(
+    f: &mut Frame<B>,
+    app: &App,
+    remind: &Remind,
+    title: &str,
+    open_ids: &HashSet<u64>,
+    area: Rect,
+) where
+    B: Backend,
+{
+    let columns = Status::ALL.len() as u32;
+    let chunks = Layout::default()
+        .constraints(
+            Status::ALL
+                .iter()
+                .map(|_| Constraint::Ratio(1, columns))
+                .collect::<Vec<_>>(),
+        )
+        .direction(Direction::Horizontal)
+        .split(area);
+
+    let selected = remind.list.get_selected().map(|t| t.status);
+    for (status, area) in Status::ALL.iter().zip(chunks) {
+        let items = remind
+            .list
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.status == *status)
+            .collect::<Vec<_>>();
+        let column = Remind {
+            list: ListState::new(items.iter().map(|(_, t)| (*t).clone()).collect()),
+            // the board always has a column for done todos
+            hide_done: false,
+            ..remind.clone()
+        };
+        let (mut title, select) = if selected == Some(*status) {
+            let idx = items.iter().position(|(i, _)| *i == remind.list.selected);
+            (format!("{} ─ {}", status.as_str(), title), idx)
+        } else {
+            (status.as_str().to_string(), None)
+        };
+        let wip = remind
+            .wip_exceeded()
+            .filter(|_| *status == Status::InProgress);
+        if let Some((doing, limit)) = wip {
+            title = format!("{} {} {}/{}", title, WIP_WARNING, doing, limit);
+        }
+        todo_list(app, &column, &title, select, open_ids, wip.is_some()).render(f, area);
+    }
+}
+
+fn todo_list<'a>(
+    app: &'a App,
+    remind: &'a Remind,
+    title: &'a str,
+    selected: Option<usize>,
+    open_ids: &'a HashSet<u64>,
+    warning: bool,
+) -> TodoList<'a> {
+    let border_style = if warning {
+        app.config.app_colors.warning.clone().into()
+    } else {
+        Style::default()
+    };
+    TodoList::new(remind)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style)
+                .title(title)
                 .title_style(
                     Style::default()
                         .bg(app.config.app_colors.titles.bg.into())
@@ -86,7 +932,7 @@ where
                         .modifier(app.config.app_colors.titles.modifier.into()),
                 ),
         )
-        .select(Some(selected))
+        .select(selected)
         .style(
             Style::default()
                 .bg(app.config.app_colors.text.bg.into())
@@ -99,133 +945,271 @@ where
                 .bg(app.config.app_colors.highlight.bg.into())
                 .modifier(app.config.app_colors.highlight.modifier.into()),
         )
-        .highlight_symbol(&app.config.highlight_string)
+        .highlight_symbol(
+            app.config
+                .note_highlights
+                .get(&remind.title)
+                .unwrap_or(&app.config.highlight_string),
+        )
         .cmd_symbol(&app.config.command_string)
-        .render(f, chunks[0]);
-
-    draw_util_block(f, app, chunks[1])
+        .cmd_style(app.config.app_colors.command.clone().into())
+        .countdown_style(app.config.app_colors.countdown.clone().into())
+        .habit_days(app.config.habit_grid_days)
+        .status_styles(
+            status_style(&app.config.app_colors.completed, app.config.strikethrough),
+            status_style(&app.config.app_colors.pending, app.config.strikethrough),
+        )
+        .rules(&app.rules)
+        .blocked(open_ids, &app.config.blocked_string)
 }
 
-fn draw_util_block<B>(f: &mut Frame<B>, app: &App, area: Rect)
+/// The files attached to the sticky note, missing ones are marked.
+fn draw_attachments<B>(f: &mut Frame<B>, app: &App, attachments: &[String], area: Rect)
 where
     B: Backend,
 {
-    let highlight_style = app.config.app_colors.highlight.clone().into();
-    let normal_style: Style = app.config.app_colors.normal.clone().into();
-
-    if app.new_reminder {
-        let remind_title = &app.add_remind.title;
-
-        Paragraph::new(
-            vec![Text::styled(
-                remind_title,
-                Style::default().fg(app.config.app_colors.text.fg.into()),
-            )]
-            .iter(),
-        )
+    let normal: Style = app.config.app_colors.text.clone().into();
+    let warning: Style = app.config.app_colors.warning.clone().into();
+    let items = attachments.iter().map(|path| {
+        if attach::exists(path) {
+            Text::styled(format!("📎 {}", path), normal)
+        } else {
+            Text::styled(format!("{} {} (missing)", WIP_WARNING, path), warning)
+        }
+    });
+    List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(highlight_style)
-                .title(ADD_REMIND)
-                .title_style(
-                    Style::default()
-                        .bg(app.config.app_colors.titles.bg.into())
-                        .fg(app.config.app_colors.titles.fg.into())
-                        .modifier(highlight_style.modifier),
-                ),
+                .title("Attachments")
+                .title_style(app.config.app_colors.titles.clone().into()),
         )
-        .wrap(true)
         .render(f, area);
-    } else if app.new_todo || app.edit_todo {
-        let task = &app.add_todo.task;
-        let cmd = &app.add_todo.cmd;
-        let question = app.add_todo.question_index;
+}
+
+/// Drops the strikethrough when the terminal can't draw it.
+fn status_style(style: &AppStyle, strikethrough: bool) -> Style {
+    let style: Style = style.clone().into();
+    if strikethrough {
+        style
+    } else {
+        style.modifier(style.modifier - Modifier::CROSSED_OUT)
+    }
+}
+
+fn draw_util_block<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let highlight_style: Style = app.config.app_colors.highlight.clone().into();
+    let normal_style: Style = app.config.app_colors.normal.clone().into();
+
+    if app.new_reminder || app.edit_reminder {
+        let question = app.add_remind.question_index;
 
         let chunks = Layout::default()
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .constraints(
+                [
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(30),
+                    Constraint::Percentage(30),
+                ]
+                .as_ref(),
+            )
             .direction(Direction::Vertical)
             .split(area);
 
-        let style = if question == 0 {
-            highlight_style
+        let title = if app.edit_reminder {
+            EDIT_REMIND
         } else {
-            normal_style
+            ADD_REMIND
         };
-        Paragraph::new(
-            vec![Text::styled(
-                task,
-                Style::default().fg(app.config.app_colors.text.fg.into()),
-            )]
-            .iter(),
-        )
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(style)
-                .title(ADD_TODO)
-                .title_style(
-                    Style::default()
-                        .bg(app.config.app_colors.titles.bg.into())
-                        .fg(app.config.app_colors.titles.fg.into())
-                        .modifier(style.modifier),
-                ),
-        )
-        .wrap(true)
-        .render(f, chunks[0]);
+        draw_input(
+            f,
+            app,
+            title,
+            &app.add_remind.title,
+            question == 0,
+            chunks[0],
+        );
+        let (title, text) = if app.add_remind.smart {
+            (ADD_QUERY, &app.add_remind.query)
+        } else {
+            (ADD_DEFAULT_CMD, &app.add_remind.cmd)
+        };
+        if app.add_remind.smart {
+            draw_input(f, app, title, text, question == 1, chunks[1]);
+        } else {
+            draw_cmd_input(f, app, title, text, question == 1, chunks[1]);
+        }
+        draw_input(
+            f,
+            app,
+            ADD_WIP_LIMIT,
+            &app.add_remind.wip_limit,
+            question == 2,
+            chunks[2],
+        );
+        let open_key = if app.edit_reminder {
+            app.config.edit_sticky_note_char_ctrl
+        } else if app.add_remind.smart {
+            app.config.new_smart_note_char_ctrl
+        } else {
+            app.config.new_sticky_note_char_ctrl
+        };
+        draw_hint(f, app, &hints::form(&app.config, open_key), chunks[2], 0);
+    } else if app.new_todo || app.edit_todo {
+        let question = app.add_todo.question_index;
 
-        let style = if question == 1 {
-            highlight_style
+        let chunks = Layout::default()
+            .constraints(
+                [
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(12),
+                    Constraint::Percentage(12),
+                    Constraint::Percentage(12),
+                    Constraint::Percentage(12),
+                    Constraint::Percentage(12),
+                ]
+                .as_ref(),
+            )
+            .direction(Direction::Vertical)
+            .split(area);
+
+        let task = spelled(app, &app.add_todo.task, text_style(app));
+        Paragraph::new(task.iter())
+            .block(input_block(app, ADD_TODO, question == 0))
+            .wrap(true)
+            .render(f, chunks[0]);
+        draw_cmd_input(f, app, ADD_CMD, &app.add_todo.cmd, question == 1, chunks[1]);
+        draw_input(
+            f,
+            app,
+            ADD_BLOCKED_BY,
+            &app.add_todo.blocked_by,
+            question == 2,
+            chunks[2],
+        );
+        draw_input(
+            f,
+            app,
+            ADD_ESTIMATE,
+            &app.add_todo.estimate,
+            question == 3,
+            chunks[3],
+        );
+        draw_input(f, app, ADD_DUE, &app.add_todo.due, question == 4, chunks[4]);
+        draw_input(
+            f,
+            app,
+            ADD_REPORT,
+            &app.add_todo.report,
+            question == 5,
+            chunks[5],
+        );
+        let open_key = if app.edit_todo {
+            app.config.edit_todo_char_ctrl
         } else {
-            normal_style
+            app.config.new_todo_char_ctrl
         };
-        Paragraph::new(
-            vec![Text::styled(
-                cmd,
-                Style::default().fg(app.config.app_colors.text.fg.into()),
-            )]
-            .iter(),
-        )
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(style)
-                .title(ADD_CMD)
-                .title_style(
-                    Style::default()
-                        .bg(app.config.app_colors.titles.bg.into())
-                        .fg(app.config.app_colors.titles.fg.into())
-                        .modifier(style.modifier),
-                ),
-        )
-        .wrap(true)
-        .render(f, chunks[1]);
+        draw_hint(f, app, &hints::form(&app.config, open_key), chunks[5], 0);
+    } else if app.runbook {
+        draw_runbook(f, app, area);
+    } else if app.passphrase {
+        let add = &app.add_passphrase;
+        let masked = |pass: &str| "*".repeat(pass.chars().count());
+        let title = match &add.error {
+            Some(err) => format!("{} ({})", PASSPHRASE, err),
+            None => PASSPHRASE.to_string(),
+        };
+        if app.current_locked() {
+            draw_input(f, app, &title, &masked(&add.pass), true, area);
+        } else {
+            let chunks = Layout::default()
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .direction(Direction::Vertical)
+                .split(area);
+            draw_input(
+                f,
+                app,
+                &title,
+                &masked(&add.pass),
+                !add.confirming,
+                chunks[0],
+            );
+            draw_input(
+                f,
+                app,
+                CONFIRM_PASSPHRASE,
+                &masked(&add.confirm),
+                add.confirming,
+                chunks[1],
+            );
+        }
+    } else if app.current_locked() {
+        draw_input(f, app, LOCKED, LOCKED_HINT, false, area);
     } else {
-        let style = if app.new_note {
+        let style = if app.new_note || app.notes_focus {
             highlight_style
         } else {
             normal_style
         };
-        let note = &app
-            .sticky_note
-            .items
-            .get(app.tabs.index)
-            .map(|n| n.note.clone())
-            .unwrap_or_default();
-        let text = Text::styled(
-            note,
-            Style::default().fg(app.config.app_colors.text.fg.into()),
-        );
-        Paragraph::new(vec![text].iter())
+        let remind = app.sticky_note.items.get(app.tabs.index);
+        let note = remind.map(|n| n.note.as_str()).unwrap_or_default();
+        let (width, height) = (area.width.saturating_sub(2), area.height.saturating_sub(2));
+        let first = match remind {
+            Some(n) if app.find.is_none() && (app.new_note || n.follow_tail) => {
+                n.note_tail(width, height)
+            }
+            Some(n) => n.note_scroll,
+            None => 0,
+        };
+        let lines = note.split('\n').count();
+        let title = match (app.new_note, first) {
+            (true, _) => "Add To Notes".to_string(),
+            (false, 0) => "Notes".to_string(),
+            (false, first) => format!("Notes (line {} of {})", first + 1, lines),
+        };
+        let attachments = remind.map(|n| n.attachments.as_slice()).unwrap_or_default();
+        let area = if attachments.is_empty() {
+            area
+        } else {
+            let rows = attachments.len().min(MAX_ATTACHMENTS_SHOWN) as u16 + 2;
+            let chunks = Layout::default()
+                .constraints([Constraint::Min(0), Constraint::Length(rows)].as_ref())
+                .direction(Direction::Vertical)
+                .split(area);
+            draw_attachments(f, app, attachments, chunks[1]);
+            chunks[0]
+        };
+        let (area, find_area) = match app.find {
+            Some(_) => {
+                let chunks = Layout::default()
+                    .constraints([Constraint::Min(0), Constraint::Length(4)].as_ref())
+                    .direction(Direction::Vertical)
+                    .split(area);
+                (chunks[0], Some(chunks[1]))
+            }
+            None => (area, None),
+        };
+        let full = note;
+        let note = window(note, first, height as usize);
+        // the raw text is edited, the Markdown is only rendered when reading
+        let spans;
+        let text = if let Some(find) = &app.find {
+            found(app, find, full, note)
+        } else if app.new_note {
+            spelled(app, note, text_style(app))
+        } else {
+            spans = markdown::parse(note);
+            note_text(app, &spans)
+        };
+        Paragraph::new(text.iter())
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_style(style)
-                    .title(if app.new_note {
-                        "Add To Notes"
-                    } else {
-                        "Notes"
-                    })
+                    .title(&title)
                     .title_style(
                         Style::default()
                             .bg(app.config.app_colors.titles.bg.into())
@@ -235,5 +1219,597 @@ where
             )
             .wrap(true)
             .render(f, area);
+        if let Some(remind) = remind {
+            if app.new_note {
+                let footer = note_footer(remind).map_or(0, |footer| footer.width());
+                draw_hint(f, app, &hints::notes(&app.config), area, footer);
+            }
+            draw_note_footer(f, app, remind, area);
+        }
+        if let (Some(find), Some(find_area)) = (&app.find, find_area) {
+            draw_find(f, app, find, full, find_area);
+        }
+    }
+}
+
+/// As many of `hints` as fit on the left of the bottom border of `area`,
+/// leaving `reserved` columns on the right for a footer.
+fn draw_hint<B>(f: &mut Frame<B>, app: &App, hints: &[String], area: Rect, reserved: usize)
+where
+    B: Backend,
+{
+    if !app.config.key_hints || area.height < 3 {
+        return;
+    }
+    let room = (area.width as usize).saturating_sub(3 + reserved);
+    let mut line = String::new();
+    for hint in hints {
+        let longer = if line.is_empty() {
+            format!(" {} ", hint)
+        } else {
+            format!("{}  {} ", line, hint)
+        };
+        if longer.width() > room {
+            break;
+        }
+        line = longer;
+    }
+    if line.is_empty() {
+        return;
+    }
+    let rect = Rect::new(area.x + 1, area.y + area.height - 1, line.width() as u16, 1);
+    let style = Style::from(app.config.app_colors.normal.clone()).modifier(Modifier::DIM);
+    Paragraph::new([Text::styled(line, style)].iter()).render(f, rect);
+}
+
+/// Word and character counts, when the note was changed and who shared it.
+fn note_footer(remind: &Remind) -> Option<String> {
+    let mut parts = Vec::new();
+    if !remind.note.is_empty() {
+        parts.push(format!("{} words", remind.note.split_whitespace().count()));
+        parts.push(format!("{} chars", remind.note.chars().count()));
+        if let Some(edited) = remind.note_edited {
+            parts.push(format!("edited {}", ago(edited)));
+        }
+    }
+    if let Some(shared) = &remind.shared {
+        parts.push(format!("shared by {} {}", shared.from, ago(shared.at)));
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some(format!(" {} ", parts.join(" · ")))
+}
+
+/// The `note_footer` in the notes' bottom border.
+fn draw_note_footer<B>(f: &mut Frame<B>, app: &App, remind: &Remind, area: Rect)
+where
+    B: Backend,
+{
+    let footer = match note_footer(remind) {
+        Some(footer) if area.height >= 3 => footer,
+        _ => return,
+    };
+    let width = (footer.width() as u16).min(area.width.saturating_sub(2));
+    let rect = Rect::new(
+        area.x + area.width - 1 - width,
+        area.y + area.height - 1,
+        width,
+        1,
+    );
+    Paragraph::new([Text::styled(footer, text_style(app))].iter()).render(f, rect);
+}
+
+/// `shown`, a window into `note`, with the matches of `find` highlighted.
+fn found<'t>(app: &App, find: &Find, note: &str, shown: &'t str) -> Vec<Text<'t>> {
+    let style = text_style(app);
+    let selected = find.selected(note);
+    // where `shown` starts in `note`
+    let offset = shown.as_ptr() as usize - note.as_ptr() as usize;
+    let mut text = Vec::new();
+    let mut end = 0;
+    for range in find::matches(shown, &find.query) {
+        if range.start > end {
+            text.push(Text::styled(&shown[end..range.start], style));
+        }
+        let current = selected
+            .as_ref()
+            .is_some_and(|s| s.start == range.start + offset);
+        let modifier = if current {
+            Modifier::REVERSED | Modifier::BOLD
+        } else {
+            Modifier::REVERSED
+        };
+        text.push(Text::styled(
+            &shown[range.clone()],
+            style.modifier(modifier),
+        ));
+        end = range.end;
+    }
+    if end < shown.len() || text.is_empty() {
+        text.push(Text::styled(&shown[end..], style));
+    }
+    text
+}
+
+/// The find and replace fields under the notes.
+fn draw_find<B>(f: &mut Frame<B>, app: &App, find: &Find, note: &str, area: Rect)
+where
+    B: Backend,
+{
+    let count = find::matches(note, &find.query).len();
+    let title = match count {
+        0 => FIND.to_string(),
+        _ => format!("{} {} of {}", FIND, find.current.min(count - 1) + 1, count),
+    };
+    let field = |label: &str, text: &str, focused: bool| {
+        let style = if focused {
+            app.config.app_colors.highlight.clone().into()
+        } else {
+            text_style(app)
+        };
+        Text::styled(format!("{}{}\n", label, text), style)
+    };
+    let text = [
+        field("Find:    ", &find.query, !find.replacing),
+        field("Replace: ", &find.replace, find.replacing),
+    ];
+    Paragraph::new(text.iter())
+        .block(input_block(app, &title, true))
+        .render(f, area);
+}
+
+/// The `height` lines of `text` from line `first` on, every line takes at least
+/// a row so the rest can't be seen and isn't worth styling.
+fn window(text: &str, first: usize, height: usize) -> &str {
+    let start = match first {
+        0 => 0,
+        _ => text
+            .match_indices('\n')
+            .nth(first - 1)
+            .map(|(i, _)| i + 1)
+            .unwrap_or(text.len()),
+    };
+    let rest = &text[start..];
+    let end = rest
+        .match_indices('\n')
+        .nth(height.saturating_sub(1))
+        .map(|(i, _)| i)
+        .unwrap_or(rest.len());
+    &rest[..end]
+}
+
+/// Hides everything behind a passphrase prompt in the middle of the screen.
+/// Drawn instead of everything else until the terminal is made bigger.
+fn draw_too_small<B>(f: &mut Frame<B>, app: &mut App)
+where
+    B: Backend,
+{
+    // nothing on screen can be clicked
+    app.areas = Areas::default();
+    let size = f.size();
+    let text = [
+        Text::styled(
+            "Terminal too small\n",
+            Style::from(app.config.app_colors.titles.clone()),
+        ),
+        Text::raw(format!(
+            "{}x{}, needs {}x{}",
+            size.width, size.height, MIN_WIDTH, MIN_HEIGHT
+        )),
+    ];
+    let area = Rect::new(
+        size.x,
+        size.y + size.height.saturating_sub(2) / 2,
+        size.width,
+        size.height.min(2),
+    );
+    Paragraph::new(text.iter())
+        .alignment(Alignment::Center)
+        .wrap(true)
+        .render(f, area);
+}
+
+/// The current sticky note over the whole screen with room around every
+/// todo, for sharing a checklist in a meeting.
+fn draw_present<B>(f: &mut Frame<B>, app: &App)
+where
+    B: Backend,
+{
+    let (remind, at) = match (app.sticky_note.items.get(app.tabs.index), app.present) {
+        (Some(remind), Some(at)) => (remind, at),
+        _ => return,
+    };
+    let size = f.size();
+    let margin = size.width / 10;
+    let area = Rect::new(
+        size.x + margin,
+        size.y + 1,
+        size.width - 2 * margin,
+        size.height.saturating_sub(2),
+    );
+    let colors = &app.config.app_colors;
+    let title = Style::from(colors.titles.clone()).modifier(Modifier::BOLD);
+    let normal = Style::from(colors.normal.clone());
+    let done = Style::from(colors.completed.clone());
+    let highlight = Style::from(colors.highlight.clone()).modifier(Modifier::BOLD);
+
+    // every todo takes two rows, the highlighted one is kept in view
+    let rows = (area.height.saturating_sub(3) / 2).max(1) as usize;
+    let first = (at + 1).saturating_sub(rows);
+    let mut text = vec![Text::styled(format!("{}\n\n", remind.title), title)];
+    for (idx, todo) in remind.list.iter().enumerate().skip(first).take(rows) {
+        // nobody steps through a kiosk
+        let (marker, style) = if idx == at && app.kiosk.is_none() {
+            ("▶", highlight)
+        } else if todo.status.is_closed() {
+            (" ", done)
+        } else {
+            (" ", normal)
+        };
+        let line = format!(
+            "{} [{}] {}\n\n",
+            marker,
+            checklist::marker(todo.status),
+            todo.task
+        );
+        text.push(Text::styled(line, style));
+    }
+    Paragraph::new(text.iter()).render(f, area);
+
+    let count = match &app.kiosk {
+        Some(kiosk) => format!(" {} {}s ", RELOAD, kiosk.left()),
+        None => format!(
+            " {}/{} ",
+            (at + 1).min(remind.list.len()),
+            remind.list.len()
+        ),
+    };
+    let width = (count.width() as u16).min(size.width);
+    let rect = Rect::new(
+        size.x + size.width - width,
+        size.y + size.height - 1,
+        width,
+        1,
+    );
+    Paragraph::new([Text::styled(count, normal)].iter()).render(f, rect);
+    if app.kiosk.is_none() {
+        draw_hint(f, app, &hints::present(&app.config), size, 10);
+    }
+}
+
+fn draw_lock_screen<B>(f: &mut Frame<B>, app: &App)
+where
+    B: Backend,
+{
+    let size = f.size();
+    let width = size.width.min(50);
+    let area = Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + size.height.saturating_sub(3) / 2,
+        width,
+        size.height.min(3),
+    );
+
+    let masked = "*".repeat(app.unlock.pass.chars().count());
+    let title = match &app.unlock.error {
+        Some(err) => format!("{} {} ({})", LOCKED, app.title, err),
+        None => format!("{} {}", LOCKED, app.title),
+    };
+    draw_input(f, app, &title, &masked, true, area);
+}
+
+fn draw_runbook<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let highlight_style: Style = app.config.app_colors.highlight.clone().into();
+    let text_style = Style::default().fg(app.config.app_colors.text.fg.into());
+
+    let remind = match app.sticky_note.items.get(app.tabs.index) {
+        Some(remind) => remind,
+        None => return,
+    };
+    let step = remind.list.selected + 1;
+    let title = format!("Runbook step {} of {}", step, remind.list.len());
+
+    let todo = remind.list.get_selected();
+    let cmd = todo.and_then(|todo| remind.command_for(todo));
+    let mut text = Vec::new();
+    if let Some(todo) = todo {
+        text.push(Text::styled(format!("{}\n\n", todo.task), highlight_style));
+        if let Some(runs) = run_summary(remind, todo, &app.dates) {
+            text.push(Text::styled(format!("{}\n", runs), text_style));
+        }
+    }
+    if let Some(cmd) = &cmd {
+        text.push(Text::styled("$ ", text_style));
+        text.extend(highlighted(app, cmd, text_style));
+        text.push(Text::raw("\n\n"));
+    }
+    text.push(Text::styled(RUNBOOK_HINT, text_style));
+
+    Paragraph::new(text.iter())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(highlight_style)
+                .title(&title)
+                .title_style(
+                    Style::default()
+                        .bg(app.config.app_colors.titles.bg.into())
+                        .fg(app.config.app_colors.titles.fg.into())
+                        .modifier(highlight_style.modifier),
+                ),
+        )
+        .wrap(true)
+        .render(f, area);
+}
+
+/// Draws a single question of a form, `focused` questions are highlighted.
+fn draw_input<B>(f: &mut Frame<B>, app: &App, title: &str, text: &str, focused: bool, area: Rect)
+where
+    B: Backend,
+{
+    Paragraph::new([Text::styled(text, text_style(app))].iter())
+        .block(input_block(app, title, focused))
+        .wrap(true)
+        .render(f, area);
+}
+
+/// A command question, the command is highlighted.
+fn draw_cmd_input<B>(f: &mut Frame<B>, app: &App, title: &str, cmd: &str, focused: bool, area: Rect)
+where
+    B: Backend,
+{
+    Paragraph::new(highlighted(app, cmd, text_style(app)).iter())
+        .block(input_block(app, title, focused))
+        .wrap(true)
+        .render(f, area);
+}
+
+/// `cmd` split into the program, flags, strings, operators and variables.
+fn highlighted<'t>(app: &App, cmd: &'t str, base: Style) -> Vec<Text<'t>> {
+    let colors = &app.config.cmd_colors;
+    shell::tokens(cmd)
+        .into_iter()
+        .map(|(range, kind)| {
+            let style: Style = match kind {
+                Kind::Program => colors.program.clone().into(),
+                Kind::Flag => colors.flag.clone().into(),
+                Kind::Str => colors.string.clone().into(),
+                Kind::Operator => colors.operator.clone().into(),
+                Kind::Variable => colors.variable.clone().into(),
+                Kind::Word | Kind::Space => base,
+            };
+            Text::styled(&cmd[range], style)
+        })
+        .collect()
+}
+
+fn input_block<'t>(app: &App, title: &'t str, focused: bool) -> Block<'t> {
+    let style: Style = if focused {
+        app.config.app_colors.highlight.clone().into()
+    } else {
+        app.config.app_colors.normal.clone().into()
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(style)
+        .title(title)
+        .title_style(
+            Style::default()
+                .bg(app.config.app_colors.titles.bg.into())
+                .fg(app.config.app_colors.titles.fg.into())
+                .modifier(style.modifier),
+        )
+}
+
+fn text_style(app: &App) -> Style {
+    Style::default().fg(app.config.app_colors.text.fg.into())
+}
+
+fn note_text<'t>(app: &App, spans: &'t [markdown::Span]) -> Vec<Text<'t>> {
+    let mut text = Vec::new();
+    for span in spans {
+        let mut style = text_style(app);
+        if span.bold {
+            style = style.modifier(style.modifier | Modifier::BOLD);
+        }
+        if span.italic {
+            style = style.modifier(style.modifier | Modifier::ITALIC);
+        }
+        if span.code {
+            style = style.fg(app.config.app_colors.tabs.fg.into());
+            text.push(Text::styled(span.text.as_str(), style));
+        } else {
+            text.extend(spelled(app, &span.text, style));
+        }
+    }
+    text
+}
+
+/// `text` split so misspelled words are underlined in the warning color.
+fn spelled<'t>(app: &App, text: &'t str, style: Style) -> Vec<Text<'t>> {
+    let dictionary = match &app.dictionary {
+        Some(dictionary) => dictionary,
+        None => return vec![Text::styled(text, style)],
+    };
+    let misspelled = style
+        .fg(app.config.app_colors.warning.fg.into())
+        .modifier(style.modifier | Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut end = 0;
+    for word in dictionary.misspelled(text) {
+        if word.start > end {
+            spans.push(Text::styled(&text[end..word.start], style));
+        }
+        spans.push(Text::styled(&text[word.clone()], misspelled));
+        end = word.end;
+    }
+    if end < text.len() || spans.is_empty() {
+        spans.push(Text::styled(&text[end..], style));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use termion::event::Key;
+    use tui::backend::TestBackend;
+
+    use super::*;
+    use crate::activity::ActivityLog;
+    use crate::agenda::Agenda;
+    use crate::config;
+
+    /// The sizes every screen is drawn at, the smallest is about the least
+    /// anyone uses.
+    const SIZES: [(u16, u16); 3] = [(40, 12), (80, 24), (140, 40)];
+
+    /// The sticky notes a new install starts with and the default config.
+    fn app() -> App {
+        let notes = config::APP.with(|app| app.clone());
+        let config = config::CFG.with(|cfg| cfg.clone());
+        let mut app = App::with_notes(notes, config).unwrap();
+        // nothing is written to the real activity log
+        app.activity = ActivityLog::default();
+        app
+    }
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            app.on_key_press(Key::Char(c));
+        }
+    }
+
+    fn render(app: &mut App, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        draw(&mut terminal, app).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(width as usize)
+            .map(|row| row.iter().map(|c| c.symbol.as_str()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Draws each size of the screen `setup` makes and compares it with its
+    /// snapshot in `src/snapshots`, `cargo insta review` takes new ones.
+    fn snapshot<F: Fn(&mut App)>(name: &str, setup: F) {
+        for (width, height) in SIZES.iter() {
+            let mut app = app();
+            setup(&mut app);
+            let screen = render(&mut app, *width, *height);
+            insta::assert_snapshot!(format!("{}_{}x{}", name, width, height), screen);
+        }
+    }
+
+    #[test]
+    fn main_view() {
+        snapshot("main", |_| {});
+    }
+
+    #[test]
+    fn add_todo_form() {
+        snapshot("add_todo", |app| {
+            app.on_key_press(Key::Ctrl(app.config.new_todo_char_ctrl));
+            press(app, "water the plants");
+        });
+    }
+
+    #[test]
+    fn notes_pane() {
+        snapshot("notes", |app| {
+            app.on_key_press(Key::Ctrl(app.config.new_note_char_ctrl));
+            press(app, " Then **bold** and *italics*.");
+        });
+    }
+
+    #[test]
+    fn agenda() {
+        snapshot("agenda", |app| {
+            app.agenda = Some(Agenda {
+                day: NaiveDate::from_ymd(2020, 3, 4),
+                selected: 0,
+            });
+        });
+    }
+
+    #[test]
+    fn present() {
+        snapshot("present", |app| {
+            app.on_key_press(Key::Alt('p'));
+            press(app, "   ");
+        });
+    }
+
+    #[test]
+    fn help_overlay() {
+        snapshot("help", |app| {
+            app.on_key_press(Key::Alt('?'));
+        });
+    }
+
+    #[test]
+    fn low_bandwidth_borders() {
+        let mut app = app();
+        app.low_bandwidth = true;
+        let screen = render(&mut app, 80, 24);
+        assert!(screen.starts_with("+Forget It---"), "{}", screen);
+        assert!(!screen.contains(|c| "─│┌┐└┘".contains(c)), "{}", screen);
+    }
+
+    #[test]
+    fn ansi_snapshot_keeps_colors() {
+        let mut app = app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        draw(&mut terminal, &mut app).unwrap();
+        let text = crate::ansi::render(terminal.backend().buffer());
+        let plain = render(&mut app, 80, 24);
+        assert_eq!(text.lines().count(), 24);
+        assert!(text.lines().all(|line| line.ends_with("\x1b[0m")));
+        assert!(text.contains("\x1b[0;"), "{:?}", text);
+        // without the escapes it's the screen
+        let mut stripped = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                stripped.push(c);
+            }
+        }
+        assert_eq!(stripped.trim_end(), plain);
+    }
+
+    #[test]
+    fn project_db_in_title() {
+        let mut app = app();
+        app.db_label = Some("forget".into());
+        let screen = render(&mut app, 80, 24);
+        assert!(screen.starts_with("┌Forget It ─ forget──"), "{}", screen);
+    }
+
+    #[test]
+    fn too_small() {
+        let mut app = app();
+        let screen = render(&mut app, MIN_WIDTH - 1, MIN_HEIGHT);
+        assert!(screen.contains("Terminal too small"), "{}", screen);
+        assert!(screen.contains("39x10, needs 40x10"), "{}", screen);
+        assert!(app.areas.tabs.is_empty());
+        for (width, height) in [(1, 1), (MIN_WIDTH, 1), (1, MIN_HEIGHT)].iter() {
+            render(&mut app, *width, *height);
+        }
+        assert!(!render(&mut app, MIN_WIDTH, MIN_HEIGHT).contains("too small"));
+    }
+
+    #[test]
+    fn remove_sticky_note_dialog() {
+        snapshot("remove_dialog", |app| {
+            app.on_key_press(Key::Ctrl(app.config.remove_sticky_note_char_ctrl));
+        });
     }
 }
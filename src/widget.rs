@@ -1,13 +1,25 @@
-use std::iter::{self, Iterator};
+use std::collections::HashSet;
 
+use chrono::Local;
 use unicode_width::UnicodeWidthStr;
 
 use tui::buffer::Buffer;
 use tui::layout::Rect;
-use tui::style::{Modifier, Style};
-use tui::widgets::{Block, List, Text, Widget};
+use tui::layout::{Constraint, Direction, Layout};
+use tui::style::{Color, Modifier, Style};
+use tui::widgets::{Block, Borders, List, Paragraph, Text, Widget};
 
-use super::app::Remind;
+use super::app::{Remind, Status};
+use super::due;
+use super::effort::Duration;
+use super::habit;
+use super::modal::Modal;
+use super::rules::ColorRules;
+
+/// Marks a todo carried over from an earlier day, with how many times.
+const CARRIED_SYMBOL: &str = "↻";
+/// Marks where a todo dragged with the mouse would be dropped.
+const DROP_SYMBOL: &str = "➜";
 
 pub struct TodoList<'b> {
     block: Option<Block<'b>>,
@@ -21,8 +33,26 @@ pub struct TodoList<'b> {
     highlight_style: Style,
     /// Symbol in front of the selected item (Shift all items to the right)
     highlight_symbol: Option<&'b str>,
-    /// Symbol in front of the selected item (Shift all items to the right)
+    /// Symbol after items with a command
     cmd_symbol: Option<&'b str>,
+    /// Style of items with a command
+    cmd_style: Style,
+    /// Style of countdown items
+    countdown_style: Style,
+    /// Days in the grid of a habit's todos
+    habit_days: usize,
+    /// Styles for items matching the user's color rules
+    rules: Option<&'b ColorRules>,
+    /// Ids of incomplete todos, items waiting on one of them are dimmed
+    open_ids: Option<&'b HashSet<u64>>,
+    /// Symbol in front of blocked items
+    blocked_symbol: Option<&'b str>,
+    /// Index a dragged item would be dropped at
+    drop_target: Option<usize>,
+    /// Style of done and cancelled items
+    completed: Style,
+    /// Style of open and waiting items
+    pending: Style,
 }
 
 impl<'b> TodoList<'b> {
@@ -35,6 +65,15 @@ impl<'b> TodoList<'b> {
             highlight_style: Default::default(),
             highlight_symbol: None,
             cmd_symbol: None,
+            cmd_style: Default::default(),
+            countdown_style: Default::default(),
+            habit_days: 0,
+            rules: None,
+            open_ids: None,
+            blocked_symbol: None,
+            drop_target: None,
+            completed: Style::default().modifier(Modifier::CROSSED_OUT),
+            pending: Style::default().modifier(Modifier::ITALIC),
         }
     }
     pub fn block(mut self, block: Block<'b>) -> TodoList<'b> {
@@ -57,6 +96,43 @@ impl<'b> TodoList<'b> {
         self
     }
 
+    pub fn cmd_style(mut self, cmd_style: Style) -> TodoList<'b> {
+        self.cmd_style = cmd_style;
+        self
+    }
+
+    pub fn countdown_style(mut self, countdown_style: Style) -> TodoList<'b> {
+        self.countdown_style = countdown_style;
+        self
+    }
+
+    pub fn habit_days(mut self, habit_days: usize) -> TodoList<'b> {
+        self.habit_days = habit_days;
+        self
+    }
+
+    pub fn rules(mut self, rules: &'b ColorRules) -> TodoList<'b> {
+        self.rules = Some(rules);
+        self
+    }
+
+    pub fn blocked(mut self, open_ids: &'b HashSet<u64>, symbol: &'b str) -> TodoList<'b> {
+        self.open_ids = Some(open_ids);
+        self.blocked_symbol = Some(symbol);
+        self
+    }
+
+    pub fn drop_target(mut self, drop_target: Option<usize>) -> TodoList<'b> {
+        self.drop_target = drop_target;
+        self
+    }
+
+    pub fn status_styles(mut self, completed: Style, pending: Style) -> TodoList<'b> {
+        self.completed = completed;
+        self.pending = pending;
+        self
+    }
+
     pub fn highlight_style(mut self, highlight_style: Style) -> TodoList<'b> {
         self.highlight_style = highlight_style;
         self
@@ -83,11 +159,14 @@ impl<'b> Widget for TodoList<'b> {
             None => (None, self.style),
         };
         let highlight_symbol = self.highlight_symbol.unwrap_or("");
-        let blank_symbol = iter::repeat(" ")
-            .take(highlight_symbol.width())
-            .collect::<String>();
+        let blank_symbol = " ".repeat(highlight_symbol.width());
+        let drop_symbol = format!("{:>width$}", DROP_SYMBOL, width = highlight_symbol.width());
+        let now = Local::now();
+        // done todos the note hides aren't rows
+        let rows = self.item.visible_rows();
         // Make sure the list show the selected item
         let offset = if let Some(selected) = selected {
+            let selected = rows.iter().position(|i| *i == selected).unwrap_or(0);
             if selected >= list_height {
                 selected - list_height + 1
             } else {
@@ -98,13 +177,12 @@ impl<'b> Widget for TodoList<'b> {
         };
 
         // Render items
-        let item = self
-            .item
-            .list
+        let item = rows
             .iter()
-            .enumerate()
+            .map(|i| (*i, &self.item.list[*i]))
             .map(|(i, todo)| {
-                let mut cmd_symbol = if !todo.cmd.is_empty() {
+                let has_cmd = self.item.command_for(todo).is_some();
+                let mut cmd_symbol = if has_cmd {
                     self.cmd_symbol.unwrap_or_default().to_string()
                 } else {
                     String::default()
@@ -113,40 +191,233 @@ impl<'b> Widget for TodoList<'b> {
                     cmd_symbol = format!(" {}", cmd_symbol);
                 }
 
-                let strike = if todo.completed {
-                    Modifier::CROSSED_OUT
-                } else {
-                    Modifier::ITALIC
+                let blocker = todo
+                    .blocked_by
+                    .filter(|id| self.open_ids.is_some_and(|open| open.contains(id)));
+                let mut task = match blocker {
+                    Some(id) => format!(
+                        "{}{} {}",
+                        self.blocked_symbol.unwrap_or_default(),
+                        id,
+                        todo.as_str()
+                    ),
+                    None => todo.as_str().to_string(),
+                };
+                let tracked = todo.tracked_secs();
+                match todo.estimate {
+                    Some(estimate) if tracked >= 60 => {
+                        task = format!("{} ({} of {})", task, Duration(tracked), estimate)
+                    }
+                    Some(estimate) => task = format!("{} (~{})", task, estimate),
+                    None if tracked >= 60 => task = format!("{} ({})", task, Duration(tracked)),
+                    None => {}
+                }
+                if todo.carried > 0 {
+                    task = format!("{} {}{}", task, CARRIED_SYMBOL, todo.carried);
+                }
+                if self.item.goal && todo.weight > 1 {
+                    task = format!("{} ×{}", task, todo.weight);
+                }
+                if self.item.habit {
+                    let today = now.naive_local().date();
+                    let (current, best) = habit::streaks(todo, today);
+                    task = format!(
+                        "{} {} streak {}, best {}",
+                        task,
+                        habit::grid(todo, today, self.habit_days),
+                        current,
+                        best
+                    );
+                }
+                let countdown = todo.due.filter(|_| todo.countdown);
+                if let Some(due) = countdown {
+                    task = format!("{} — {}", task, due::countdown(due, now));
+                }
+
+                let status = match todo.status {
+                    _ if blocker.is_some() => Style::default().modifier(Modifier::DIM),
+                    Status::Open => self.pending,
+                    Status::InProgress => Style::default().modifier(Modifier::BOLD),
+                    Status::Waiting => dimmed(self.pending),
+                    Status::Done => self.completed,
+                    Status::Cancelled => dimmed(self.completed),
                 };
+                let strike = status.modifier;
 
-                if let Some(s) = selected {
-                    if i == s {
+                match selected {
+                    Some(s) if i == s => {
                         let style = Style::default()
                             .bg(highlight_style.bg)
                             .fg(highlight_style.fg)
                             .modifier(strike);
                         Text::styled(
-                            format!("{} {}{}", highlight_symbol, todo.as_str(), cmd_symbol),
-                            style,
-                        )
-                    } else {
-                        let style = Style::default()
-                            .bg(self.style.bg)
-                            .fg(self.style.fg)
-                            .modifier(strike);
-                        Text::styled(
-                            format!("{} {}{}", blank_symbol, todo.as_str(), cmd_symbol),
+                            format!("{} {}{}", highlight_symbol, task, cmd_symbol),
                             style,
                         )
                     }
-                } else {
-                    Text::styled(todo.as_str().to_string(), self.style)
+                    _ => {
+                        let list = if countdown.is_some() {
+                            Style::default()
+                                .bg(or_list(self.countdown_style.bg, self.style.bg))
+                                .fg(or_list(self.countdown_style.fg, self.style.fg))
+                                .modifier(self.countdown_style.modifier)
+                        } else if has_cmd {
+                            Style::default()
+                                .bg(or_list(self.cmd_style.bg, self.style.bg))
+                                .fg(or_list(self.cmd_style.fg, self.style.fg))
+                                .modifier(self.cmd_style.modifier)
+                        } else {
+                            Style::default().bg(self.style.bg).fg(self.style.fg)
+                        };
+                        let style = match self.rules.and_then(|r| r.style_for(todo, has_cmd)) {
+                            Some(rule) => Style::default()
+                                .bg(rule.bg)
+                                .fg(rule.fg)
+                                .modifier(rule.modifier | strike),
+                            None => Style::default()
+                                .bg(or_list(status.bg, list.bg))
+                                .fg(or_list(status.fg, list.fg))
+                                .modifier(list.modifier | strike),
+                        };
+                        // unselected lists aren't shifted for the highlight symbol
+                        let symbol = if self.drop_target == Some(i) {
+                            &drop_symbol
+                        } else if selected.is_some() {
+                            &blank_symbol
+                        } else {
+                            ""
+                        };
+                        Text::styled(format!("{} {}{}", symbol, task, cmd_symbol), style)
+                    }
                 }
             })
-            .skip(offset as usize);
+            .skip(offset);
         List::new(item)
             .block(self.block.unwrap_or_default())
             .style(self.style)
             .draw(area, buf);
     }
 }
+
+fn dimmed(style: Style) -> Style {
+    style.modifier(style.modifier | Modifier::DIM)
+}
+
+/// A status or command color of `Reset` keeps the color of the list.
+fn or_list(status: Color, list: Color) -> Color {
+    if status == Color::Reset {
+        list
+    } else {
+        status
+    }
+}
+
+/// Blanks its area so a popup drawn over other widgets doesn't show them through.
+pub struct Clear;
+
+impl Widget for Clear {
+    fn draw(&mut self, area: Rect, buf: &mut Buffer) {
+        for x in area.left()..area.right() {
+            for y in area.top()..area.bottom() {
+                buf.get_mut(x, y).reset();
+            }
+        }
+    }
+}
+
+/// Swaps the box drawing borders under it for `+`, `-` and `|`, one byte a
+/// cell instead of three for the low bandwidth mode.
+pub struct AsciiBorders;
+
+impl Widget for AsciiBorders {
+    fn draw(&mut self, area: Rect, buf: &mut Buffer) {
+        for x in area.left()..area.right() {
+            for y in area.top()..area.bottom() {
+                let cell = buf.get_mut(x, y);
+                let ascii = match cell.symbol.as_str() {
+                    "─" => "-",
+                    "│" => "|",
+                    "┌" | "┐" | "└" | "┘" | "├" | "┤" | "┬" | "┴" | "┼" => "+",
+                    _ => continue,
+                };
+                cell.set_symbol(ascii);
+            }
+        }
+    }
+}
+
+/// Draws a `Modal` over whatever is under it.
+pub struct Dialog<'b> {
+    modal: &'b Modal,
+    style: Style,
+    highlight_style: Style,
+}
+
+impl<'b> Dialog<'b> {
+    pub fn new(modal: &'b Modal) -> Dialog<'b> {
+        Dialog {
+            modal,
+            style: Default::default(),
+            highlight_style: Default::default(),
+        }
+    }
+
+    pub fn style(mut self, style: Style) -> Dialog<'b> {
+        self.style = style;
+        self
+    }
+
+    pub fn highlight_style(mut self, highlight_style: Style) -> Dialog<'b> {
+        self.highlight_style = highlight_style;
+        self
+    }
+}
+
+impl<'b> Widget for Dialog<'b> {
+    fn draw(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.draw(area, buf);
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(&self.modal.title)
+            .title_style(self.highlight_style)
+            .border_style(self.style);
+        block.draw(area, buf);
+        let inner = block.inner(area);
+
+        let message_height = if self.modal.message.is_empty() { 0 } else { 2 };
+        let chunks = Layout::default()
+            .constraints([Constraint::Length(message_height), Constraint::Min(0)].as_ref())
+            .direction(Direction::Vertical)
+            .split(inner);
+
+        if message_height > 0 {
+            Paragraph::new([Text::styled(self.modal.message.as_str(), self.style)].iter())
+                .wrap(true)
+                .draw(chunks[0], buf);
+        }
+
+        if let Some(input) = &self.modal.input {
+            Paragraph::new([Text::styled(format!("> {}", input), self.highlight_style)].iter())
+                .draw(chunks[1], buf);
+            return;
+        }
+
+        // keep the selected choice in view
+        let height = chunks[1].height as usize;
+        let offset = (self.modal.selected + 1).saturating_sub(height);
+        let choices = self
+            .modal
+            .choices
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .map(|(i, choice)| {
+                if i == self.modal.selected {
+                    Text::styled(format!("> {}", choice), self.highlight_style)
+                } else {
+                    Text::styled(format!("  {}", choice), self.style)
+                }
+            });
+        List::new(choices).draw(chunks[1], buf);
+    }
+}
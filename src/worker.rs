@@ -0,0 +1,241 @@
+//! Work that would hold up drawing runs here on a thread of its own, fetching
+//! remote sticky notes and page titles, publishing and posting to chat. Tasks
+//! report how far along they are and what they made as `Event::Task`s into
+//! the main loop, where `App::on_task` hands the output to whatever started
+//! them. Built with `--features tokio` the tasks run on a tokio runtime,
+//! blocking ones on its pool of threads, and saving runs here too with its
+//! async files. The running tasks show as a spinner in the tab bar.
+
+use std::collections::BTreeMap;
+use std::fmt;
+#[cfg(feature = "tokio")]
+use std::future::Future;
+use std::io;
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+#[cfg(feature = "tokio")]
+use std::sync::OnceLock;
+#[cfg(not(feature = "tokio"))]
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tokio")]
+use tokio::runtime::{Builder, Runtime};
+
+use crate::event::TaskSender;
+use crate::feed::FeedFetched;
+#[cfg(feature = "links")]
+use crate::links::Titled;
+use crate::remote::Fetched;
+#[cfg(feature = "sync")]
+use crate::sync::Synced;
+
+/// The frames of the spinner, one every `FRAME`.
+const FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+pub const FRAME: Duration = Duration::from_millis(100);
+
+/// What a finished task made.
+pub enum Output {
+    /// The body of a remote sticky note.
+    Fetched(Fetched),
+    /// The entries of a feed.
+    FeedFetched(FeedFetched),
+    /// The link of a published sticky note.
+    Published(io::Result<String>),
+    /// The page title of a link todo.
+    #[cfg(feature = "links")]
+    Titled(Titled),
+    /// The chat messages that couldn't be sent.
+    #[cfg(feature = "chat")]
+    Posted(Vec<io::Error>),
+    /// The GitHub issue a done todo closed, or why it couldn't.
+    #[cfg(feature = "github")]
+    IssueClosed(io::Result<String>),
+    /// Whether the sticky notes were written to disk.
+    #[cfg(feature = "tokio")]
+    Saved(io::Result<()>),
+    /// What syncing each shared sticky note got back.
+    #[cfg(feature = "sync")]
+    Synced(Vec<Synced>),
+}
+
+pub enum TaskEvent {
+    /// The task with this id is this many percent done.
+    Progress(u64, u8),
+    Done(u64, Output),
+}
+
+/// Handed to a task so it can tell how far along it is.
+pub struct Progress {
+    id: u64,
+    tx: TaskSender,
+}
+
+impl Progress {
+    pub fn report(&self, percent: u8) {
+        self.tx.send(TaskEvent::Progress(self.id, percent.min(100)));
+    }
+}
+
+type Work = Box<dyn FnOnce(&Progress) -> Output + Send>;
+
+enum Task {
+    Blocking(Work),
+    #[cfg(feature = "tokio")]
+    Async(Pin<Box<dyn Future<Output = Output> + Send>>),
+}
+
+/// A task that hasn't finished.
+#[derive(Clone, Debug)]
+pub struct Running {
+    pub label: String,
+    /// Only known for tasks that report progress.
+    pub percent: Option<u8>,
+}
+
+#[derive(Default)]
+pub struct Workers {
+    tx: Option<TaskSender>,
+    /// Tasks started before the main loop was there to hear from them.
+    waiting: Vec<(u64, Task)>,
+    running: BTreeMap<u64, Running>,
+    next_id: u64,
+}
+
+impl fmt::Debug for Workers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Workers")
+            .field("running", &self.running)
+            .field("waiting", &self.waiting.len())
+            .finish()
+    }
+}
+
+impl Workers {
+    /// Starts the tasks waiting on the main loop.
+    pub fn connect(&mut self, tx: TaskSender) {
+        self.tx = Some(tx.clone());
+        for (id, task) in self.waiting.drain(..) {
+            run(id, task, tx.clone());
+        }
+    }
+
+    /// Runs `work` on its own thread, its output comes back through `on_event`.
+    pub fn spawn<S, F>(&mut self, label: S, work: F) -> u64
+    where
+        S: Into<String>,
+        F: FnOnce(&Progress) -> Output + Send + 'static,
+    {
+        self.start(label.into(), Task::Blocking(Box::new(work)))
+    }
+
+    /// Runs `work` on the tokio runtime, its output comes back through `on_event`.
+    #[cfg(feature = "tokio")]
+    pub fn spawn_async<S, F>(&mut self, label: S, work: F) -> u64
+    where
+        S: Into<String>,
+        F: Future<Output = Output> + Send + 'static,
+    {
+        self.start(label.into(), Task::Async(Box::pin(work)))
+    }
+
+    fn start(&mut self, label: String, task: Task) -> u64 {
+        self.next_id += 1;
+        let id = self.next_id;
+        tracing::debug!("task {} started: {}", id, label);
+        self.running.insert(
+            id,
+            Running {
+                label,
+                percent: None,
+            },
+        );
+        match &self.tx {
+            Some(tx) => run(id, task, tx.clone()),
+            None => self.waiting.push((id, task)),
+        }
+        id
+    }
+
+    /// Keeps track of a task's event, the output once it's done.
+    pub fn on_event(&mut self, event: TaskEvent) -> Option<Output> {
+        match event {
+            TaskEvent::Progress(id, percent) => {
+                if let Some(task) = self.running.get_mut(&id) {
+                    task.percent = Some(percent);
+                }
+                None
+            }
+            TaskEvent::Done(id, output) => {
+                if let Some(task) = self.running.remove(&id) {
+                    tracing::debug!("task {} finished: {}", id, task.label);
+                }
+                Some(output)
+            }
+        }
+    }
+
+    pub fn is_running(&self, id: u64) -> bool {
+        self.running.contains_key(&id)
+    }
+
+    /// True while any task runs.
+    pub fn is_busy(&self) -> bool {
+        !self.running.is_empty()
+    }
+
+    /// Like `⠹ Publishing Daily 40% +2`, the oldest task with how many more
+    /// run, `frame` is the spinner's `Anim::Spinner` frame.
+    pub fn spinner(&self, frame: usize) -> Option<String> {
+        let (_, task) = self.running.iter().next()?;
+        let frame = FRAMES[frame % FRAMES.len()];
+        let mut text = format!("{} {}", frame, task.label);
+        if let Some(percent) = task.percent {
+            text = format!("{} {}%", text, percent);
+        }
+        if self.running.len() > 1 {
+            text = format!("{} +{}", text, self.running.len() - 1);
+        }
+        Some(text)
+    }
+}
+
+fn run(id: u64, task: Task, tx: TaskSender) {
+    match task {
+        Task::Blocking(work) => spawn_blocking(move || {
+            let progress = Progress { id, tx: tx.clone() };
+            let output = work(&progress);
+            tx.send(TaskEvent::Done(id, output));
+        }),
+        #[cfg(feature = "tokio")]
+        Task::Async(work) => {
+            runtime().spawn(async move {
+                let output = work.await;
+                tx.send(TaskEvent::Done(id, output));
+            });
+        }
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+fn spawn_blocking<F: FnOnce() + Send + 'static>(f: F) {
+    thread::spawn(f);
+}
+
+#[cfg(feature = "tokio")]
+fn spawn_blocking<F: FnOnce() + Send + 'static>(f: F) {
+    runtime().spawn_blocking(f);
+}
+
+/// Started by the first task, it lives until `forget` quits.
+#[cfg(feature = "tokio")]
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("forget-worker")
+            .build()
+            .expect("can't start the tokio runtime")
+    })
+}